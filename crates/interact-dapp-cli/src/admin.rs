@@ -0,0 +1,49 @@
+use anyhow::Result;
+use clap::Subcommand;
+use interact_dapp::states::Config;
+use interact_dapp_client::get_config_address;
+use solana_sdk::signature::Signer;
+use crate::context::CliContext;
+
+#[derive(Debug, Subcommand)]
+pub enum ConfigCommand {
+    /// Show the program config
+    Show,
+    /// Pause the program, or resume it with `--resume`
+    Pause {
+        #[arg(long)]
+        resume: bool,
+    },
+    /// Set the protocol fee
+    SetFee {
+        /// Fee in bps
+        #[arg(long)]
+        bps: u16,
+    },
+}
+
+pub fn process(ctx: &CliContext, command: ConfigCommand) -> Result<()> {
+    match command {
+        ConfigCommand::Show => {
+            let address = get_config_address();
+            let config: Config = ctx.get_anchor_account(&address)?;
+            println!("Config:  {address}");
+            println!("Admin:   {}", config.admin);
+            println!("Paused:  {}", config.paused);
+            println!("Fee:     {} bps", config.fee_bps);
+            Ok(())
+        }
+        ConfigCommand::Pause { resume } => {
+            let admin = ctx.config.admin()?;
+            println!("{} the program", if resume { "Resuming" } else { "Pausing" });
+            let ix = interact_dapp_client::update_config(&admin.pubkey(), Some(!resume), None);
+            ctx.process(&[ix], &[&admin])
+        }
+        ConfigCommand::SetFee { bps } => {
+            let admin = ctx.config.admin()?;
+            println!("Setting the protocol fee to {bps} bps");
+            let ix = interact_dapp_client::update_config(&admin.pubkey(), None, Some(bps));
+            ctx.process(&[ix], &[&admin])
+        }
+    }
+}
@@ -0,0 +1,109 @@
+use std::path::{Path, PathBuf};
+use anyhow::{anyhow, Context, Result};
+use interact_dapp_client::{JUPITER_LENDING_PROGRAM_ID, JUPITER_LIQUIDITY_PROGRAM_ID};
+use serde::Deserialize;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{read_keypair_file, Keypair};
+
+/// Settings read from the CLI config file, by default `~/.config/interact-dapp/cli.toml`:
+///
+/// ```toml
+/// cluster = "devnet"                      # mainnet, devnet, localnet or an RPC URL
+/// keypair = "~/.config/solana/id.json"    # fee payer and signer
+/// admin_keypair = "~/.config/solana/admin.json"  # optional, signs `config` commands
+/// commitment = "confirmed"
+/// lending_program = "..."                 # optional Jupiter program overrides
+/// liquidity_program = "..."
+/// ```
+#[derive(Debug, Deserialize)]
+pub struct CliConfig {
+    pub cluster: String,
+    pub keypair: String,
+    pub admin_keypair: Option<String>,
+    #[serde(default = "default_commitment")]
+    pub commitment: String,
+    pub lending_program: Option<String>,
+    pub liquidity_program: Option<String>,
+}
+
+fn default_commitment() -> String {
+    "confirmed".to_string()
+}
+
+impl CliConfig {
+    pub fn default_path() -> Result<PathBuf> {
+        let home = std::env::var("HOME").context("HOME is not set")?;
+        Ok(Path::new(&home).join(".config/interact-dapp/cli.toml"))
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("cannot read config file {}", path.display()))?;
+        toml::from_str(&content).with_context(|| format!("invalid config file {}", path.display()))
+    }
+
+    pub fn rpc_url(&self) -> String {
+        match self.cluster.as_str() {
+            "mainnet" | "mainnet-beta" => "https://api.mainnet-beta.solana.com".to_string(),
+            "devnet" => "https://api.devnet.solana.com".to_string(),
+            "localnet" | "localhost" => "http://127.0.0.1:8899".to_string(),
+            url => url.to_string(),
+        }
+    }
+
+    /// Cluster named by `cluster`, as in `interact_dapp::programs::CLUSTER`. `None` for an
+    /// RPC URL.
+    pub fn cluster_name(&self) -> Option<&'static str> {
+        match self.cluster.as_str() {
+            "mainnet" | "mainnet-beta" => Some("mainnet"),
+            "devnet" => Some("devnet"),
+            "localnet" | "localhost" => Some("localnet"),
+            _ => None,
+        }
+    }
+
+    pub fn commitment(&self) -> Result<CommitmentConfig> {
+        self.commitment
+            .parse()
+            .map_err(|_| anyhow!("invalid commitment {}", self.commitment))
+    }
+
+    pub fn payer(&self) -> Result<Keypair> {
+        read_keypair(&self.keypair)
+    }
+
+    /// Admin signer of the `config` commands, the payer unless `admin_keypair` is set.
+    pub fn admin(&self) -> Result<Keypair> {
+        read_keypair(self.admin_keypair.as_deref().unwrap_or(&self.keypair))
+    }
+
+    pub fn lending_program(&self) -> Result<Pubkey> {
+        parse_program(self.lending_program.as_deref(), JUPITER_LENDING_PROGRAM_ID)
+    }
+
+    pub fn liquidity_program(&self) -> Result<Pubkey> {
+        parse_program(self.liquidity_program.as_deref(), JUPITER_LIQUIDITY_PROGRAM_ID)
+    }
+}
+
+fn parse_program(value: Option<&str>, default: Pubkey) -> Result<Pubkey> {
+    match value {
+        Some(value) => value
+            .parse()
+            .map_err(|_| anyhow!("invalid program id {value}")),
+        None => Ok(default),
+    }
+}
+
+fn expand_home(path: &str) -> PathBuf {
+    match (path.strip_prefix("~/"), std::env::var("HOME")) {
+        (Some(rest), Ok(home)) => Path::new(&home).join(rest),
+        _ => PathBuf::from(path),
+    }
+}
+
+fn read_keypair(path: &str) -> Result<Keypair> {
+    let path = expand_home(path);
+    read_keypair_file(&path).map_err(|e| anyhow!("cannot read keypair {}: {e}", path.display()))
+}
@@ -0,0 +1,153 @@
+use anyhow::{anyhow, bail, Context, Result};
+use anchor_lang::AccountDeserialize;
+use anchor_spl::token_interface::{Mint, TokenAccount};
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::account::Account;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signer};
+use solana_sdk::transaction::Transaction;
+use crate::config::CliConfig;
+
+const MAINNET_GENESIS_HASH: &str = "5eykt4UsFv8P8NJdTREpY1vzqKqZKvdpKuc147dw2N9d";
+const DEVNET_GENESIS_HASH: &str = "EtWTRABZaYq6iMfeYKouRu166VU2xqa1wcaWoxPkrZBG";
+
+/// Cluster behind an RPC URL, from its genesis hash. Any other cluster is taken as a local
+/// validator.
+fn cluster_of(rpc: &RpcClient) -> Result<&'static str> {
+    let genesis_hash = rpc
+        .get_genesis_hash()
+        .with_context(|| format!("cannot fetch the genesis hash of {}", rpc.url()))?;
+    Ok(match genesis_hash.to_string().as_str() {
+        MAINNET_GENESIS_HASH => "mainnet",
+        DEVNET_GENESIS_HASH => "devnet",
+        _ => "localnet",
+    })
+}
+
+/// Builds the CLI with the program ids of `cluster`.
+fn build_command(cluster: &str) -> &'static str {
+    match cluster {
+        "mainnet" => "cargo build -p interact-dapp-cli --no-default-features --features mainnet",
+        "localnet" => "cargo build -p interact-dapp-cli --features interact-dapp/localnet",
+        _ => "cargo build -p interact-dapp-cli",
+    }
+}
+
+pub struct CliContext {
+    pub config: CliConfig,
+    pub rpc: RpcClient,
+    pub payer: Keypair,
+    pub dry_run: bool,
+}
+
+impl CliContext {
+    pub fn new(config: CliConfig, dry_run: bool) -> Result<Self> {
+        let rpc = RpcClient::new_with_commitment(config.rpc_url(), config.commitment()?);
+        let cluster = match config.cluster_name() {
+            Some(cluster) => cluster,
+            None => cluster_of(&rpc)?,
+        };
+        // The program ids are compiled in by the cluster feature, which `cargo build
+        // --workspace` turns to the `localnet` of the test crate
+        let built_for = interact_dapp::programs::CLUSTER;
+        if cluster != built_for {
+            bail!(
+                "built with the {built_for} program ids, {} is on {cluster}: rebuild with `{}`",
+                config.cluster,
+                build_command(cluster)
+            );
+        }
+        let payer = config.payer()?;
+        Ok(Self {
+            config,
+            rpc,
+            payer,
+            dry_run,
+        })
+    }
+
+    pub fn get_account(&self, address: &Pubkey) -> Result<Account> {
+        self.rpc
+            .get_account(address)
+            .with_context(|| format!("cannot fetch account {address}"))
+    }
+
+    /// Fetches an account and deserializes it, checking its discriminator.
+    pub fn get_anchor_account<T: AccountDeserialize>(&self, address: &Pubkey) -> Result<T> {
+        let account = self.get_account(address)?;
+        T::try_deserialize(&mut &account.data[..])
+            .map_err(|e| anyhow!("cannot deserialize account {address}: {e}"))
+    }
+
+    pub fn get_mint(&self, address: &Pubkey) -> Result<(Mint, Pubkey)> {
+        let account = self.get_account(address)?;
+        let mint = Mint::try_deserialize(&mut &account.data[..])
+            .map_err(|e| anyhow!("{address} is not a mint: {e}"))?;
+        Ok((mint, account.owner))
+    }
+
+    /// Balance of a token account, 0 when it does not exist yet.
+    pub fn get_token_balance(&self, address: &Pubkey) -> Result<u64> {
+        let Some(account) = self
+            .rpc
+            .get_account_with_commitment(address, self.rpc.commitment())?
+            .value
+        else {
+            return Ok(0);
+        };
+        let token_account = TokenAccount::try_deserialize(&mut &account.data[..])
+            .map_err(|e| anyhow!("{address} is not a token account: {e}"))?;
+        Ok(token_account.amount)
+    }
+
+    /// Signs `instructions` with the payer and `signers`, then sends them, or only simulates
+    /// them with `--dry-run`.
+    pub fn process(&self, instructions: &[Instruction], signers: &[&Keypair]) -> Result<()> {
+        let mut all_signers: Vec<&Keypair> = vec![&self.payer];
+        for signer in signers {
+            if all_signers.iter().all(|s| s.pubkey() != signer.pubkey()) {
+                all_signers.push(signer);
+            }
+        }
+        let blockhash = self.rpc.get_latest_blockhash()?;
+        let transaction = Transaction::new_signed_with_payer(
+            instructions,
+            Some(&self.payer.pubkey()),
+            &all_signers,
+            blockhash,
+        );
+
+        if self.dry_run {
+            let result = self.rpc.simulate_transaction(&transaction)?.value;
+            for log in result.logs.unwrap_or_default() {
+                println!("  {log}");
+            }
+            if let Some(units) = result.units_consumed {
+                println!("Compute units: {units}");
+            }
+            if let Some(err) = result.err {
+                bail!("simulation failed: {err}");
+            }
+            println!("Simulation succeeded");
+        } else {
+            let signature = self.rpc.send_and_confirm_transaction(&transaction)?;
+            println!("Signature: {signature}");
+        }
+        Ok(())
+    }
+}
+
+/// Formats a raw token amount with the mint decimals.
+pub fn ui_amount(amount: u64, decimals: u8) -> String {
+    if decimals == 0 {
+        return amount.to_string();
+    }
+    let unit = 10u64.pow(decimals as u32);
+    format!(
+        "{}.{:0width$}",
+        amount / unit,
+        amount % unit,
+        width = decimals as usize
+    )
+}
@@ -0,0 +1,95 @@
+use anyhow::{anyhow, Result};
+use clap::Subcommand;
+use interact_dapp::math::convert_to_assets;
+use interact_dapp::states::Lending;
+use interact_dapp_client::{
+    with_compute_budget, EarnMarket, DEPOSIT_EARN_COMPUTE_UNITS, WITHDRAW_EARN_COMPUTE_UNITS,
+};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signer;
+use crate::context::{ui_amount, CliContext};
+
+#[derive(Debug, Subcommand)]
+pub enum EarnCommand {
+    /// Deposit the underlying of a Jupiter Earn market
+    Deposit {
+        /// Underlying mint of the market
+        #[arg(long)]
+        mint: Pubkey,
+        /// Amount in base units
+        #[arg(long)]
+        amount: u64,
+    },
+    /// Withdraw the underlying of a Jupiter Earn market
+    Withdraw {
+        #[arg(long)]
+        mint: Pubkey,
+        /// Amount of underlying in base units
+        #[arg(long)]
+        amount: u64,
+        /// Withdraw what the liquidity layer allows, up to `amount`, instead of failing
+        #[arg(long)]
+        max_available: bool,
+    },
+    /// Show the fToken position of an owner in a Jupiter Earn market
+    Position {
+        #[arg(long)]
+        mint: Pubkey,
+        /// Owner of the position, the configured keypair by default
+        #[arg(long)]
+        owner: Option<Pubkey>,
+    },
+}
+
+fn load_market(ctx: &CliContext, mint: Pubkey) -> Result<(EarnMarket, u8)> {
+    let (mint_account, token_program) = ctx.get_mint(&mint)?;
+    let market = EarnMarket {
+        lending_program: ctx.config.lending_program()?,
+        liquidity_program: ctx.config.liquidity_program()?,
+        mint,
+        token_program,
+    };
+    Ok((market, mint_account.decimals))
+}
+
+pub fn process(ctx: &CliContext, command: EarnCommand) -> Result<()> {
+    match command {
+        EarnCommand::Deposit { mint, amount } => {
+            let (market, decimals) = load_market(ctx, mint)?;
+            println!("Depositing {} of {mint}", ui_amount(amount, decimals));
+            let ix = interact_dapp_client::deposit_earn(&market, &ctx.payer.pubkey(), amount);
+            ctx.process(&with_compute_budget(&[ix], DEPOSIT_EARN_COMPUTE_UNITS, None), &[])
+        }
+        EarnCommand::Withdraw {
+            mint,
+            amount,
+            max_available,
+        } => {
+            let (market, decimals) = load_market(ctx, mint)?;
+            println!("Withdrawing {} of {mint}", ui_amount(amount, decimals));
+            let signer = ctx.payer.pubkey();
+            let ix = if max_available {
+                interact_dapp_client::withdraw_earn_max_available(&market, &signer, amount)
+            } else {
+                interact_dapp_client::withdraw_earn(&market, &signer, amount)
+            };
+            ctx.process(&with_compute_budget(&[ix], WITHDRAW_EARN_COMPUTE_UNITS, None), &[])
+        }
+        EarnCommand::Position { mint, owner } => {
+            let (market, decimals) = load_market(ctx, mint)?;
+            let owner = owner.unwrap_or_else(|| ctx.payer.pubkey());
+            let lending: Lending = ctx.get_anchor_account(&market.lending())?;
+            let shares = ctx.get_token_balance(&market.f_token_account(&owner))?;
+            let assets = convert_to_assets(shares, lending.token_exchange_price)
+                .ok_or_else(|| anyhow!("math overflow"))?;
+
+            println!("Owner:          {owner}");
+            println!("Lending:        {}", market.lending());
+            println!("fToken mint:    {}", market.f_token_mint());
+            println!("Shares:         {}", ui_amount(shares, decimals));
+            println!("Value:          {}", ui_amount(assets, decimals));
+            println!("Exchange price: {}", lending.token_exchange_price);
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,60 @@
+mod admin;
+mod config;
+mod context;
+mod earn;
+mod snapshot;
+mod swap;
+
+use std::path::PathBuf;
+use anyhow::Result;
+use clap::{Parser, Subcommand};
+use crate::admin::ConfigCommand;
+use crate::config::CliConfig;
+use crate::context::CliContext;
+use crate::earn::EarnCommand;
+use crate::snapshot::SnapshotArgs;
+use crate::swap::SwapCommand;
+
+/// Operate the interact_dapp program.
+#[derive(Debug, Parser)]
+#[command(name = "interact-dapp-cli", version)]
+struct Cli {
+    /// CLI config file, `~/.config/interact-dapp/cli.toml` by default
+    #[arg(long, global = true)]
+    config: Option<PathBuf>,
+    /// Simulate the transaction and print its logs instead of sending it
+    #[arg(long, global = true)]
+    dry_run: bool,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Jupiter Earn deposits and withdrawals
+    #[command(subcommand)]
+    Earn(EarnCommand),
+    /// Raydium CLMM swaps
+    #[command(subcommand)]
+    Swap(SwapCommand),
+    /// Program config, admin only for updates
+    #[command(subcommand)]
+    Config(ConfigCommand),
+    Snapshot(SnapshotArgs),
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    let path = match cli.config {
+        Some(path) => path,
+        None => CliConfig::default_path()?,
+    };
+    let ctx = CliContext::new(CliConfig::load(&path)?, cli.dry_run)?;
+
+    match cli.command {
+        Command::Earn(command) => earn::process(&ctx, command),
+        Command::Swap(command) => swap::process(&ctx, command),
+        Command::Config(command) => admin::process(&ctx, command),
+        Command::Snapshot(args) => snapshot::process(&ctx, args),
+    }
+}
@@ -0,0 +1,112 @@
+use std::path::PathBuf;
+use anyhow::{anyhow, Result};
+use clap::Args;
+use interact_dapp_client::{
+    get_earn_snapshot_addresses, get_pool_snapshot_addresses, AccountFixture, EarnMarket,
+    Snapshot,
+};
+use solana_sdk::bpf_loader_upgradeable;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::sysvar;
+use crate::context::CliContext;
+use crate::swap::load_pool;
+
+/// Accounts per `getMultipleAccounts` request
+const MAX_MULTIPLE_ACCOUNTS: usize = 100;
+
+/// Dump accounts into a JSON fixture for offline replay tests
+#[derive(Debug, Args)]
+pub struct SnapshotArgs {
+    /// Fixture file to write
+    #[arg(long)]
+    out: PathBuf,
+    /// Raydium CLMM pools, dumped with their config, vaults, mints and tick arrays
+    #[arg(long)]
+    pool: Vec<Pubkey>,
+    /// Tick arrays dumped on each side of the current tick of a pool
+    #[arg(long, default_value_t = 3)]
+    tick_arrays: i32,
+    /// Underlying mints of Jupiter Earn markets, dumped with their lending and liquidity
+    /// accounts
+    #[arg(long)]
+    earn_mint: Vec<Pubkey>,
+    /// Upgradeable programs, dumped with their program data
+    #[arg(long)]
+    program: Vec<Pubkey>,
+    /// Any other account
+    accounts: Vec<Pubkey>,
+}
+
+pub fn process(ctx: &CliContext, args: SnapshotArgs) -> Result<()> {
+    let mut addresses = args.accounts;
+    for address in &args.pool {
+        let info = load_pool(ctx, address)?;
+        addresses.extend(get_pool_snapshot_addresses(
+            &info.pool,
+            &info.state,
+            args.tick_arrays,
+        ));
+    }
+    for mint in &args.earn_mint {
+        let market = EarnMarket {
+            lending_program: ctx.config.lending_program()?,
+            liquidity_program: ctx.config.liquidity_program()?,
+            mint: *mint,
+            token_program: ctx.get_mint(mint)?.1,
+        };
+        addresses.extend(get_earn_snapshot_addresses(&market));
+    }
+    for program in &args.program {
+        addresses.push(*program);
+        addresses.push(
+            Pubkey::find_program_address(&[program.as_ref()], &bpf_loader_upgradeable::ID).0,
+        );
+    }
+    let mut seen = std::collections::HashSet::new();
+    addresses.retain(|address| seen.insert(*address));
+
+    // The clock is read in the same request as the first accounts, so both are at one slot
+    let mut requests = vec![sysvar::clock::ID];
+    requests.extend(addresses);
+    let mut accounts = Vec::with_capacity(requests.len());
+    let mut clock = None;
+    for chunk in requests.chunks(MAX_MULTIPLE_ACCOUNTS) {
+        for (address, account) in chunk.iter().zip(ctx.rpc.get_multiple_accounts(chunk)?) {
+            let Some(account) = account else {
+                println!("Skipping {address}, account not found");
+                continue;
+            };
+            if *address == sysvar::clock::ID {
+                clock = Some(account.data);
+                continue;
+            }
+            accounts.push(AccountFixture {
+                address: *address,
+                owner: account.owner,
+                lamports: account.lamports,
+                executable: account.executable,
+                rent_epoch: account.rent_epoch,
+                data: account.data,
+            });
+        }
+    }
+
+    // Clock layout: slot, epoch_start_timestamp, epoch, leader_schedule_epoch, unix_timestamp
+    let clock = clock.ok_or_else(|| anyhow!("cannot read the clock sysvar"))?;
+    let read_u64 =
+        |offset: usize| u64::from_le_bytes(clock[offset..offset + 8].try_into().unwrap());
+    let snapshot = Snapshot {
+        cluster: ctx.config.cluster.clone(),
+        slot: read_u64(0),
+        unix_timestamp: read_u64(32) as i64,
+        accounts,
+    };
+    snapshot.write(&args.out)?;
+    println!(
+        "Wrote {} accounts at slot {} to {}",
+        snapshot.accounts.len(),
+        snapshot.slot,
+        args.out.display()
+    );
+    Ok(())
+}
@@ -0,0 +1,202 @@
+use anyhow::{anyhow, bail, Result};
+use clap::Subcommand;
+use interact_dapp::math::apply_slippage;
+use interact_dapp_client::{
+    load_zero_copy, proxy_swap_compute_units, quote_swap, with_compute_budget, ClmmPool,
+    QuoteParams, SwapArgs, SwapQuote,
+};
+use raydium_amm_v3::states::{AmmConfig, PoolState, TickArrayBitmapExtension, TickArrayState};
+use solana_sdk::account::from_account;
+use solana_sdk::clock::Clock;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signer;
+use solana_sdk::sysvar;
+use crate::context::{ui_amount, CliContext};
+
+/// Tick arrays fetched in the swap direction for quoting
+const SWAP_TICK_ARRAYS: usize = 5;
+
+#[derive(Debug, Subcommand)]
+pub enum SwapCommand {
+    /// Simulate a swap and show its output, price impact and tick arrays
+    Quote {
+        /// Raydium CLMM pool
+        #[arg(long)]
+        pool: Pubkey,
+        #[arg(long)]
+        input_mint: Pubkey,
+        /// Amount in, in base units
+        #[arg(long)]
+        amount: u64,
+    },
+    /// Swap through `proxy_swap`
+    Execute {
+        #[arg(long)]
+        pool: Pubkey,
+        #[arg(long)]
+        input_mint: Pubkey,
+        #[arg(long)]
+        amount: u64,
+        /// Slippage applied to the quote to get the minimum amount out
+        #[arg(long, default_value_t = 50)]
+        slippage_bps: u16,
+    },
+}
+
+pub(crate) struct PoolInfo {
+    pub(crate) pool: ClmmPool,
+    pub(crate) state: PoolState,
+    amm_config: AmmConfig,
+}
+
+pub(crate) fn load_pool(ctx: &CliContext, address: &Pubkey) -> Result<PoolInfo> {
+    let account = ctx.get_account(address)?;
+    let state: PoolState = load_zero_copy(&account.data)
+        .ok_or_else(|| anyhow!("{address} is not a Raydium CLMM pool"))?;
+    let amm_config: AmmConfig = ctx.get_anchor_account(&state.amm_config)?;
+    let (_, token_program_0) = ctx.get_mint(&state.token_mint_0)?;
+    let (_, token_program_1) = ctx.get_mint(&state.token_mint_1)?;
+
+    let pool = ClmmPool {
+        clmm_program: account.owner,
+        amm_config: state.amm_config,
+        pool_state: *address,
+        token_mint_0: state.token_mint_0,
+        token_mint_1: state.token_mint_1,
+        token_program_0,
+        token_program_1,
+        tick_spacing: state.tick_spacing,
+    };
+    Ok(PoolInfo {
+        pool,
+        state,
+        amm_config,
+    })
+}
+
+impl PoolInfo {
+    fn zero_for_one(&self, input_mint: &Pubkey) -> Result<bool> {
+        if *input_mint == self.state.token_mint_0 {
+            Ok(true)
+        } else if *input_mint == self.state.token_mint_1 {
+            Ok(false)
+        } else {
+            bail!("{input_mint} is not a mint of the pool")
+        }
+    }
+
+    fn decimals(&self, zero_for_one: bool) -> (u8, u8) {
+        if zero_for_one {
+            (self.state.mint_decimals_0, self.state.mint_decimals_1)
+        } else {
+            (self.state.mint_decimals_1, self.state.mint_decimals_0)
+        }
+    }
+
+    /// Quotes an exact input swap against the tick arrays around the current tick.
+    fn quote(&self, ctx: &CliContext, amount: u64, zero_for_one: bool) -> Result<SwapQuote> {
+        let candidates = self.pool.swap_tick_arrays(
+            self.state.tick_current,
+            zero_for_one,
+            SWAP_TICK_ARRAYS,
+        );
+        // The clock is read in the same request as the tick arrays, so both are at one slot
+        let mut requests = vec![sysvar::clock::ID];
+        requests.extend(candidates);
+        let mut accounts = ctx.rpc.get_multiple_accounts(&requests)?.into_iter();
+        let clock: Clock = accounts
+            .next()
+            .flatten()
+            .and_then(|account| from_account(&account))
+            .ok_or_else(|| anyhow!("cannot read the clock sysvar"))?;
+        let tick_arrays: Vec<TickArrayState> = accounts
+            .flatten()
+            .filter_map(|account| load_zero_copy(&account.data))
+            .collect();
+        let bitmap_extension: Option<TickArrayBitmapExtension> = ctx
+            .rpc
+            .get_account_with_commitment(
+                &self.pool.tick_array_bitmap_extension(),
+                ctx.rpc.commitment(),
+            )?
+            .value
+            .and_then(|account| load_zero_copy(&account.data));
+
+        quote_swap(
+            &self.pool,
+            &self.state,
+            &self.amm_config,
+            &tick_arrays,
+            bitmap_extension.as_ref(),
+            QuoteParams {
+                amount,
+                zero_for_one,
+                is_base_input: true,
+                sqrt_price_limit_x64: 0,
+                block_timestamp: clock.unix_timestamp as u32,
+            },
+        )
+        .map_err(|e| anyhow!("cannot quote the swap: {e}"))
+    }
+}
+
+pub fn process(ctx: &CliContext, command: SwapCommand) -> Result<()> {
+    match command {
+        SwapCommand::Quote {
+            pool,
+            input_mint,
+            amount,
+        } => {
+            let info = load_pool(ctx, &pool)?;
+            let zero_for_one = info.zero_for_one(&input_mint)?;
+            let (decimals_in, decimals_out) = info.decimals(zero_for_one);
+            let quote = info.quote(ctx, amount, zero_for_one)?;
+
+            println!("Pool:         {pool}");
+            println!("Current tick: {}", info.state.tick_current);
+            println!("Amount in:    {}", ui_amount(quote.amount_in, decimals_in));
+            println!("Amount out:   {}", ui_amount(quote.amount_out, decimals_out));
+            println!("Fee:          {}", ui_amount(quote.fee_amount, decimals_in));
+            println!("Price impact: {} bps", quote.price_impact_bps);
+            println!("Tick after:   {}", quote.tick_after);
+            println!("Tick arrays:");
+            for tick_array in &quote.tick_arrays {
+                println!("  {tick_array}");
+            }
+            Ok(())
+        }
+        SwapCommand::Execute {
+            pool,
+            input_mint,
+            amount,
+            slippage_bps,
+        } => {
+            let info = load_pool(ctx, &pool)?;
+            let zero_for_one = info.zero_for_one(&input_mint)?;
+            let (decimals_in, decimals_out) = info.decimals(zero_for_one);
+            let quote = info.quote(ctx, amount, zero_for_one)?;
+            let minimum_amount_out = apply_slippage(quote.amount_out, slippage_bps);
+
+            println!(
+                "Swapping {} for at least {}",
+                ui_amount(quote.amount_in, decimals_in),
+                ui_amount(minimum_amount_out, decimals_out)
+            );
+            let ix = interact_dapp_client::proxy_swap(
+                &info.pool,
+                &ctx.payer.pubkey(),
+                &input_mint,
+                &quote.tick_arrays,
+                quote.needs_bitmap_extension,
+                SwapArgs {
+                    amount,
+                    other_amount_threshold: minimum_amount_out,
+                    sqrt_price_limit_x64: 0,
+                    is_base_input: true,
+                },
+            );
+            let units = proxy_swap_compute_units(quote.tick_arrays.len());
+            ctx.process(&with_compute_budget(&[ix], units, None), &[])
+        }
+    }
+}
@@ -0,0 +1,74 @@
+use anchor_lang::solana_program::instruction::Instruction;
+use solana_sdk::compute_budget::ComputeBudgetInstruction;
+
+/// Most compute units a transaction can request
+pub const MAX_COMPUTE_UNIT_LIMIT: u32 = 1_400_000;
+
+// Budgets per instruction, including the CPIs. The `compute_units` benchmark of
+// interact-dapp-tests fails when an instruction runs over them, so limits built from these
+// constants stay sufficient. The Earn budgets keep room for the Jupiter programs, which do
+// more work than the local mock the benchmark runs against.
+pub const DEPOSIT_EARN_COMPUTE_UNITS: u32 = 120_000;
+pub const WITHDRAW_EARN_COMPUTE_UNITS: u32 = 150_000;
+/// `proxy_swap` reading a single tick array
+pub const PROXY_SWAP_COMPUTE_UNITS: u32 = 110_000;
+/// Added to [`PROXY_SWAP_COMPUTE_UNITS`] for every further tick array the swap crosses into
+pub const PROXY_SWAP_COMPUTE_UNITS_PER_TICK_ARRAY: u32 = 30_000;
+
+// Budgets of the other instructions swapping through Raydium, with the swap reading a
+// single tick array. They grow per further tick array like `proxy_swap`, see
+// `compute_units_with_tick_arrays`.
+/// Swap of the due leg, then the Earn deposit of the output when the order has one
+pub const EXECUTE_DCA_COMPUTE_UNITS: u32 = 250_000;
+/// Earn withdrawal of the escrow, swap, then the Earn deposit of the unfilled rest
+pub const FILL_LIMIT_ORDER_COMPUTE_UNITS: u32 = 400_000;
+/// Earn redemption of the escrow and swap, priced at the pool TWAP
+pub const EXECUTE_TRIGGER_COMPUTE_UNITS: u32 = 300_000;
+/// Earn withdrawal, swap and Earn deposit, with the APY of both markets read twice
+pub const REALLOCATE_COMPUTE_UNITS: u32 = 450_000;
+/// Each compound step: the fee harvest, the swap of the pair token or the Earn deposit
+pub const CRANK_COMPOUND_COMPUTE_UNITS: u32 = 200_000;
+/// Closing the position, the swap to the new range and opening a position with its
+/// Metaplex metadata
+pub const REBALANCE_RANGE_COMPUTE_UNITS: u32 = 500_000;
+
+/// Headroom added to simulated consumption by [`compute_unit_limit_from_simulation`], in bps
+pub const SIMULATION_MARGIN_BPS: u64 = 1_000;
+
+/// Budget of a `proxy_swap` reading `tick_arrays` tick arrays, as listed in its quote.
+pub fn proxy_swap_compute_units(tick_arrays: usize) -> u32 {
+    compute_units_with_tick_arrays(PROXY_SWAP_COMPUTE_UNITS, tick_arrays)
+}
+
+/// Grows `units`, the budget of a swapping instruction over a single tick array, to a swap
+/// reading `tick_arrays` tick arrays.
+pub fn compute_units_with_tick_arrays(units: u32, tick_arrays: usize) -> u32 {
+    let extra_tick_arrays = tick_arrays.saturating_sub(1) as u32;
+    units
+        .saturating_add(extra_tick_arrays.saturating_mul(PROXY_SWAP_COMPUTE_UNITS_PER_TICK_ARRAY))
+        .min(MAX_COMPUTE_UNIT_LIMIT)
+}
+
+/// Limit for a transaction that consumed `units_consumed` in simulation, with
+/// [`SIMULATION_MARGIN_BPS`] of headroom for state changes until it lands.
+pub fn compute_unit_limit_from_simulation(units_consumed: u64) -> u32 {
+    let units = units_consumed.saturating_mul(10_000 + SIMULATION_MARGIN_BPS) / 10_000;
+    units.min(MAX_COMPUTE_UNIT_LIMIT as u64) as u32
+}
+
+/// Prepends a compute unit limit of `units` to `instructions` and, when set, a priority fee
+/// of `micro_lamports_per_unit`.
+pub fn with_compute_budget(
+    instructions: &[Instruction],
+    units: u32,
+    micro_lamports_per_unit: Option<u64>,
+) -> Vec<Instruction> {
+    let mut all_instructions = vec![ComputeBudgetInstruction::set_compute_unit_limit(
+        units.min(MAX_COMPUTE_UNIT_LIMIT),
+    )];
+    if let Some(micro_lamports) = micro_lamports_per_unit {
+        all_instructions.push(ComputeBudgetInstruction::set_compute_unit_price(micro_lamports));
+    }
+    all_instructions.extend_from_slice(instructions);
+    all_instructions
+}
@@ -0,0 +1,188 @@
+use anchor_lang::prelude::*;
+use anchor_lang::Discriminator;
+use base64::{engine::general_purpose::STANDARD, Engine};
+use interact_dapp::events::{DepositEarnEvent, ProxySwapEvent, WithdrawEarnEvent};
+use interact_dapp::jupiter_lend::events::{LogDeposit, LogWithdraw};
+
+/// Prefix of self-CPI event instructions (`emit_cpi!`)
+pub const EVENT_IX_TAG_LE: [u8; 8] = [228, 69, 165, 46, 81, 203, 154, 29];
+const PROGRAM_DATA: &str = "Program data: ";
+
+pub enum InteractDappEvent {
+    DepositEarn(DepositEarnEvent),
+    WithdrawEarn(WithdrawEarnEvent),
+    ProxySwap(ProxySwapEvent),
+}
+
+pub enum JupiterLendEvent {
+    Deposit(LogDeposit),
+    Withdraw(LogWithdraw),
+}
+
+pub enum DecodedEvent {
+    InteractDapp(InteractDappEvent),
+    JupiterLend(JupiterLendEvent),
+}
+
+/// An inner instruction of a transaction, as returned by `getTransaction`.
+#[derive(Debug, Clone)]
+pub struct InnerInstructionData {
+    pub program_id: Pubkey,
+    pub data: Vec<u8>,
+}
+
+fn decode<T: AnchorDeserialize + Discriminator>(data: &[u8]) -> Option<T> {
+    let payload = data.strip_prefix(T::DISCRIMINATOR)?;
+    T::deserialize(&mut &payload[..]).ok()
+}
+
+pub struct EventDecoder {
+    pub interact_dapp_program: Pubkey,
+    pub lending_program: Pubkey,
+}
+
+impl EventDecoder {
+    pub fn new(lending_program: Pubkey) -> Self {
+        Self {
+            interact_dapp_program: interact_dapp::ID,
+            lending_program,
+        }
+    }
+
+    /// Decodes an event payload (discriminator + borsh data) emitted by `program_id`.
+    pub fn decode_event(&self, program_id: &Pubkey, data: &[u8]) -> Option<DecodedEvent> {
+        if *program_id == self.interact_dapp_program {
+            let event = if let Some(event) = decode::<DepositEarnEvent>(data) {
+                InteractDappEvent::DepositEarn(event)
+            } else if let Some(event) = decode::<WithdrawEarnEvent>(data) {
+                InteractDappEvent::WithdrawEarn(event)
+            } else {
+                InteractDappEvent::ProxySwap(decode::<ProxySwapEvent>(data)?)
+            };
+            return Some(DecodedEvent::InteractDapp(event));
+        }
+        if *program_id == self.lending_program {
+            let event = if let Some(event) = decode::<LogDeposit>(data) {
+                JupiterLendEvent::Deposit(event)
+            } else {
+                JupiterLendEvent::Withdraw(decode::<LogWithdraw>(data)?)
+            };
+            return Some(DecodedEvent::JupiterLend(event));
+        }
+        None
+    }
+
+    /// Decodes the `Program data:` lines of a transaction log, attributing each line to
+    /// the program on top of the invocation stack.
+    pub fn decode_logs<S: AsRef<str>>(&self, logs: &[S]) -> Vec<DecodedEvent> {
+        let mut stack: Vec<Pubkey> = Vec::new();
+        let mut events = Vec::new();
+        for log in logs {
+            let log = log.as_ref();
+            if let Some(data) = log.strip_prefix(PROGRAM_DATA) {
+                let Some(program_id) = stack.last() else {
+                    continue;
+                };
+                if let Ok(data) = STANDARD.decode(data.trim()) {
+                    events.extend(self.decode_event(program_id, &data));
+                }
+                continue;
+            }
+
+            let mut parts = log.split_whitespace();
+            if parts.next() != Some("Program") {
+                continue;
+            }
+            let (Some(program), Some(action)) = (parts.next(), parts.next()) else {
+                continue;
+            };
+            match action {
+                "invoke" => {
+                    if let Ok(program_id) = program.parse() {
+                        stack.push(program_id);
+                    }
+                }
+                "success" | "failed:" => {
+                    stack.pop();
+                }
+                _ => {}
+            }
+        }
+        events
+    }
+
+    /// Decodes events emitted through self-CPI (`emit_cpi!`) inner instructions.
+    pub fn decode_inner_instructions(
+        &self,
+        instructions: &[InnerInstructionData],
+    ) -> Vec<DecodedEvent> {
+        instructions
+            .iter()
+            .filter_map(|ix| {
+                let data = ix.data.strip_prefix(&EVENT_IX_TAG_LE[..])?;
+                self.decode_event(&ix.program_id, data)
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EarnAction {
+    Deposit,
+    Withdraw,
+}
+
+/// Amounts reported by interact_dapp next to the ones reported by Jupiter for the same CPI.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EarnReconciliation {
+    pub action: EarnAction,
+    pub signer: Pubkey,
+    pub reported_assets: u64,
+    pub protocol_assets: u64,
+    pub reported_shares: u64,
+    pub protocol_shares: u64,
+}
+
+impl EarnReconciliation {
+    pub fn is_consistent(&self) -> bool {
+        self.reported_assets == self.protocol_assets && self.reported_shares == self.protocol_shares
+    }
+}
+
+/// Pairs every interact_dapp Earn event with the Jupiter event emitted by its CPI. The
+/// Jupiter event is logged first, since our event is emitted once the CPI returned.
+pub fn reconcile(events: &[DecodedEvent]) -> Vec<EarnReconciliation> {
+    let mut pending: Option<&JupiterLendEvent> = None;
+    let mut reconciliations = Vec::new();
+    for event in events {
+        match event {
+            DecodedEvent::JupiterLend(event) => pending = Some(event),
+            DecodedEvent::InteractDapp(InteractDappEvent::DepositEarn(ours)) => {
+                if let Some(JupiterLendEvent::Deposit(theirs)) = pending.take() {
+                    reconciliations.push(EarnReconciliation {
+                        action: EarnAction::Deposit,
+                        signer: ours.signer,
+                        reported_assets: ours.assets_deposited,
+                        protocol_assets: theirs.assets,
+                        reported_shares: ours.shares_minted,
+                        protocol_shares: theirs.shares_minted,
+                    });
+                }
+            }
+            DecodedEvent::InteractDapp(InteractDappEvent::WithdrawEarn(ours)) => {
+                if let Some(JupiterLendEvent::Withdraw(theirs)) = pending.take() {
+                    reconciliations.push(EarnReconciliation {
+                        action: EarnAction::Withdraw,
+                        signer: ours.signer,
+                        reported_assets: ours.assets_withdrawn,
+                        protocol_assets: theirs.assets,
+                        reported_shares: ours.shares_burned,
+                        protocol_shares: theirs.shares_burned,
+                    });
+                }
+            }
+            DecodedEvent::InteractDapp(InteractDappEvent::ProxySwap(_)) => {}
+        }
+    }
+    reconciliations
+}
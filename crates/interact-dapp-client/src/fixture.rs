@@ -0,0 +1,119 @@
+use std::path::Path;
+use anchor_lang::prelude::*;
+use base64::{engine::general_purpose::STANDARD, Engine};
+use raydium_amm_v3::states::PoolState;
+use serde::{Deserialize, Serialize};
+use crate::instructions::ClmmPool;
+use crate::pda::{get_tick_array_start_index, EarnMarket, TICK_ARRAY_SIZE};
+
+/// An account of a snapshot, with its data base64 encoded in the JSON fixture.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AccountFixture {
+    #[serde(with = "pubkey_string")]
+    pub address: Pubkey,
+    #[serde(with = "pubkey_string")]
+    pub owner: Pubkey,
+    pub lamports: u64,
+    pub executable: bool,
+    pub rent_epoch: u64,
+    #[serde(with = "base64_data")]
+    pub data: Vec<u8>,
+}
+
+/// A set of accounts read at one slot, replayed in local banks by the test harness.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Snapshot {
+    /// RPC URL or cluster name the accounts were read from
+    pub cluster: String,
+    pub slot: u64,
+    /// Clock timestamp at `slot`, restored on replay so exchange prices accrue the same
+    pub unix_timestamp: i64,
+    pub accounts: Vec<AccountFixture>,
+}
+
+impl Snapshot {
+    pub fn read(path: &Path) -> std::io::Result<Self> {
+        let content = std::fs::read_to_string(path)?;
+        serde_json::from_str(&content).map_err(std::io::Error::other)
+    }
+
+    pub fn write(&self, path: &Path) -> std::io::Result<()> {
+        let content = serde_json::to_string_pretty(self).map_err(std::io::Error::other)?;
+        std::fs::write(path, content)
+    }
+
+    pub fn get(&self, address: &Pubkey) -> Option<&AccountFixture> {
+        self.accounts.iter().find(|account| account.address == *address)
+    }
+}
+
+/// Accounts a swap on `pool` reads: the pool, its config, vaults, mints, observation,
+/// bitmap extension and `tick_array_count` tick arrays on each side of the current tick.
+pub fn get_pool_snapshot_addresses(
+    pool: &ClmmPool,
+    pool_state: &PoolState,
+    tick_array_count: i32,
+) -> Vec<Pubkey> {
+    let mut addresses = vec![
+        pool.pool_state,
+        pool.amm_config,
+        pool.token_mint_0,
+        pool.token_mint_1,
+        pool.vault(&pool.token_mint_0),
+        pool.vault(&pool.token_mint_1),
+        pool.observation(),
+        pool.tick_array_bitmap_extension(),
+    ];
+    let ticks_in_array = TICK_ARRAY_SIZE * pool.tick_spacing as i32;
+    let start = get_tick_array_start_index(pool_state.tick_current, pool.tick_spacing);
+    addresses.extend(
+        (-tick_array_count..=tick_array_count)
+            .map(|offset| pool.tick_array(start + offset * ticks_in_array)),
+    );
+    addresses
+}
+
+/// Accounts of an Earn market read by the lending instructions and the Earn math.
+pub fn get_earn_snapshot_addresses(market: &EarnMarket) -> Vec<Pubkey> {
+    vec![
+        market.mint,
+        market.f_token_mint(),
+        market.lending_admin(),
+        market.lending(),
+        market.rewards_rate_model(),
+        market.liquidity(),
+        market.token_reserve(),
+        market.supply_position(),
+        market.rate_model(),
+        market.vault(),
+    ]
+}
+
+mod pubkey_string {
+    use std::str::FromStr;
+    use anchor_lang::prelude::Pubkey;
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(pubkey: &Pubkey, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(pubkey)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Pubkey, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        Pubkey::from_str(&value).map_err(D::Error::custom)
+    }
+}
+
+mod base64_data {
+    use super::{Engine, STANDARD};
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(data: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&STANDARD.encode(data))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        STANDARD.decode(value).map_err(D::Error::custom)
+    }
+}
@@ -0,0 +1,80 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::bpf_loader_upgradeable;
+use anchor_lang::solana_program::instruction::Instruction;
+use crate::pda::*;
+use super::program_instruction;
+
+/// `initialize_config`: creates the program config with `admin`, paid and signed by the
+/// program upgrade `authority`.
+pub fn initialize_config(authority: &Pubkey, admin: &Pubkey) -> Instruction {
+    let program_data = Pubkey::find_program_address(
+        &[interact_dapp::ID.as_ref()],
+        &bpf_loader_upgradeable::ID,
+    )
+    .0;
+    program_instruction(
+        interact_dapp::accounts::InitializeConfig {
+            authority: *authority,
+            config: get_config_address(),
+            program: interact_dapp::ID,
+            program_data,
+            system_program: system_program::ID,
+        },
+        interact_dapp::instruction::InitializeConfig { admin: *admin },
+    )
+}
+
+/// `update_config`: sets the pause flag and / or protocol fee, `None` leaves a setting as is.
+pub fn update_config(admin: &Pubkey, paused: Option<bool>, fee_bps: Option<u16>) -> Instruction {
+    program_instruction(
+        interact_dapp::accounts::UpdateConfig {
+            admin: *admin,
+            config: get_config_address(),
+        },
+        interact_dapp::instruction::UpdateConfig { paused, fee_bps },
+    )
+}
+
+/// `initialize_deposit_caps` of the market underlying, paid by `admin`.
+pub fn initialize_deposit_caps(
+    market: &EarnMarket,
+    admin: &Pubkey,
+    global_cap: u64,
+    per_user_cap: u64,
+) -> Instruction {
+    program_instruction(
+        interact_dapp::accounts::InitializeDepositCaps {
+            admin: *admin,
+            config: get_config_address(),
+            deposit_caps: get_deposit_caps_address(&market.mint),
+            mint: market.mint,
+            lending: market.lending(),
+            lending_program: market.lending_program,
+            system_program: system_program::ID,
+        },
+        interact_dapp::instruction::InitializeDepositCaps {
+            global_cap,
+            per_user_cap,
+        },
+    )
+}
+
+/// `update_deposit_caps` of `mint`.
+pub fn update_deposit_caps(
+    mint: &Pubkey,
+    admin: &Pubkey,
+    global_cap: u64,
+    per_user_cap: u64,
+) -> Instruction {
+    program_instruction(
+        interact_dapp::accounts::UpdateDepositCaps {
+            admin: *admin,
+            config: get_config_address(),
+            deposit_caps: get_deposit_caps_address(mint),
+        },
+        interact_dapp::instruction::UpdateDepositCaps {
+            global_cap,
+            per_user_cap,
+        },
+    )
+}
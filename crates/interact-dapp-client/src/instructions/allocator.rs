@@ -0,0 +1,137 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_spl::associated_token;
+use crate::pda::*;
+use super::{
+    clmm_swap_accounts, earn_accounts, program_instruction, program_instruction_with_remaining,
+    swap_remaining_accounts, ClmmPool,
+};
+
+/// `initialize_allocator` of `authority`, rebalanced by `keeper`.
+pub fn initialize_allocator(
+    authority: &Pubkey,
+    keeper: &Pubkey,
+    min_apy_gap: u64,
+    max_slippage_bps: u16,
+) -> Instruction {
+    program_instruction(
+        interact_dapp::accounts::InitializeAllocator {
+            authority: *authority,
+            allocator: get_allocator_address(authority),
+            system_program: system_program::ID,
+        },
+        interact_dapp::instruction::InitializeAllocator {
+            keeper: *keeper,
+            min_apy_gap,
+            max_slippage_bps,
+        },
+    )
+}
+
+/// `add_allocator_market`: allowlists `market` and creates the allocator vaults of it.
+pub fn add_allocator_market(market: &EarnMarket, authority: &Pubkey) -> Instruction {
+    let allocator = get_allocator_address(authority);
+    program_instruction(
+        interact_dapp::accounts::AddAllocatorMarket {
+            authority: *authority,
+            allocator,
+            mint: market.mint,
+            f_token_mint: market.f_token_mint(),
+            token_vault: market.token_account(&allocator),
+            f_token_vault: market.f_token_account(&allocator),
+            lending: market.lending(),
+            lending_program: market.lending_program,
+            token_program: market.token_program,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+        },
+        interact_dapp::instruction::AddAllocatorMarket {},
+    )
+}
+
+/// `allocator_deposit` of `amount` from the authority ATA into `market`.
+pub fn allocator_deposit(market: &EarnMarket, authority: &Pubkey, amount: u64) -> Instruction {
+    let allocator = get_allocator_address(authority);
+    program_instruction(
+        interact_dapp::accounts::AllocatorDeposit {
+            authority: *authority,
+            config: get_config_address(),
+            allocator,
+            mint: market.mint,
+            authority_token_account: market.token_account(authority),
+            token_vault: market.token_account(&allocator),
+            f_token_vault: market.f_token_account(&allocator),
+            earn: earn_accounts(market),
+            deposit_caps: get_deposit_caps_address(&market.mint),
+            user_deposits: get_user_deposits_address(&market.mint, authority),
+            token_program: market.token_program,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+        },
+        interact_dapp::instruction::AllocatorDeposit { amount },
+    )
+}
+
+/// `allocator_withdraw` of `assets` from `market` to the authority ATA.
+pub fn allocator_withdraw(market: &EarnMarket, authority: &Pubkey, assets: u64) -> Instruction {
+    let allocator = get_allocator_address(authority);
+    program_instruction(
+        interact_dapp::accounts::AllocatorWithdraw {
+            authority: *authority,
+            config: get_config_address(),
+            allocator,
+            mint: market.mint,
+            authority_token_account: market.token_account(authority),
+            token_vault: market.token_account(&allocator),
+            f_token_vault: market.f_token_account(&allocator),
+            earn: earn_accounts(market),
+            claim_account: market.claim_account(&allocator),
+            deposit_caps: get_deposit_caps_address(&market.mint),
+            user_deposits: get_user_deposits_address(&market.mint, authority),
+            token_program: market.token_program,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+        },
+        interact_dapp::instruction::AllocatorWithdraw { assets },
+    )
+}
+
+/// `reallocate` of `amount` of the allocator of `authority` from market `from` to market
+/// `to`, swapping the underlying on `pool`. `tick_arrays` and `with_bitmap_extension` are
+/// the swap accounts of [`swap_remaining_accounts`].
+#[allow(clippy::too_many_arguments)]
+pub fn reallocate(
+    from: &EarnMarket,
+    to: &EarnMarket,
+    pool: &ClmmPool,
+    authority: &Pubkey,
+    keeper: &Pubkey,
+    tick_arrays: &[Pubkey],
+    with_bitmap_extension: bool,
+    amount: u64,
+) -> Instruction {
+    let allocator = get_allocator_address(authority);
+    program_instruction_with_remaining(
+        interact_dapp::accounts::Reallocate {
+            keeper: *keeper,
+            config: get_config_address(),
+            allocator,
+            from_earn: earn_accounts(from),
+            from_token_vault: from.token_account(&allocator),
+            from_f_token_vault: from.f_token_account(&allocator),
+            from_claim_account: from.claim_account(&allocator),
+            from_deposit_caps: get_deposit_caps_address(&from.mint),
+            from_user_deposits: get_user_deposits_address(&from.mint, authority),
+            to_earn: earn_accounts(to),
+            to_token_vault: to.token_account(&allocator),
+            to_f_token_vault: to.f_token_account(&allocator),
+            to_deposit_caps: get_deposit_caps_address(&to.mint),
+            to_user_deposits: get_user_deposits_address(&to.mint, authority),
+            swap: clmm_swap_accounts(pool, &from.mint),
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+        },
+        swap_remaining_accounts(pool, tick_arrays, with_bitmap_extension),
+        interact_dapp::instruction::Reallocate { amount },
+    )
+}
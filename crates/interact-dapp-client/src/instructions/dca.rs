@@ -0,0 +1,140 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_spl::associated_token;
+use crate::pda::*;
+use super::{
+    clmm_swap_accounts, earn_accounts, program_instruction, program_instruction_with_remaining,
+    swap_remaining_accounts, ClmmPool,
+};
+
+/// Parameters of `open_dca_order`, as taken by the program instruction.
+#[derive(Debug, Clone, Copy)]
+pub struct DcaOrderArgs {
+    pub amount: u64,
+    pub amount_per_interval: u64,
+    pub min_output_per_interval: u64,
+    pub interval: i64,
+    pub tip_lamports: u64,
+}
+
+/// `open_dca_order` of `owner` selling `input_mint` for the other mint of `pool`, from the
+/// owner ATA. `output_earn` is the Earn market of the output mint when the order deposits
+/// its output into Earn.
+pub fn open_dca_order(
+    pool: &ClmmPool,
+    input_mint: &Pubkey,
+    owner: &Pubkey,
+    order_id: u64,
+    output_earn: Option<&EarnMarket>,
+    args: DcaOrderArgs,
+) -> Instruction {
+    let dca_order = get_dca_order_address(owner, order_id);
+    let output_mint = pool.other_mint(input_mint);
+    program_instruction(
+        interact_dapp::accounts::OpenDcaOrder {
+            owner: *owner,
+            config: get_config_address(),
+            dca_order,
+            input_mint: *input_mint,
+            output_mint,
+            owner_input_account: pool.token_account(owner, input_mint),
+            input_vault: pool.token_account(&dca_order, input_mint),
+            output_vault: pool.token_account(&dca_order, &output_mint),
+            pool_state: pool.pool_state,
+            lending: output_earn.map(|market| market.lending()),
+            lending_program: output_earn.map(|market| market.lending_program),
+            f_token_mint: output_earn.map(|market| market.f_token_mint()),
+            f_token_vault: output_earn.map(|market| market.f_token_account(&dca_order)),
+            deposit_caps: output_earn.map(|_| get_deposit_caps_address(&output_mint)),
+            user_deposits: output_earn.map(|_| get_user_deposits_address(&output_mint, owner)),
+            input_token_program: pool.token_program_of(input_mint),
+            output_token_program: pool.token_program_of(&output_mint),
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+        },
+        interact_dapp::instruction::OpenDcaOrder {
+            order_id,
+            amount: args.amount,
+            amount_per_interval: args.amount_per_interval,
+            min_output_per_interval: args.min_output_per_interval,
+            interval: args.interval,
+            tip_lamports: args.tip_lamports,
+        },
+    )
+}
+
+/// `execute_dca` of the due leg of an order opened by [`open_dca_order`] with the same
+/// `pool`, `input_mint` and `output_earn`. `tick_arrays` and `with_bitmap_extension` are the
+/// swap accounts of [`swap_remaining_accounts`].
+#[allow(clippy::too_many_arguments)]
+pub fn execute_dca(
+    pool: &ClmmPool,
+    input_mint: &Pubkey,
+    cranker: &Pubkey,
+    owner: &Pubkey,
+    order_id: u64,
+    output_earn: Option<&EarnMarket>,
+    tick_arrays: &[Pubkey],
+    with_bitmap_extension: bool,
+) -> Instruction {
+    let dca_order = get_dca_order_address(owner, order_id);
+    let output_mint = pool.other_mint(input_mint);
+    let mut remaining = swap_remaining_accounts(pool, tick_arrays, with_bitmap_extension);
+    let swap_count = remaining.len() as u8;
+    if let Some(market) = output_earn {
+        remaining.extend(earn_accounts(market).to_account_metas(None));
+    }
+    let earn_count = remaining.len() as u8 - swap_count;
+    program_instruction_with_remaining(
+        interact_dapp::accounts::ExecuteDca {
+            cranker: *cranker,
+            config: get_config_address(),
+            dca_order,
+            input_vault: pool.token_account(&dca_order, input_mint),
+            output_vault: pool.token_account(&dca_order, &output_mint),
+            f_token_vault: output_earn.map(|market| market.f_token_account(&dca_order)),
+            deposit_caps: output_earn.map(|_| get_deposit_caps_address(&output_mint)),
+            user_deposits: output_earn.map(|_| get_user_deposits_address(&output_mint, owner)),
+            swap: clmm_swap_accounts(pool, input_mint),
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+        },
+        remaining,
+        interact_dapp::instruction::ExecuteDca {
+            remaining_accounts_indices: vec![swap_count, earn_count],
+        },
+    )
+}
+
+/// `close_dca_order`: closes an order opened by [`open_dca_order`] with the same `pool`,
+/// `input_mint` and `output_earn`, returning its vaults to the owner ATAs.
+pub fn close_dca_order(
+    pool: &ClmmPool,
+    input_mint: &Pubkey,
+    owner: &Pubkey,
+    order_id: u64,
+    output_earn: Option<&EarnMarket>,
+) -> Instruction {
+    let dca_order = get_dca_order_address(owner, order_id);
+    let output_mint = pool.other_mint(input_mint);
+    program_instruction(
+        interact_dapp::accounts::CloseDcaOrder {
+            owner: *owner,
+            dca_order,
+            input_mint: *input_mint,
+            output_mint,
+            input_vault: pool.token_account(&dca_order, input_mint),
+            output_vault: pool.token_account(&dca_order, &output_mint),
+            owner_input_account: pool.token_account(owner, input_mint),
+            owner_output_account: pool.token_account(owner, &output_mint),
+            f_token_mint: output_earn.map(|market| market.f_token_mint()),
+            f_token_vault: output_earn.map(|market| market.f_token_account(&dca_order)),
+            owner_f_token_account: output_earn.map(|market| market.f_token_account(owner)),
+            deposit_caps: output_earn.map(|_| get_deposit_caps_address(&output_mint)),
+            user_deposits: output_earn.map(|_| get_user_deposits_address(&output_mint, owner)),
+            input_token_program: pool.token_program_of(input_mint),
+            output_token_program: pool.token_program_of(&output_mint),
+        },
+        interact_dapp::instruction::CloseDcaOrder {},
+    )
+}
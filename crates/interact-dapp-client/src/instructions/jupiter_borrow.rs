@@ -0,0 +1,22 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::Instruction;
+use super::program_instruction_with_remaining;
+
+/// `position_health` of the Jupiter Vaults position in `accounts`, priced by the oracle
+/// `sources`. They are passed in the `operate` layout, without the branches and tick has
+/// debt arrays the instruction does not read.
+pub fn position_health(
+    accounts: interact_dapp::accounts::PositionHealthParams,
+    sources: &[Pubkey],
+) -> Instruction {
+    program_instruction_with_remaining(
+        accounts,
+        sources
+            .iter()
+            .map(|source| AccountMeta::new_readonly(*source, false))
+            .collect(),
+        interact_dapp::instruction::PositionHealth {
+            remaining_accounts_indices: vec![sources.len() as u8, 0, 0],
+        },
+    )
+}
@@ -0,0 +1,163 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_spl::associated_token;
+use crate::pda::*;
+use super::program_instruction;
+
+/// `EarnAccounts` of a market, as nested in the instructions that deposit into it.
+pub fn earn_accounts(market: &EarnMarket) -> interact_dapp::accounts::EarnAccounts {
+    interact_dapp::accounts::EarnAccounts {
+        lending_admin: market.lending_admin(),
+        lending: market.lending(),
+        f_token_mint: market.f_token_mint(),
+        supply_token_reserves_liquidity: market.token_reserve(),
+        lending_supply_position_on_liquidity: market.supply_position(),
+        rate_model: market.rate_model(),
+        vault: market.vault(),
+        liquidity: market.liquidity(),
+        liquidity_program: market.liquidity_program,
+        rewards_rate_model: market.rewards_rate_model(),
+        lending_program: market.lending_program,
+    }
+}
+
+/// `deposit_earn`: supplies `amount` of the market underlying from the signer ATA and mints
+/// fTokens to the signer fToken ATA.
+pub fn deposit_earn(market: &EarnMarket, signer: &Pubkey, amount: u64) -> Instruction {
+    program_instruction(
+        interact_dapp::accounts::DepositParams {
+            signer: *signer,
+            config: get_config_address(),
+            depositor_token_account: market.token_account(signer),
+            recipient_token_account: market.f_token_account(signer),
+            mint: market.mint,
+            lending_admin: market.lending_admin(),
+            lending: market.lending(),
+            f_token_mint: market.f_token_mint(),
+            supply_token_reserves_liquidity: market.token_reserve(),
+            lending_supply_position_on_liquidity: market.supply_position(),
+            rate_model: market.rate_model(),
+            vault: market.vault(),
+            liquidity: market.liquidity(),
+            liquidity_program: market.liquidity_program,
+            rewards_rate_model: market.rewards_rate_model(),
+            token_program: market.token_program,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+            lending_program: market.lending_program,
+        },
+        interact_dapp::instruction::DepositEarn { amount },
+    )
+}
+
+fn withdraw_accounts(
+    market: &EarnMarket,
+    signer: &Pubkey,
+) -> interact_dapp::accounts::WithdrawParams {
+    interact_dapp::accounts::WithdrawParams {
+    config: get_config_address(),
+        signer: *signer,
+        owner_token_account: market.f_token_account(signer),
+        recipient_token_account: market.token_account(signer),
+        lending_admin: market.lending_admin(),
+        lending: market.lending(),
+        mint: market.mint,
+        f_token_mint: market.f_token_mint(),
+        supply_token_reserves_liquidity: market.token_reserve(),
+        lending_supply_position_on_liquidity: market.supply_position(),
+        rate_model: market.rate_model(),
+        vault: market.vault(),
+        claim_account: market.claim_account(signer),
+        liquidity: market.liquidity(),
+        liquidity_program: market.liquidity_program,
+        rewards_rate_model: market.rewards_rate_model(),
+        token_program: market.token_program,
+        associated_token_program: associated_token::ID,
+        system_program: system_program::ID,
+        lending_program: market.lending_program,
+    }
+}
+
+/// `withdraw_earn`: burns the signer fTokens worth `assets` of the underlying and sends the
+/// underlying to the signer ATA.
+pub fn withdraw_earn(market: &EarnMarket, signer: &Pubkey, assets: u64) -> Instruction {
+    program_instruction(
+        withdraw_accounts(market, signer),
+        interact_dapp::instruction::WithdrawEarn { assets },
+    )
+}
+
+/// `withdraw_earn_max_available`: withdraws up to `assets`, capped by the liquidity layer
+/// withdrawal limit.
+pub fn withdraw_earn_max_available(
+    market: &EarnMarket,
+    signer: &Pubkey,
+    assets: u64,
+) -> Instruction {
+    program_instruction(
+        withdraw_accounts(market, signer),
+        interact_dapp::instruction::WithdrawEarnMaxAvailable { assets },
+    )
+}
+
+/// `claim_withdrawal`: pays out the withdrawal the liquidity layer queued for the signer to
+/// the signer ATA.
+pub fn claim_withdrawal(market: &EarnMarket, signer: &Pubkey) -> Instruction {
+    program_instruction(
+        interact_dapp::accounts::ClaimWithdrawalParams {
+            signer: *signer,
+            recipient_token_account: market.token_account(signer),
+            liquidity: market.liquidity(),
+            supply_token_reserves_liquidity: market.token_reserve(),
+            mint: market.mint,
+            vault: market.vault(),
+            claim_account: market.claim_account(signer),
+            token_program: market.token_program,
+            associated_token_program: associated_token::ID,
+            liquidity_program: market.liquidity_program,
+        },
+        interact_dapp::instruction::ClaimWithdrawal {},
+    )
+}
+
+/// `preview_earn_position` of the fToken ATA of `owner`, with the rewards included in the
+/// exchange price.
+pub fn preview_earn_position(
+    market: &EarnMarket,
+    owner: &Pubkey,
+    principal: u64,
+    deposit_assets: u64,
+    withdraw_assets: u64,
+) -> Instruction {
+    program_instruction(
+        interact_dapp::accounts::PreviewEarnPositionParams {
+            owner_token_account: market.f_token_account(owner),
+            lending: market.lending(),
+            supply_token_reserves_liquidity: market.token_reserve(),
+            rewards_rate_model: Some(market.rewards_rate_model()),
+            f_token_mint: Some(market.f_token_mint()),
+            lending_program: market.lending_program,
+            liquidity_program: market.liquidity_program,
+        },
+        interact_dapp::instruction::PreviewEarnPosition {
+            principal,
+            deposit_assets,
+            withdraw_assets,
+        },
+    )
+}
+
+/// `earn_apy` of the market.
+pub fn earn_apy(market: &EarnMarket) -> Instruction {
+    program_instruction(
+        interact_dapp::accounts::EarnApyParams {
+            lending: market.lending(),
+            f_token_mint: market.f_token_mint(),
+            supply_token_reserves_liquidity: market.token_reserve(),
+            rewards_rate_model: market.rewards_rate_model(),
+            lending_program: market.lending_program,
+            liquidity_program: market.liquidity_program,
+        },
+        interact_dapp::instruction::EarnApy {},
+    )
+}
@@ -0,0 +1,123 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_spl::associated_token;
+use crate::pda::*;
+use super::{
+    clmm_swap_accounts, earn_accounts, program_instruction, program_instruction_with_remaining,
+    swap_remaining_accounts, ClmmPool,
+};
+
+/// `open_limit_order` of `owner` selling `amount` of `input_mint` for the other mint of
+/// `pool` at `sqrt_price_limit_x64` or better. `input_earn` is the Earn market of the input
+/// mint when the escrow is kept in Earn.
+pub fn open_limit_order(
+    pool: &ClmmPool,
+    input_mint: &Pubkey,
+    owner: &Pubkey,
+    order_id: u64,
+    input_earn: Option<&EarnMarket>,
+    amount: u64,
+    sqrt_price_limit_x64: u128,
+) -> Instruction {
+    let limit_order = get_limit_order_address(owner, order_id);
+    program_instruction_with_remaining(
+        interact_dapp::accounts::OpenLimitOrder {
+            owner: *owner,
+            config: get_config_address(),
+            limit_order,
+            input_mint: *input_mint,
+            output_mint: pool.other_mint(input_mint),
+            owner_input_account: pool.token_account(owner, input_mint),
+            input_vault: pool.token_account(&limit_order, input_mint),
+            pool_state: pool.pool_state,
+            lending: input_earn.map(|market| market.lending()),
+            lending_program: input_earn.map(|market| market.lending_program),
+            f_token_mint: input_earn.map(|market| market.f_token_mint()),
+            f_token_vault: input_earn.map(|market| market.f_token_account(&limit_order)),
+            deposit_caps: input_earn.map(|_| get_deposit_caps_address(input_mint)),
+            user_deposits: input_earn.map(|_| get_user_deposits_address(input_mint, owner)),
+            input_token_program: pool.token_program_of(input_mint),
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+        },
+        input_earn
+            .map(|market| earn_accounts(market).to_account_metas(None))
+            .unwrap_or_default(),
+        interact_dapp::instruction::OpenLimitOrder {
+            order_id,
+            amount,
+            sqrt_price_limit_x64,
+        },
+    )
+}
+
+/// `fill_limit_order` of an order opened by [`open_limit_order`] with the same `pool`,
+/// `input_mint` and `input_earn`, paying the output to the owner ATA. `tick_arrays` and
+/// `with_bitmap_extension` are the swap accounts of [`swap_remaining_accounts`].
+#[allow(clippy::too_many_arguments)]
+pub fn fill_limit_order(
+    pool: &ClmmPool,
+    input_mint: &Pubkey,
+    keeper: &Pubkey,
+    owner: &Pubkey,
+    order_id: u64,
+    input_earn: Option<&EarnMarket>,
+    tick_arrays: &[Pubkey],
+    with_bitmap_extension: bool,
+) -> Instruction {
+    let limit_order = get_limit_order_address(owner, order_id);
+    let mut remaining = swap_remaining_accounts(pool, tick_arrays, with_bitmap_extension);
+    let swap_count = remaining.len() as u8;
+    if let Some(market) = input_earn {
+        remaining.extend(earn_accounts(market).to_account_metas(None));
+    }
+    let earn_count = remaining.len() as u8 - swap_count;
+    program_instruction_with_remaining(
+        interact_dapp::accounts::FillLimitOrder {
+            keeper: *keeper,
+            config: get_config_address(),
+            limit_order,
+            input_vault: pool.token_account(&limit_order, input_mint),
+            owner_output_account: pool.token_account(owner, &pool.other_mint(input_mint)),
+            f_token_vault: input_earn.map(|market| market.f_token_account(&limit_order)),
+            claim_account: input_earn.map(|market| market.claim_account(&limit_order)),
+            deposit_caps: input_earn.map(|_| get_deposit_caps_address(input_mint)),
+            user_deposits: input_earn.map(|_| get_user_deposits_address(input_mint, owner)),
+            swap: clmm_swap_accounts(pool, input_mint),
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+        },
+        remaining,
+        interact_dapp::instruction::FillLimitOrder {
+            remaining_accounts_indices: vec![swap_count, earn_count],
+        },
+    )
+}
+
+/// `cancel_limit_order`: closes an order opened by [`open_limit_order`] with the same
+/// `pool`, `input_mint` and `input_earn`, returning the escrow to the owner ATAs.
+pub fn cancel_limit_order(
+    pool: &ClmmPool,
+    input_mint: &Pubkey,
+    owner: &Pubkey,
+    order_id: u64,
+    input_earn: Option<&EarnMarket>,
+) -> Instruction {
+    let limit_order = get_limit_order_address(owner, order_id);
+    program_instruction(
+        interact_dapp::accounts::CancelLimitOrder {
+            owner: *owner,
+            limit_order,
+            input_mint: *input_mint,
+            input_vault: pool.token_account(&limit_order, input_mint),
+            owner_input_account: pool.token_account(owner, input_mint),
+            f_token_mint: input_earn.map(|market| market.f_token_mint()),
+            f_token_vault: input_earn.map(|market| market.f_token_account(&limit_order)),
+            owner_f_token_account: input_earn.map(|market| market.f_token_account(owner)),
+            deposit_caps: input_earn.map(|_| get_deposit_caps_address(input_mint)),
+            user_deposits: input_earn.map(|_| get_user_deposits_address(input_mint, owner)),
+            input_token_program: pool.token_program_of(input_mint),
+        },
+        interact_dapp::instruction::CancelLimitOrder {},
+    )
+}
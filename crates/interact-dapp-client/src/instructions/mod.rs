@@ -0,0 +1,51 @@
+pub mod admin;
+pub mod allocator;
+pub mod dca;
+pub mod jupiter_borrow;
+pub mod jupiter_earn;
+pub mod limit_order;
+pub mod operator;
+pub mod raydium;
+pub mod strategy;
+pub mod trigger_order;
+
+pub use admin::*;
+pub use allocator::*;
+pub use dca::*;
+pub use jupiter_borrow::*;
+pub use jupiter_earn::*;
+pub use limit_order::*;
+pub use operator::*;
+pub use raydium::*;
+pub use strategy::*;
+pub use trigger_order::*;
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::InstructionData;
+
+// `Instruction` is the `solana_program` type re-exported by `solana_sdk::instruction`, so the
+// builders below can be added to a `solana_sdk::transaction::Transaction` as they are.
+
+fn program_instruction(accounts: impl ToAccountMetas, data: impl InstructionData) -> Instruction {
+    program_instruction_with_remaining(accounts, Vec::new(), data)
+}
+
+/// Instruction of `accounts` followed by the `remaining` accounts read by the handler.
+fn program_instruction_with_remaining(
+    accounts: impl ToAccountMetas,
+    remaining: Vec<AccountMeta>,
+    data: impl InstructionData,
+) -> Instruction {
+    let mut accounts = accounts.to_account_metas(None);
+    accounts.extend(remaining);
+    Instruction {
+        program_id: interact_dapp::ID,
+        accounts,
+        data: data.data(),
+    }
+}
+
+fn writable_metas(keys: &[Pubkey]) -> Vec<AccountMeta> {
+    keys.iter().map(|key| AccountMeta::new(*key, false)).collect()
+}
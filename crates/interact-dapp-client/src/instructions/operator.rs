@@ -0,0 +1,131 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_spl::associated_token;
+use crate::pda::*;
+use super::{earn_accounts, program_instruction};
+
+/// Parameters of `create_operator_delegation`, as taken by the program instruction.
+#[derive(Debug, Clone, Copy)]
+pub struct DelegationArgs {
+    /// `OperatorDelegation` permission flags
+    pub permissions: u8,
+    pub max_amount_per_period: u64,
+    pub period: i64,
+    pub expires_at: i64,
+}
+
+/// `create_operator_delegation` of `owner` to `operator` for the Earn market underlying.
+pub fn create_operator_delegation(
+    market: &EarnMarket,
+    owner: &Pubkey,
+    operator: &Pubkey,
+    args: DelegationArgs,
+) -> Instruction {
+    let delegation = get_operator_delegation_address(owner, operator, &market.mint);
+    program_instruction(
+        interact_dapp::accounts::CreateOperatorDelegation {
+            owner: *owner,
+            operator: *operator,
+            delegation,
+            mint: market.mint,
+            f_token_mint: market.f_token_mint(),
+            token_vault: market.token_account(&delegation),
+            f_token_vault: market.f_token_account(&delegation),
+            lending: market.lending(),
+            lending_program: market.lending_program,
+            token_program: market.token_program,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+        },
+        interact_dapp::instruction::CreateOperatorDelegation {
+            permissions: args.permissions,
+            max_amount_per_period: args.max_amount_per_period,
+            period: args.period,
+            expires_at: args.expires_at,
+        },
+    )
+}
+
+/// `revoke_operator_delegation`: closes the delegation, returning its vaults to the owner
+/// ATAs.
+pub fn revoke_operator_delegation(
+    market: &EarnMarket,
+    owner: &Pubkey,
+    operator: &Pubkey,
+) -> Instruction {
+    let delegation = get_operator_delegation_address(owner, operator, &market.mint);
+    program_instruction(
+        interact_dapp::accounts::RevokeOperatorDelegation {
+            owner: *owner,
+            delegation,
+            mint: market.mint,
+            f_token_mint: market.f_token_mint(),
+            token_vault: market.token_account(&delegation),
+            f_token_vault: market.f_token_account(&delegation),
+            owner_token_account: market.token_account(owner),
+            owner_f_token_account: market.f_token_account(owner),
+            token_program: market.token_program,
+        },
+        interact_dapp::instruction::RevokeOperatorDelegation {},
+    )
+}
+
+/// `operator_deposit_earn` of `amount` from the owner ATA into the owner fToken ATA, signed
+/// by the operator.
+pub fn operator_deposit_earn(
+    market: &EarnMarket,
+    owner: &Pubkey,
+    operator: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    let delegation = get_operator_delegation_address(owner, operator, &market.mint);
+    program_instruction(
+        interact_dapp::accounts::OperatorDepositEarn {
+            operator: *operator,
+            config: get_config_address(),
+            delegation,
+            mint: market.mint,
+            f_token_mint: market.f_token_mint(),
+            owner_token_account: market.token_account(owner),
+            owner_f_token_account: market.f_token_account(owner),
+            token_vault: market.token_account(&delegation),
+            f_token_vault: market.f_token_account(&delegation),
+            earn: earn_accounts(market),
+            deposit_caps: get_deposit_caps_address(&market.mint),
+            token_program: market.token_program,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+        },
+        interact_dapp::instruction::OperatorDepositEarn { amount },
+    )
+}
+
+/// `operator_withdraw_earn` of `assets` from the owner fToken ATA to the owner ATA, signed
+/// by the operator.
+pub fn operator_withdraw_earn(
+    market: &EarnMarket,
+    owner: &Pubkey,
+    operator: &Pubkey,
+    assets: u64,
+) -> Instruction {
+    let delegation = get_operator_delegation_address(owner, operator, &market.mint);
+    program_instruction(
+        interact_dapp::accounts::OperatorWithdrawEarn {
+            operator: *operator,
+            config: get_config_address(),
+            delegation,
+            mint: market.mint,
+            f_token_mint: market.f_token_mint(),
+            owner_token_account: market.token_account(owner),
+            owner_f_token_account: market.f_token_account(owner),
+            token_vault: market.token_account(&delegation),
+            f_token_vault: market.f_token_account(&delegation),
+            earn: earn_accounts(market),
+            claim_account: market.claim_account(&delegation),
+            token_program: market.token_program,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+        },
+        interact_dapp::instruction::OperatorWithdrawEarn { assets },
+    )
+}
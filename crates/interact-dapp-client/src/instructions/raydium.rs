@@ -0,0 +1,187 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_spl::associated_token::get_associated_token_address_with_program_id;
+use anchor_spl::{memo, token, token_2022};
+use crate::pda::*;
+use super::{program_instruction_with_remaining, writable_metas};
+
+/// Addresses of a Raydium CLMM pool, derived from its config and sorted mints.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClmmPool {
+    pub clmm_program: Pubkey,
+    pub amm_config: Pubkey,
+    pub pool_state: Pubkey,
+    pub token_mint_0: Pubkey,
+    pub token_mint_1: Pubkey,
+    pub token_program_0: Pubkey,
+    pub token_program_1: Pubkey,
+    pub tick_spacing: u16,
+}
+
+impl ClmmPool {
+    /// `token_mint_0` must sort before `token_mint_1`, as Raydium requires on pool creation.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        clmm_program: Pubkey,
+        amm_config_index: u16,
+        token_mint_0: Pubkey,
+        token_mint_1: Pubkey,
+        token_program_0: Pubkey,
+        token_program_1: Pubkey,
+        tick_spacing: u16,
+    ) -> Self {
+        let amm_config = get_amm_config_address(amm_config_index, &clmm_program);
+        Self {
+            clmm_program,
+            amm_config,
+            pool_state: get_pool_address(&amm_config, &token_mint_0, &token_mint_1, &clmm_program),
+            token_mint_0,
+            token_mint_1,
+            token_program_0,
+            token_program_1,
+            tick_spacing,
+        }
+    }
+
+    pub fn vault(&self, mint: &Pubkey) -> Pubkey {
+        get_pool_vault_address(&self.pool_state, mint, &self.clmm_program)
+    }
+
+    pub fn observation(&self) -> Pubkey {
+        get_observation_address(&self.pool_state, &self.clmm_program)
+    }
+
+    pub fn tick_array_bitmap_extension(&self) -> Pubkey {
+        get_tick_array_bitmap_extension_address(&self.pool_state, &self.clmm_program)
+    }
+
+    pub fn tick_array(&self, start_index: i32) -> Pubkey {
+        get_tick_array_address(&self.pool_state, start_index, &self.clmm_program)
+    }
+
+    /// The `count` tick arrays a swap starting at `tick_current` walks through, in order.
+    /// Tick arrays that are not initialized must be skipped by the caller.
+    pub fn swap_tick_arrays(
+        &self,
+        tick_current: i32,
+        zero_for_one: bool,
+        count: usize,
+    ) -> Vec<Pubkey> {
+        let ticks_in_array = TICK_ARRAY_SIZE * self.tick_spacing as i32;
+        let start = get_tick_array_start_index(tick_current, self.tick_spacing);
+        let step = if zero_for_one { -ticks_in_array } else { ticks_in_array };
+        (0..count as i32)
+            .map(|i| self.tick_array(start + i * step))
+            .collect()
+    }
+
+    /// The pool mint `mint` is swapped for.
+    pub fn other_mint(&self, mint: &Pubkey) -> Pubkey {
+        if *mint == self.token_mint_0 {
+            self.token_mint_1
+        } else {
+            self.token_mint_0
+        }
+    }
+
+    pub fn token_program_of(&self, mint: &Pubkey) -> Pubkey {
+        if *mint == self.token_mint_0 {
+            self.token_program_0
+        } else {
+            self.token_program_1
+        }
+    }
+
+    /// ATA of `owner` for the pool mint `mint`.
+    pub fn token_account(&self, owner: &Pubkey, mint: &Pubkey) -> Pubkey {
+        get_associated_token_address_with_program_id(owner, mint, &self.token_program_of(mint))
+    }
+}
+
+/// `ClmmSwapAccounts` of a swap of `input_mint` for the other mint of `pool`.
+pub fn clmm_swap_accounts(
+    pool: &ClmmPool,
+    input_mint: &Pubkey,
+) -> interact_dapp::accounts::ClmmSwapAccounts {
+    let output_mint = pool.other_mint(input_mint);
+    interact_dapp::accounts::ClmmSwapAccounts {
+        clmm_program: pool.clmm_program,
+        amm_config: pool.amm_config,
+        pool_state: pool.pool_state,
+        observation_state: pool.observation(),
+        input_vault: pool.vault(input_mint),
+        output_vault: pool.vault(&output_mint),
+        input_vault_mint: *input_mint,
+        output_vault_mint: output_mint,
+        token_program: token::ID,
+        token_program_2022: token_2022::ID,
+        memo_program: memo::ID,
+    }
+}
+
+/// Remaining accounts of a `swap_v2` crossing `tick_arrays`, see
+/// [`ClmmPool::swap_tick_arrays`]. The tick array bitmap extension is prepended when
+/// `with_bitmap_extension` is set, which is needed once the swap leaves the range covered by
+/// the pool bitmap.
+pub fn swap_remaining_accounts(
+    pool: &ClmmPool,
+    tick_arrays: &[Pubkey],
+    with_bitmap_extension: bool,
+) -> Vec<AccountMeta> {
+    let mut accounts = Vec::with_capacity(tick_arrays.len() + 1);
+    if with_bitmap_extension {
+        accounts.push(AccountMeta::new(pool.tick_array_bitmap_extension(), false));
+    }
+    accounts.extend(writable_metas(tick_arrays));
+    accounts
+}
+
+/// Parameters of `proxy_swap`, as taken by the program instruction.
+#[derive(Debug, Clone, Copy)]
+pub struct SwapArgs {
+    pub amount: u64,
+    pub other_amount_threshold: u64,
+    pub sqrt_price_limit_x64: u128,
+    pub is_base_input: bool,
+}
+
+/// `proxy_swap` of `input_mint` for the other pool mint between the payer ATAs.
+///
+/// `tick_arrays` and `with_bitmap_extension` are the swap accounts of
+/// [`swap_remaining_accounts`].
+pub fn proxy_swap(
+    pool: &ClmmPool,
+    payer: &Pubkey,
+    input_mint: &Pubkey,
+    tick_arrays: &[Pubkey],
+    with_bitmap_extension: bool,
+    args: SwapArgs,
+) -> Instruction {
+    let output_mint = pool.other_mint(input_mint);
+    program_instruction_with_remaining(
+        interact_dapp::accounts::ProxySwap {
+            clmm_program: pool.clmm_program,
+            payer: *payer,
+            config: get_config_address(),
+            amm_config: pool.amm_config,
+            pool_state: pool.pool_state,
+            input_token_account: pool.token_account(payer, input_mint),
+            output_token_account: pool.token_account(payer, &output_mint),
+            input_vault: pool.vault(input_mint),
+            output_vault: pool.vault(&output_mint),
+            observation_state: pool.observation(),
+            token_program: token::ID,
+            token_program_2022: token_2022::ID,
+            memo_program: memo::ID,
+            input_vault_mint: *input_mint,
+            output_vault_mint: output_mint,
+        },
+        swap_remaining_accounts(pool, tick_arrays, with_bitmap_extension),
+        interact_dapp::instruction::ProxySwap {
+            amount: args.amount,
+            other_amount_threshold: args.other_amount_threshold,
+            sqrt_price_limit_x64: args.sqrt_price_limit_x64,
+            is_base_input: args.is_base_input,
+        },
+    )
+}
@@ -0,0 +1,437 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::sysvar;
+use anchor_spl::associated_token::{self, get_associated_token_address_with_program_id};
+use anchor_spl::{memo, token, token_2022};
+use crate::pda::*;
+use super::{
+    earn_accounts, program_instruction, program_instruction_with_remaining,
+    swap_remaining_accounts, writable_metas, ClmmPool,
+};
+
+/// Addresses of the strategy providing liquidity with the underlying of `market` on `pool`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClmmStrategy {
+    pub market: EarnMarket,
+    pub pool: ClmmPool,
+}
+
+impl ClmmStrategy {
+    pub fn new(market: EarnMarket, pool: ClmmPool) -> Self {
+        Self { market, pool }
+    }
+
+    pub fn address(&self) -> Pubkey {
+        get_strategy_address(&self.pool.pool_state, &self.market.mint)
+    }
+
+    pub fn share_mint(&self) -> Pubkey {
+        get_strategy_share_mint_address(&self.address())
+    }
+
+    /// The pool mint other than the base mint
+    pub fn pair_mint(&self) -> Pubkey {
+        self.pool.other_mint(&self.market.mint)
+    }
+
+    pub fn base_vault(&self) -> Pubkey {
+        self.market.token_account(&self.address())
+    }
+
+    pub fn pair_vault(&self) -> Pubkey {
+        self.pool.token_account(&self.address(), &self.pair_mint())
+    }
+
+    pub fn f_token_vault(&self) -> Pubkey {
+        self.market.f_token_account(&self.address())
+    }
+
+    /// Share ATA of `owner`
+    pub fn share_account(&self, owner: &Pubkey) -> Pubkey {
+        get_associated_token_address_with_program_id(
+            owner,
+            &self.share_mint(),
+            &self.market.token_program,
+        )
+    }
+}
+
+/// A CLMM position of a strategy, identified by its NFT mint.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StrategyPosition {
+    pub nft_mint: Pubkey,
+    pub tick_lower_index: i32,
+    pub tick_upper_index: i32,
+}
+
+impl StrategyPosition {
+    pub fn personal_position(&self, pool: &ClmmPool) -> Pubkey {
+        get_personal_position_address(&self.nft_mint, &pool.clmm_program)
+    }
+
+    pub fn protocol_position(&self, pool: &ClmmPool) -> Pubkey {
+        get_protocol_position_address(
+            &pool.pool_state,
+            self.tick_lower_index,
+            self.tick_upper_index,
+            &pool.clmm_program,
+        )
+    }
+
+    pub fn tick_array_lower_start_index(&self, pool: &ClmmPool) -> i32 {
+        get_tick_array_start_index(self.tick_lower_index, pool.tick_spacing)
+    }
+
+    pub fn tick_array_upper_start_index(&self, pool: &ClmmPool) -> i32 {
+        get_tick_array_start_index(self.tick_upper_index, pool.tick_spacing)
+    }
+
+    /// NFT account of the strategy, created by Raydium with the SPL Token program
+    pub fn nft_account(&self, strategy: &Pubkey) -> Pubkey {
+        get_associated_token_address_with_program_id(strategy, &self.nft_mint, &token::ID)
+    }
+}
+
+/// `initialize_strategy`, restricted to the config admin.
+pub fn initialize_strategy(
+    strategy: &ClmmStrategy,
+    admin: &Pubkey,
+    compound_interval: i64,
+    max_slippage_bps: u16,
+) -> Instruction {
+    let (market, pool) = (&strategy.market, &strategy.pool);
+    let pair_mint = strategy.pair_mint();
+    program_instruction(
+        interact_dapp::accounts::InitializeStrategy {
+            admin: *admin,
+            config: get_config_address(),
+            strategy: strategy.address(),
+            share_mint: strategy.share_mint(),
+            pool_state: pool.pool_state,
+            base_mint: market.mint,
+            pair_mint,
+            f_token_mint: market.f_token_mint(),
+            base_vault: strategy.base_vault(),
+            pair_vault: strategy.pair_vault(),
+            f_token_vault: strategy.f_token_vault(),
+            lending: market.lending(),
+            lending_program: market.lending_program,
+            token_program: market.token_program,
+            pair_token_program: pool.token_program_of(&pair_mint),
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+        },
+        interact_dapp::instruction::InitializeStrategy {
+            compound_interval,
+            max_slippage_bps,
+        },
+    )
+}
+
+/// `open_strategy_position`: opens `position` for the strategy, funded from the authority
+/// ATAs. `position.nft_mint` is a new keypair that signs the transaction.
+pub fn open_strategy_position(
+    strategy: &ClmmStrategy,
+    authority: &Pubkey,
+    position: &StrategyPosition,
+    liquidity: u128,
+    base_amount_max: u64,
+    pair_amount_max: u64,
+    with_bitmap_extension: bool,
+) -> Instruction {
+    let (market, pool) = (&strategy.market, &strategy.pool);
+    let address = strategy.address();
+    let pair_mint = strategy.pair_mint();
+    let tick_array_lower_start_index = position.tick_array_lower_start_index(pool);
+    let tick_array_upper_start_index = position.tick_array_upper_start_index(pool);
+    program_instruction_with_remaining(
+        interact_dapp::accounts::OpenStrategyPosition {
+            clmm_program: pool.clmm_program,
+            authority: *authority,
+            config: get_config_address(),
+            strategy: address,
+            share_mint: strategy.share_mint(),
+            base_mint: market.mint,
+            pair_mint,
+            authority_base_account: market.token_account(authority),
+            authority_pair_account: pool.token_account(authority, &pair_mint),
+            authority_share_account: strategy.share_account(authority),
+            base_vault: strategy.base_vault(),
+            pair_vault: strategy.pair_vault(),
+            f_token_vault: strategy.f_token_vault(),
+            pool_state: pool.pool_state,
+            observation_state: pool.observation(),
+            pool_base_vault: pool.vault(&market.mint),
+            pool_pair_vault: pool.vault(&pair_mint),
+            position_nft_mint: position.nft_mint,
+            position_nft_account: position.nft_account(&address),
+            metadata_account: get_metadata_address(&position.nft_mint),
+            protocol_position: position.protocol_position(pool),
+            tick_array_lower: pool.tick_array(tick_array_lower_start_index),
+            tick_array_upper: pool.tick_array(tick_array_upper_start_index),
+            personal_position: position.personal_position(pool),
+            lending: market.lending(),
+            lending_program: market.lending_program,
+            rent: sysvar::rent::ID,
+            system_program: system_program::ID,
+            token_program: token::ID,
+            token_program_2022: token_2022::ID,
+            associated_token_program: associated_token::ID,
+            metadata_program: METADATA_PROGRAM_ID,
+        },
+        swap_remaining_accounts(pool, &[], with_bitmap_extension),
+        interact_dapp::instruction::OpenStrategyPosition {
+            tick_lower_index: position.tick_lower_index,
+            tick_upper_index: position.tick_upper_index,
+            tick_array_lower_start_index,
+            tick_array_upper_start_index,
+            liquidity,
+            base_amount_max,
+            pair_amount_max,
+        },
+    )
+}
+
+/// `deposit_strategy` of `amount` of the base token from the depositor ATA, minting the
+/// shares to `share_account`. `position` is the open position of the strategy, if any.
+pub fn deposit_strategy(
+    strategy: &ClmmStrategy,
+    depositor: &Pubkey,
+    share_account: &Pubkey,
+    position: Option<&StrategyPosition>,
+    amount: u64,
+) -> Instruction {
+    let (market, pool) = (&strategy.market, &strategy.pool);
+    program_instruction(
+        interact_dapp::accounts::DepositStrategy {
+            depositor: *depositor,
+            config: get_config_address(),
+            strategy: strategy.address(),
+            share_mint: strategy.share_mint(),
+            base_mint: market.mint,
+            depositor_token_account: market.token_account(depositor),
+            depositor_share_account: *share_account,
+            base_vault: strategy.base_vault(),
+            pair_vault: strategy.pair_vault(),
+            f_token_vault: strategy.f_token_vault(),
+            pool_state: pool.pool_state,
+            observation_state: pool.observation(),
+            personal_position: position.map(|position| position.personal_position(pool)),
+            lending: market.lending(),
+            lending_program: market.lending_program,
+            deposit_caps: get_deposit_caps_address(&market.mint),
+            user_deposits: get_user_deposits_address(&market.mint, depositor),
+            token_program: market.token_program,
+            system_program: system_program::ID,
+        },
+        interact_dapp::instruction::DepositStrategy { amount },
+    )
+}
+
+/// Accounts of `withdraw_strategy` that depend on the strategy and owner state.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct WithdrawStrategyOptions<'a> {
+    /// Open position of the strategy, unwound when the vaults cannot cover the withdrawal
+    pub position: Option<&'a StrategyPosition>,
+    /// Reward token accounts `decrease_liquidity_v2` expects for `position`
+    pub reward_accounts: &'a [Pubkey],
+    /// Pair token account paid the value the base balance cannot cover, required once the
+    /// withdrawal reaches the pair vault
+    pub recipient_pair_account: Option<Pubkey>,
+    /// Set when the owner received the shares by transfer and has no deposit record
+    pub without_user_deposits: bool,
+}
+
+/// `withdraw_strategy`: burns `shares` of the owner share ATA, paying the base token to the
+/// owner ATA.
+pub fn withdraw_strategy(
+    strategy: &ClmmStrategy,
+    owner: &Pubkey,
+    shares: u64,
+    options: WithdrawStrategyOptions,
+) -> Instruction {
+    let (market, pool) = (&strategy.market, &strategy.pool);
+    let address = strategy.address();
+    let pair_mint = strategy.pair_mint();
+    let position = options.position;
+    program_instruction_with_remaining(
+        interact_dapp::accounts::WithdrawStrategy {
+            owner: *owner,
+            config: get_config_address(),
+            strategy: address,
+            share_mint: strategy.share_mint(),
+            base_mint: market.mint,
+            pair_mint,
+            owner_share_account: strategy.share_account(owner),
+            recipient_token_account: market.token_account(owner),
+            recipient_pair_account: options.recipient_pair_account,
+            base_vault: strategy.base_vault(),
+            pair_vault: strategy.pair_vault(),
+            f_token_vault: strategy.f_token_vault(),
+            clmm_program: pool.clmm_program,
+            pool_state: pool.pool_state,
+            observation_state: pool.observation(),
+            personal_position: position.map(|position| position.personal_position(pool)),
+            position_nft_account: position.map(|position| position.nft_account(&address)),
+            protocol_position: position.map(|position| position.protocol_position(pool)),
+            tick_array_lower: position
+                .map(|position| pool.tick_array(position.tick_array_lower_start_index(pool))),
+            tick_array_upper: position
+                .map(|position| pool.tick_array(position.tick_array_upper_start_index(pool))),
+            pool_base_vault: position.map(|_| pool.vault(&market.mint)),
+            pool_pair_vault: position.map(|_| pool.vault(&pair_mint)),
+            earn: earn_accounts(market),
+            claim_account: market.claim_account(&address),
+            deposit_caps: get_deposit_caps_address(&market.mint),
+            user_deposits: (!options.without_user_deposits)
+                .then(|| get_user_deposits_address(&market.mint, owner)),
+            token_program: market.token_program,
+            pair_token_program: pool.token_program_of(&pair_mint),
+            token_program_2022: token_2022::ID,
+            memo_program: memo::ID,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+        },
+        writable_metas(options.reward_accounts),
+        interact_dapp::instruction::WithdrawStrategy { shares },
+    )
+}
+
+/// `crank_compound` of the strategy. `remaining` are the accounts of the step due: the
+/// reward token accounts of `position` for the harvest, or the [`swap_remaining_accounts`]
+/// for the swap.
+pub fn crank_compound(
+    strategy: &ClmmStrategy,
+    cranker: &Pubkey,
+    position: Option<&StrategyPosition>,
+    remaining: Vec<AccountMeta>,
+) -> Instruction {
+    let (market, pool) = (&strategy.market, &strategy.pool);
+    let address = strategy.address();
+    let pair_mint = strategy.pair_mint();
+    program_instruction_with_remaining(
+        interact_dapp::accounts::CrankCompound {
+            clmm_program: pool.clmm_program,
+            cranker: *cranker,
+            config: get_config_address(),
+            strategy: address,
+            base_mint: market.mint,
+            pair_mint,
+            base_vault: strategy.base_vault(),
+            pair_vault: strategy.pair_vault(),
+            f_token_vault: strategy.f_token_vault(),
+            amm_config: pool.amm_config,
+            pool_state: pool.pool_state,
+            observation_state: pool.observation(),
+            pool_base_vault: pool.vault(&market.mint),
+            pool_pair_vault: pool.vault(&pair_mint),
+            personal_position: position.map(|position| position.personal_position(pool)),
+            position_nft_account: position.map(|position| position.nft_account(&address)),
+            protocol_position: position.map(|position| position.protocol_position(pool)),
+            tick_array_lower: position
+                .map(|position| pool.tick_array(position.tick_array_lower_start_index(pool))),
+            tick_array_upper: position
+                .map(|position| pool.tick_array(position.tick_array_upper_start_index(pool))),
+            earn: earn_accounts(market),
+            deposit_caps: get_deposit_caps_address(&market.mint),
+            token_program: token::ID,
+            token_program_2022: token_2022::ID,
+            memo_program: memo::ID,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+        },
+        remaining,
+        interact_dapp::instruction::CrankCompound {},
+    )
+}
+
+/// `set_rebalance_config` of the strategy at `strategy`, signed by its authority.
+pub fn set_rebalance_config(
+    strategy: &Pubkey,
+    authority: &Pubkey,
+    range_width: i32,
+    twap_window: u32,
+    max_tick_deviation: u32,
+) -> Instruction {
+    program_instruction(
+        interact_dapp::accounts::SetRebalanceConfig {
+            authority: *authority,
+            strategy: *strategy,
+        },
+        interact_dapp::instruction::SetRebalanceConfig {
+            range_width,
+            twap_window,
+            max_tick_deviation,
+        },
+    )
+}
+
+/// `rebalance_range`: closes `position` and opens `new_position`, whose NFT mint is a new
+/// keypair that signs the transaction.
+///
+/// `reward_accounts` are the reward token accounts of `position`, `tick_arrays` the tick
+/// arrays crossed by the swap to the new range. The bitmap extension, when
+/// `with_bitmap_extension` is set, is passed to both the swap and the new position.
+pub fn rebalance_range(
+    strategy: &ClmmStrategy,
+    keeper: &Pubkey,
+    position: &StrategyPosition,
+    new_position: &StrategyPosition,
+    reward_accounts: &[Pubkey],
+    tick_arrays: &[Pubkey],
+    with_bitmap_extension: bool,
+) -> Instruction {
+    let (market, pool) = (&strategy.market, &strategy.pool);
+    let address = strategy.address();
+    let pair_mint = strategy.pair_mint();
+    let swap = swap_remaining_accounts(pool, tick_arrays, with_bitmap_extension);
+    let open = swap_remaining_accounts(pool, &[], with_bitmap_extension);
+    let remaining_accounts_indices =
+        vec![reward_accounts.len() as u8, swap.len() as u8, open.len() as u8];
+    let mut remaining = writable_metas(reward_accounts);
+    remaining.extend(swap);
+    remaining.extend(open);
+    program_instruction_with_remaining(
+        interact_dapp::accounts::RebalanceRange {
+            clmm_program: pool.clmm_program,
+            keeper: *keeper,
+            config: get_config_address(),
+            strategy: address,
+            base_mint: market.mint,
+            pair_mint,
+            base_vault: strategy.base_vault(),
+            pair_vault: strategy.pair_vault(),
+            amm_config: pool.amm_config,
+            pool_state: pool.pool_state,
+            observation_state: pool.observation(),
+            pool_base_vault: pool.vault(&market.mint),
+            pool_pair_vault: pool.vault(&pair_mint),
+            position_nft_mint: position.nft_mint,
+            position_nft_account: position.nft_account(&address),
+            personal_position: position.personal_position(pool),
+            protocol_position: position.protocol_position(pool),
+            tick_array_lower: pool.tick_array(position.tick_array_lower_start_index(pool)),
+            tick_array_upper: pool.tick_array(position.tick_array_upper_start_index(pool)),
+            new_position_nft_mint: new_position.nft_mint,
+            new_position_nft_account: new_position.nft_account(&address),
+            new_metadata_account: get_metadata_address(&new_position.nft_mint),
+            new_protocol_position: new_position.protocol_position(pool),
+            new_tick_array_lower: pool
+                .tick_array(new_position.tick_array_lower_start_index(pool)),
+            new_tick_array_upper: pool
+                .tick_array(new_position.tick_array_upper_start_index(pool)),
+            new_personal_position: new_position.personal_position(pool),
+            rent: sysvar::rent::ID,
+            system_program: system_program::ID,
+            token_program: token::ID,
+            token_program_2022: token_2022::ID,
+            memo_program: memo::ID,
+            associated_token_program: associated_token::ID,
+            metadata_program: METADATA_PROGRAM_ID,
+        },
+        remaining,
+        interact_dapp::instruction::RebalanceRange {
+            remaining_accounts_indices,
+        },
+    )
+}
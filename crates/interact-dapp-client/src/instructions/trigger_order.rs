@@ -0,0 +1,116 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_spl::associated_token;
+use interact_dapp::states::TriggerKind;
+use crate::pda::*;
+use super::{
+    clmm_swap_accounts, earn_accounts, program_instruction, program_instruction_with_remaining,
+    swap_remaining_accounts, ClmmPool,
+};
+
+/// Parameters of `open_trigger_order`, as taken by the program instruction.
+#[derive(Debug, Clone, Copy)]
+pub struct TriggerOrderArgs {
+    pub shares: u64,
+    pub kind: TriggerKind,
+    pub trigger_tick: i32,
+    pub twap_window: u32,
+    pub max_slippage_bps: u16,
+}
+
+/// `open_trigger_order` of `owner` escrowing fTokens of `market` from the owner fToken ATA,
+/// to be sold for the other mint of `pool` once the pool TWAP reaches the trigger.
+pub fn open_trigger_order(
+    market: &EarnMarket,
+    pool: &ClmmPool,
+    owner: &Pubkey,
+    order_id: u64,
+    args: TriggerOrderArgs,
+) -> Instruction {
+    let trigger_order = get_trigger_order_address(owner, order_id);
+    program_instruction(
+        interact_dapp::accounts::OpenTriggerOrder {
+            owner: *owner,
+            config: get_config_address(),
+            trigger_order,
+            mint: market.mint,
+            f_token_mint: market.f_token_mint(),
+            output_mint: pool.other_mint(&market.mint),
+            owner_f_token_account: market.f_token_account(owner),
+            f_token_vault: market.f_token_account(&trigger_order),
+            token_vault: market.token_account(&trigger_order),
+            pool_state: pool.pool_state,
+            lending: market.lending(),
+            lending_program: market.lending_program,
+            deposit_caps: get_deposit_caps_address(&market.mint),
+            user_deposits: get_user_deposits_address(&market.mint, owner),
+            token_program: market.token_program,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+        },
+        interact_dapp::instruction::OpenTriggerOrder {
+            order_id,
+            shares: args.shares,
+            kind: args.kind,
+            trigger_tick: args.trigger_tick,
+            twap_window: args.twap_window,
+            max_slippage_bps: args.max_slippage_bps,
+        },
+    )
+}
+
+/// `execute_trigger` of an order opened by [`open_trigger_order`] with the same `market`
+/// and `pool`, paying the output to the owner ATA. `tick_arrays` and
+/// `with_bitmap_extension` are the swap accounts of [`swap_remaining_accounts`].
+pub fn execute_trigger(
+    market: &EarnMarket,
+    pool: &ClmmPool,
+    keeper: &Pubkey,
+    owner: &Pubkey,
+    order_id: u64,
+    tick_arrays: &[Pubkey],
+    with_bitmap_extension: bool,
+) -> Instruction {
+    let trigger_order = get_trigger_order_address(owner, order_id);
+    program_instruction_with_remaining(
+        interact_dapp::accounts::ExecuteTrigger {
+            keeper: *keeper,
+            config: get_config_address(),
+            trigger_order,
+            f_token_vault: market.f_token_account(&trigger_order),
+            token_vault: market.token_account(&trigger_order),
+            owner_output_account: pool.token_account(owner, &pool.other_mint(&market.mint)),
+            earn: earn_accounts(market),
+            claim_account: market.claim_account(&trigger_order),
+            deposit_caps: get_deposit_caps_address(&market.mint),
+            user_deposits: get_user_deposits_address(&market.mint, owner),
+            swap: clmm_swap_accounts(pool, &market.mint),
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+        },
+        swap_remaining_accounts(pool, tick_arrays, with_bitmap_extension),
+        interact_dapp::instruction::ExecuteTrigger {},
+    )
+}
+
+/// `cancel_trigger_order`: closes an order of `market`, returning the escrow to the owner
+/// ATAs.
+pub fn cancel_trigger_order(market: &EarnMarket, owner: &Pubkey, order_id: u64) -> Instruction {
+    let trigger_order = get_trigger_order_address(owner, order_id);
+    program_instruction(
+        interact_dapp::accounts::CancelTriggerOrder {
+            owner: *owner,
+            trigger_order,
+            mint: market.mint,
+            f_token_mint: market.f_token_mint(),
+            f_token_vault: market.f_token_account(&trigger_order),
+            token_vault: market.token_account(&trigger_order),
+            owner_f_token_account: market.f_token_account(owner),
+            owner_token_account: market.token_account(owner),
+            deposit_caps: get_deposit_caps_address(&market.mint),
+            user_deposits: get_user_deposits_address(&market.mint, owner),
+            token_program: market.token_program,
+        },
+        interact_dapp::instruction::CancelTriggerOrder {},
+    )
+}
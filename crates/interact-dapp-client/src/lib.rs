@@ -0,0 +1,15 @@
+pub mod compute_budget;
+pub mod events;
+pub mod fixture;
+pub mod instructions;
+pub mod lookup_table;
+pub mod pda;
+pub mod quote;
+
+pub use compute_budget::*;
+pub use events::*;
+pub use fixture::*;
+pub use instructions::*;
+pub use lookup_table::*;
+pub use pda::*;
+pub use quote::*;
@@ -0,0 +1,119 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_spl::{associated_token, memo, token_2022};
+use solana_sdk::address_lookup_table::instruction::{create_lookup_table, extend_lookup_table};
+use solana_sdk::address_lookup_table::AddressLookupTableAccount;
+use solana_sdk::hash::Hash;
+use solana_sdk::message::{v0, CompileError, VersionedMessage};
+use solana_sdk::transaction::VersionedTransaction;
+use crate::instructions::ClmmPool;
+use crate::pda::EarnMarket;
+
+/// Addresses added per `extend_lookup_table`, keeping the extend transaction under the
+/// packet size
+pub const MAX_ADDRESSES_PER_EXTEND: usize = 30;
+
+/// Accounts of an Earn market that do not depend on the user: the lending and liquidity
+/// accounts, the programs and interact_dapp itself.
+pub fn get_earn_lookup_table_addresses(market: &EarnMarket) -> Vec<Pubkey> {
+    vec![
+        interact_dapp::ID,
+        market.lending_program,
+        market.liquidity_program,
+        market.mint,
+        market.f_token_mint(),
+        market.lending_admin(),
+        market.lending(),
+        market.rewards_rate_model(),
+        market.liquidity(),
+        market.token_reserve(),
+        market.supply_position(),
+        market.rate_model(),
+        market.vault(),
+        market.token_program,
+        associated_token::ID,
+        system_program::ID,
+    ]
+}
+
+/// Accounts of a CLMM pool read by every swap: the pool, its config, mints, vaults,
+/// observation and bitmap extension, and the programs of `swap_v2`. Tick arrays move with
+/// the price and are left out.
+pub fn get_pool_lookup_table_addresses(pool: &ClmmPool) -> Vec<Pubkey> {
+    vec![
+        interact_dapp::ID,
+        pool.clmm_program,
+        pool.amm_config,
+        pool.pool_state,
+        pool.token_mint_0,
+        pool.token_mint_1,
+        pool.vault(&pool.token_mint_0),
+        pool.vault(&pool.token_mint_1),
+        pool.observation(),
+        pool.tick_array_bitmap_extension(),
+        pool.token_program_0,
+        pool.token_program_1,
+        token_2022::ID,
+        memo::ID,
+    ]
+}
+
+/// Instructions creating a lookup table of `addresses`, deduplicated, and its address. The
+/// first instruction creates the table and the following ones extend it by
+/// [`MAX_ADDRESSES_PER_EXTEND`], each to be sent in its own transaction. `recent_slot` has
+/// to be a slot in the `SlotHashes` sysvar, usually the latest finalized one.
+pub fn create_market_lookup_table(
+    authority: &Pubkey,
+    payer: &Pubkey,
+    recent_slot: u64,
+    addresses: &[Pubkey],
+) -> (Pubkey, Vec<Instruction>) {
+    let (create_ix, lookup_table) = create_lookup_table(*authority, *payer, recent_slot);
+    let mut instructions = vec![create_ix];
+    instructions.extend(extend_market_lookup_table(
+        &lookup_table,
+        authority,
+        payer,
+        &[],
+        addresses,
+    ));
+    (lookup_table, instructions)
+}
+
+/// Instructions adding the `addresses` missing from the `existing` ones of `lookup_table`,
+/// by [`MAX_ADDRESSES_PER_EXTEND`].
+pub fn extend_market_lookup_table(
+    lookup_table: &Pubkey,
+    authority: &Pubkey,
+    payer: &Pubkey,
+    existing: &[Pubkey],
+    addresses: &[Pubkey],
+) -> Vec<Instruction> {
+    let mut new_addresses: Vec<Pubkey> = Vec::with_capacity(addresses.len());
+    for address in addresses {
+        if !existing.contains(address) && !new_addresses.contains(address) {
+            new_addresses.push(*address);
+        }
+    }
+    new_addresses
+        .chunks(MAX_ADDRESSES_PER_EXTEND)
+        .map(|chunk| extend_lookup_table(*lookup_table, *authority, Some(*payer), chunk.to_vec()))
+        .collect()
+}
+
+/// Compiles `instructions` into a v0 message paid by `payer`, resolving the accounts found
+/// in `lookup_tables` through them. Sign it with `VersionedTransaction::try_new`.
+pub fn compile_v0_message(
+    payer: &Pubkey,
+    instructions: &[Instruction],
+    lookup_tables: &[AddressLookupTableAccount],
+    recent_blockhash: Hash,
+) -> std::result::Result<VersionedMessage, CompileError> {
+    let message = v0::Message::try_compile(payer, instructions, lookup_tables, recent_blockhash)?;
+    Ok(VersionedMessage::V0(message))
+}
+
+/// Serialized size of `transaction`, to compare with `PACKET_DATA_SIZE`.
+pub fn transaction_size(transaction: &VersionedTransaction) -> usize {
+    bincode::serialized_size(transaction).expect("transaction serializes") as usize
+}
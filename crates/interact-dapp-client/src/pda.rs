@@ -0,0 +1,274 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::get_associated_token_address_with_program_id;
+use interact_dapp::constant::{
+    ALLOCATOR_SEED, CONFIG_SEED, DCA_ORDER_SEED, DEPOSIT_CAPS_SEED, LIMIT_ORDER_SEED,
+    OPERATOR_DELEGATION_SEED, STRATEGY_SEED, STRATEGY_SHARE_MINT_SEED, TRIGGER_ORDER_SEED,
+    USER_DEPOSITS_SEED,
+};
+
+// Program addresses of the cluster interact_dapp is built for
+pub use interact_dapp::programs::{
+    JUPITER_LENDING_PROGRAM_ID, JUPITER_LIQUIDITY_PROGRAM_ID, JUPITER_VAULTS_PROGRAM_ID,
+    RAYDIUM_CLMM_PROGRAM_ID,
+};
+
+/// Ticks per Raydium tick array
+pub const TICK_ARRAY_SIZE: i32 = 60;
+
+// Jupiter lending seeds
+pub const LENDING_ADMIN_SEED: &[u8] = b"lending_admin";
+pub const F_TOKEN_MINT_SEED: &[u8] = b"f_token_mint";
+pub const LENDING_SEED: &[u8] = b"lending";
+pub const LENDING_REWARDS_RATE_MODEL_SEED: &[u8] = b"lending_rewards_rate_model";
+
+// Jupiter liquidity seeds
+pub const LIQUIDITY_SEED: &[u8] = b"liquidity";
+pub const USER_SUPPLY_POSITION_SEED: &[u8] = b"user_supply_position";
+pub const TOKEN_RESERVE_SEED: &[u8] = b"reserve";
+pub const RATE_MODEL_SEED: &[u8] = b"rate_model";
+pub const USER_CLAIM_SEED: &[u8] = b"user_claim";
+
+// Raydium CLMM seeds
+pub const AMM_CONFIG_SEED: &[u8] = b"amm_config";
+pub const POOL_SEED: &[u8] = b"pool";
+pub const POOL_VAULT_SEED: &[u8] = b"pool_vault";
+pub const POSITION_SEED: &[u8] = b"position";
+pub const TICK_ARRAY_SEED: &[u8] = b"tick_array";
+pub const OBSERVATION_SEED: &[u8] = b"observation";
+pub const POOL_TICK_ARRAY_BITMAP_SEED: &[u8] = b"pool_tick_array_bitmap_extension";
+
+/// Metaplex token metadata program, which keeps the metadata of CLMM position NFTs
+pub const METADATA_PROGRAM_ID: Pubkey = pubkey!("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s");
+pub const METADATA_SEED: &[u8] = b"metadata";
+
+fn find(seeds: &[&[u8]], program_id: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(seeds, program_id).0
+}
+
+/// Addresses of a Jupiter Earn lending market, derived from its underlying mint.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EarnMarket {
+    pub lending_program: Pubkey,
+    pub liquidity_program: Pubkey,
+    pub mint: Pubkey,
+    /// Token program of `mint`, also used for the fToken mint
+    pub token_program: Pubkey,
+}
+
+impl EarnMarket {
+    pub fn new(mint: Pubkey, token_program: Pubkey) -> Self {
+        Self {
+            lending_program: JUPITER_LENDING_PROGRAM_ID,
+            liquidity_program: JUPITER_LIQUIDITY_PROGRAM_ID,
+            mint,
+            token_program,
+        }
+    }
+
+    pub fn lending_admin(&self) -> Pubkey {
+        find(&[LENDING_ADMIN_SEED], &self.lending_program)
+    }
+
+    pub fn f_token_mint(&self) -> Pubkey {
+        find(&[F_TOKEN_MINT_SEED, self.mint.as_ref()], &self.lending_program)
+    }
+
+    pub fn lending(&self) -> Pubkey {
+        find(
+            &[LENDING_SEED, self.mint.as_ref(), self.f_token_mint().as_ref()],
+            &self.lending_program,
+        )
+    }
+
+    pub fn rewards_rate_model(&self) -> Pubkey {
+        find(
+            &[LENDING_REWARDS_RATE_MODEL_SEED, self.mint.as_ref()],
+            &self.lending_program,
+        )
+    }
+
+    pub fn liquidity(&self) -> Pubkey {
+        find(&[LIQUIDITY_SEED], &self.liquidity_program)
+    }
+
+    pub fn token_reserve(&self) -> Pubkey {
+        find(&[TOKEN_RESERVE_SEED, self.mint.as_ref()], &self.liquidity_program)
+    }
+
+    /// Supply position of the lending market on the liquidity layer
+    pub fn supply_position(&self) -> Pubkey {
+        find(
+            &[USER_SUPPLY_POSITION_SEED, self.mint.as_ref(), self.lending().as_ref()],
+            &self.liquidity_program,
+        )
+    }
+
+    pub fn rate_model(&self) -> Pubkey {
+        find(&[RATE_MODEL_SEED, self.mint.as_ref()], &self.liquidity_program)
+    }
+
+    /// Liquidity layer token vault, the ATA of the liquidity account
+    pub fn vault(&self) -> Pubkey {
+        get_associated_token_address_with_program_id(
+            &self.liquidity(),
+            &self.mint,
+            &self.token_program,
+        )
+    }
+
+    pub fn claim_account(&self, user: &Pubkey) -> Pubkey {
+        find(
+            &[USER_CLAIM_SEED, user.as_ref(), self.mint.as_ref()],
+            &self.liquidity_program,
+        )
+    }
+
+    /// Underlying ATA of `owner`, the Earn depositor / recipient account
+    pub fn token_account(&self, owner: &Pubkey) -> Pubkey {
+        get_associated_token_address_with_program_id(owner, &self.mint, &self.token_program)
+    }
+
+    /// fToken ATA of `owner`
+    pub fn f_token_account(&self, owner: &Pubkey) -> Pubkey {
+        get_associated_token_address_with_program_id(
+            owner,
+            &self.f_token_mint(),
+            &self.token_program,
+        )
+    }
+}
+
+pub fn get_amm_config_address(index: u16, clmm_program: &Pubkey) -> Pubkey {
+    find(&[AMM_CONFIG_SEED, &index.to_be_bytes()], clmm_program)
+}
+
+pub fn get_pool_address(
+    amm_config: &Pubkey,
+    token_mint_0: &Pubkey,
+    token_mint_1: &Pubkey,
+    clmm_program: &Pubkey,
+) -> Pubkey {
+    find(
+        &[
+            POOL_SEED,
+            amm_config.as_ref(),
+            token_mint_0.as_ref(),
+            token_mint_1.as_ref(),
+        ],
+        clmm_program,
+    )
+}
+
+pub fn get_pool_vault_address(pool: &Pubkey, mint: &Pubkey, clmm_program: &Pubkey) -> Pubkey {
+    find(&[POOL_VAULT_SEED, pool.as_ref(), mint.as_ref()], clmm_program)
+}
+
+pub fn get_observation_address(pool: &Pubkey, clmm_program: &Pubkey) -> Pubkey {
+    find(&[OBSERVATION_SEED, pool.as_ref()], clmm_program)
+}
+
+pub fn get_tick_array_bitmap_extension_address(pool: &Pubkey, clmm_program: &Pubkey) -> Pubkey {
+    find(&[POOL_TICK_ARRAY_BITMAP_SEED, pool.as_ref()], clmm_program)
+}
+
+/// Start index of the tick array holding `tick`.
+pub fn get_tick_array_start_index(tick: i32, tick_spacing: u16) -> i32 {
+    let ticks_in_array = TICK_ARRAY_SIZE * tick_spacing as i32;
+    tick.div_euclid(ticks_in_array) * ticks_in_array
+}
+
+pub fn get_tick_array_address(pool: &Pubkey, start_index: i32, clmm_program: &Pubkey) -> Pubkey {
+    find(
+        &[TICK_ARRAY_SEED, pool.as_ref(), &start_index.to_be_bytes()],
+        clmm_program,
+    )
+}
+
+pub fn get_protocol_position_address(
+    pool: &Pubkey,
+    tick_lower_index: i32,
+    tick_upper_index: i32,
+    clmm_program: &Pubkey,
+) -> Pubkey {
+    find(
+        &[
+            POSITION_SEED,
+            pool.as_ref(),
+            &tick_lower_index.to_be_bytes(),
+            &tick_upper_index.to_be_bytes(),
+        ],
+        clmm_program,
+    )
+}
+
+pub fn get_personal_position_address(nft_mint: &Pubkey, clmm_program: &Pubkey) -> Pubkey {
+    find(&[POSITION_SEED, nft_mint.as_ref()], clmm_program)
+}
+
+/// Metaplex metadata account of `mint`
+pub fn get_metadata_address(mint: &Pubkey) -> Pubkey {
+    find(
+        &[METADATA_SEED, METADATA_PROGRAM_ID.as_ref(), mint.as_ref()],
+        &METADATA_PROGRAM_ID,
+    )
+}
+
+pub fn get_config_address() -> Pubkey {
+    find(&[CONFIG_SEED], &interact_dapp::ID)
+}
+
+pub fn get_strategy_address(pool: &Pubkey, base_mint: &Pubkey) -> Pubkey {
+    find(
+        &[STRATEGY_SEED, pool.as_ref(), base_mint.as_ref()],
+        &interact_dapp::ID,
+    )
+}
+
+pub fn get_strategy_share_mint_address(strategy: &Pubkey) -> Pubkey {
+    find(&[STRATEGY_SHARE_MINT_SEED, strategy.as_ref()], &interact_dapp::ID)
+}
+
+pub fn get_dca_order_address(owner: &Pubkey, order_id: u64) -> Pubkey {
+    find(
+        &[DCA_ORDER_SEED, owner.as_ref(), &order_id.to_le_bytes()],
+        &interact_dapp::ID,
+    )
+}
+
+pub fn get_limit_order_address(owner: &Pubkey, order_id: u64) -> Pubkey {
+    find(
+        &[LIMIT_ORDER_SEED, owner.as_ref(), &order_id.to_le_bytes()],
+        &interact_dapp::ID,
+    )
+}
+
+pub fn get_trigger_order_address(owner: &Pubkey, order_id: u64) -> Pubkey {
+    find(
+        &[TRIGGER_ORDER_SEED, owner.as_ref(), &order_id.to_le_bytes()],
+        &interact_dapp::ID,
+    )
+}
+
+pub fn get_operator_delegation_address(owner: &Pubkey, operator: &Pubkey, mint: &Pubkey) -> Pubkey {
+    find(
+        &[
+            OPERATOR_DELEGATION_SEED,
+            owner.as_ref(),
+            operator.as_ref(),
+            mint.as_ref(),
+        ],
+        &interact_dapp::ID,
+    )
+}
+
+pub fn get_deposit_caps_address(mint: &Pubkey) -> Pubkey {
+    find(&[DEPOSIT_CAPS_SEED, mint.as_ref()], &interact_dapp::ID)
+}
+
+pub fn get_user_deposits_address(mint: &Pubkey, user: &Pubkey) -> Pubkey {
+    find(&[USER_DEPOSITS_SEED, mint.as_ref(), user.as_ref()], &interact_dapp::ID)
+}
+
+pub fn get_allocator_address(authority: &Pubkey) -> Pubkey {
+    find(&[ALLOCATOR_SEED, authority.as_ref()], &interact_dapp::ID)
+}
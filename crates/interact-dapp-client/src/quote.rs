@@ -0,0 +1,269 @@
+use anchor_lang::prelude::*;
+use anchor_lang::Discriminator;
+use interact_dapp::math::{token_0_to_token_1, token_1_to_token_0};
+use raydium_amm_v3::libraries::{liquidity_math, swap_math, tick_math};
+use raydium_amm_v3::states::{
+    AmmConfig, PoolState, TickArrayBitmapExtension, TickArrayState, TickState,
+};
+use crate::instructions::ClmmPool;
+use crate::pda::{get_tick_array_start_index, TICK_ARRAY_SIZE};
+
+/// Tick arrays tracked by the pool bitmap on each side of tick 0, further ones are tracked
+/// by the bitmap extension
+const TICK_ARRAY_BITMAP_SIZE: i32 = 512;
+
+#[error_code]
+pub enum QuoteError {
+    #[msg("A tick array crossed by the swap was not provided.")]
+    MissingTickArray,
+    #[msg("Tick array does not belong to the pool.")]
+    InvalidTickArray,
+    #[msg("Price limit is on the wrong side of the pool price.")]
+    InvalidPriceLimit,
+    #[msg("The swap runs out of initialized tick arrays.")]
+    LiquidityExhausted,
+    #[msg("Math overflow.")]
+    MathOverflow,
+}
+
+/// Result of simulating `swap_v2` against a pool snapshot.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SwapQuote {
+    /// Amount taken from the user, fees included
+    pub amount_in: u64,
+    pub amount_out: u64,
+    /// Trade fee, included in `amount_in`
+    pub fee_amount: u64,
+    pub sqrt_price_after_x64: u128,
+    pub tick_after: i32,
+    /// Shortfall of `amount_out` against the fee-free amount at the starting price, in bps
+    pub price_impact_bps: u64,
+    /// Tick arrays the swap reads, in order, to pass as `proxy_swap` remaining accounts
+    pub tick_arrays: Vec<Pubkey>,
+    /// Whether one of `tick_arrays` is tracked by the bitmap extension, which then has to
+    /// be passed as well
+    pub needs_bitmap_extension: bool,
+}
+
+/// Parameters of the simulated swap, as passed to `proxy_swap`.
+#[derive(Debug, Clone, Copy)]
+pub struct QuoteParams {
+    pub amount: u64,
+    pub zero_for_one: bool,
+    pub is_base_input: bool,
+    /// 0 for no limit
+    pub sqrt_price_limit_x64: u128,
+    pub block_timestamp: u32,
+}
+
+/// Deserializes a zero-copy account from its data, checking the discriminator.
+pub fn load_zero_copy<T: bytemuck::Pod + Discriminator>(data: &[u8]) -> Option<T> {
+    let size = 8 + std::mem::size_of::<T>();
+    if data.len() < size || !data.starts_with(T::DISCRIMINATOR) {
+        return None;
+    }
+    bytemuck::try_pod_read_unaligned(&data[8..size]).ok()
+}
+
+fn is_out_of_default_bitmap(start_index: i32, tick_spacing: u16) -> bool {
+    let max_tick = TICK_ARRAY_BITMAP_SIZE * TICK_ARRAY_SIZE * tick_spacing as i32;
+    start_index < -max_tick || start_index >= max_tick
+}
+
+/// Next initialized tick of `ticks` from `tick_current` in the swap direction, matching
+/// `TickArrayState::next_initialized_tick`: at or below the current tick for zero for one,
+/// strictly above it otherwise.
+fn next_initialized_tick(
+    ticks: &[TickState],
+    start_index: i32,
+    tick_current: i32,
+    tick_spacing: u16,
+    zero_for_one: bool,
+) -> Option<TickState> {
+    if get_tick_array_start_index(tick_current, tick_spacing) != start_index {
+        return None;
+    }
+    let offset = ((tick_current - start_index) / tick_spacing as i32) as usize;
+    if zero_for_one {
+        ticks[..=offset]
+            .iter()
+            .rev()
+            .find(|tick| tick.liquidity_gross != 0)
+            .copied()
+    } else {
+        ticks[offset + 1..]
+            .iter()
+            .find(|tick| tick.liquidity_gross != 0)
+            .copied()
+    }
+}
+
+/// First initialized tick of `ticks` in the swap direction.
+fn first_initialized_tick(ticks: &[TickState], zero_for_one: bool) -> Option<TickState> {
+    if zero_for_one {
+        ticks.iter().rev().find(|tick| tick.liquidity_gross != 0).copied()
+    } else {
+        ticks.iter().find(|tick| tick.liquidity_gross != 0).copied()
+    }
+}
+
+/// Simulates Raydium `swap_v2` on `pool_state` step by step: the same swap steps, fees and
+/// liquidity updates on tick crossing, stopping at the price limit. `tick_arrays` may hold
+/// any loaded tick arrays of the pool, the ones read by the swap are returned in the quote.
+pub fn quote_swap(
+    pool: &ClmmPool,
+    pool_state: &PoolState,
+    amm_config: &AmmConfig,
+    tick_arrays: &[TickArrayState],
+    bitmap_extension: Option<&TickArrayBitmapExtension>,
+    params: QuoteParams,
+) -> Result<SwapQuote> {
+    let zero_for_one = params.zero_for_one;
+    let tick_spacing = pool_state.tick_spacing;
+    let sqrt_price_start_x64 = pool_state.sqrt_price_x64;
+    let sqrt_price_limit_x64 = match params.sqrt_price_limit_x64 {
+        0 if zero_for_one => tick_math::MIN_SQRT_PRICE_X64 + 1,
+        0 => tick_math::MAX_SQRT_PRICE_X64 - 1,
+        limit => limit,
+    };
+    if zero_for_one {
+        require!(
+            sqrt_price_limit_x64 < sqrt_price_start_x64
+                && sqrt_price_limit_x64 > tick_math::MIN_SQRT_PRICE_X64,
+            QuoteError::InvalidPriceLimit
+        );
+    } else {
+        require!(
+            sqrt_price_limit_x64 > sqrt_price_start_x64
+                && sqrt_price_limit_x64 < tick_math::MAX_SQRT_PRICE_X64,
+            QuoteError::InvalidPriceLimit
+        );
+    }
+    for tick_array in tick_arrays {
+        require_keys_eq!(tick_array.pool_id, pool.pool_state, QuoteError::InvalidTickArray);
+    }
+    let find_ticks = |start_index: i32| -> Result<[TickState; TICK_ARRAY_SIZE as usize]> {
+        tick_arrays
+            .iter()
+            .find(|tick_array| tick_array.start_tick_index == start_index)
+            .map(|tick_array| tick_array.ticks)
+            .ok_or_else(|| QuoteError::MissingTickArray.into())
+    };
+
+    let extension = bitmap_extension.copied();
+    let (mut is_match_current_tick_array, mut start_index) =
+        pool_state.get_first_initialized_tick_array(&extension, zero_for_one)?;
+    let mut ticks = find_ticks(start_index)?;
+    let mut used_tick_arrays = vec![start_index];
+
+    let mut amount_remaining = params.amount;
+    let mut amount_calculated = 0u64;
+    let mut fee_amount = 0u64;
+    let mut sqrt_price_x64 = sqrt_price_start_x64;
+    let mut tick = pool_state.tick_current;
+    let mut liquidity = pool_state.liquidity;
+
+    while amount_remaining != 0 && sqrt_price_x64 != sqrt_price_limit_x64 {
+        let step_sqrt_price_start_x64 = sqrt_price_x64;
+        let mut next_tick =
+            next_initialized_tick(&ticks, start_index, tick, tick_spacing, zero_for_one);
+        if next_tick.is_none() && !is_match_current_tick_array {
+            is_match_current_tick_array = true;
+            next_tick = first_initialized_tick(&ticks, zero_for_one);
+        }
+        let next_tick = match next_tick {
+            Some(next_tick) => next_tick,
+            None => {
+                start_index = pool_state
+                    .next_initialized_tick_array_start_index(&extension, start_index, zero_for_one)?
+                    .ok_or(QuoteError::LiquidityExhausted)?;
+                ticks = find_ticks(start_index)?;
+                used_tick_arrays.push(start_index);
+                first_initialized_tick(&ticks, zero_for_one)
+                    .ok_or(QuoteError::InvalidTickArray)?
+            }
+        };
+
+        let tick_next = next_tick.tick.clamp(tick_math::MIN_TICK, tick_math::MAX_TICK);
+        let sqrt_price_next_x64 = tick_math::get_sqrt_price_at_tick(tick_next)?;
+        let sqrt_price_target_x64 = if (zero_for_one && sqrt_price_next_x64 < sqrt_price_limit_x64)
+            || (!zero_for_one && sqrt_price_next_x64 > sqrt_price_limit_x64)
+        {
+            sqrt_price_limit_x64
+        } else {
+            sqrt_price_next_x64
+        };
+        let step = swap_math::compute_swap_step(
+            sqrt_price_x64,
+            sqrt_price_target_x64,
+            liquidity,
+            amount_remaining,
+            amm_config.trade_fee_rate,
+            params.is_base_input,
+            zero_for_one,
+            params.block_timestamp,
+        )?;
+        sqrt_price_x64 = step.sqrt_price_next_x64;
+
+        let (consumed, calculated) = if params.is_base_input {
+            (step.amount_in.checked_add(step.fee_amount), Some(step.amount_out))
+        } else {
+            (Some(step.amount_out), step.amount_in.checked_add(step.fee_amount))
+        };
+        amount_remaining = consumed
+            .and_then(|consumed| amount_remaining.checked_sub(consumed))
+            .ok_or(QuoteError::MathOverflow)?;
+        amount_calculated = calculated
+            .and_then(|calculated| amount_calculated.checked_add(calculated))
+            .ok_or(QuoteError::MathOverflow)?;
+        fee_amount = fee_amount
+            .checked_add(step.fee_amount)
+            .ok_or(QuoteError::MathOverflow)?;
+
+        if sqrt_price_x64 == sqrt_price_next_x64 {
+            let liquidity_net = next_tick.liquidity_net;
+            liquidity = liquidity_math::add_delta(
+                liquidity,
+                if zero_for_one { -liquidity_net } else { liquidity_net },
+            )?;
+            tick = if zero_for_one { tick_next - 1 } else { tick_next };
+        } else if sqrt_price_x64 != step_sqrt_price_start_x64 {
+            tick = tick_math::get_tick_at_sqrt_price(sqrt_price_x64)?;
+        }
+    }
+
+    let (amount_in, amount_out) = if params.is_base_input {
+        (params.amount - amount_remaining, amount_calculated)
+    } else {
+        (amount_calculated, params.amount - amount_remaining)
+    };
+    let amount_in_after_fee = amount_in.saturating_sub(fee_amount);
+    let spot_amount_out = if zero_for_one {
+        token_0_to_token_1(amount_in_after_fee, sqrt_price_start_x64)
+    } else {
+        token_1_to_token_0(amount_in_after_fee, sqrt_price_start_x64)
+    }
+    .ok_or(QuoteError::MathOverflow)?;
+    let price_impact_bps = if spot_amount_out == 0 {
+        0
+    } else {
+        (spot_amount_out.saturating_sub(amount_out) as u128 * 10_000 / spot_amount_out as u128)
+            as u64
+    };
+
+    Ok(SwapQuote {
+        amount_in,
+        amount_out,
+        fee_amount,
+        sqrt_price_after_x64: sqrt_price_x64,
+        tick_after: tick,
+        price_impact_bps,
+        needs_bitmap_extension: used_tick_arrays
+            .iter()
+            .any(|start_index| is_out_of_default_bitmap(*start_index, tick_spacing)),
+        tick_arrays: used_tick_arrays
+            .into_iter()
+            .map(|start_index| pool.tick_array(start_index))
+            .collect(),
+    })
+}
@@ -0,0 +1,17 @@
+use anchor_lang::prelude::*;
+use interact_dapp::constant::CONFIG_SEED;
+use interact_dapp::states::Config;
+use crate::env::TestEnv;
+
+/// Writes an unpaused program config with `admin`. `initialize_config` is restricted to the
+/// program upgrade authority, which natively loaded programs do not have.
+pub async fn set_config(env: &mut TestEnv, admin: &Pubkey) {
+    let (address, bump) = Pubkey::find_program_address(&[CONFIG_SEED], &interact_dapp::ID);
+    let config = Config {
+        admin: *admin,
+        paused: false,
+        fee_bps: 0,
+        bump,
+    };
+    env.set_anchor_account(&address, &interact_dapp::ID, &config).await;
+}
@@ -0,0 +1,202 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::InstructionData;
+use anchor_spl::associated_token::get_associated_token_address_with_program_id;
+use anchor_spl::{associated_token, token, token_2022};
+use interact_dapp_client::{
+    get_personal_position_address, get_protocol_position_address, get_tick_array_start_index,
+    proxy_swap, ClmmPool, SwapArgs,
+};
+use solana_sdk::signature::{Keypair, Signer};
+use crate::env::{TestEnv, AMM_CONFIG_INDEX, TICK_SPACING};
+
+/// sqrt(1) in Q64.64, a 1:1 pool price
+pub const SQRT_PRICE_ONE_X64: u128 = 1 << 64;
+
+/// Bounds of the position [`create_liquid_pool`] opens
+pub const POSITION_TICK_LOWER: i32 = -6_000;
+pub const POSITION_TICK_UPPER: i32 = 6_000;
+
+/// Creates a Raydium CLMM pool of two SPL Token mints on the environment AMM config.
+pub async fn create_pool(
+    env: &mut TestEnv,
+    mint_a: Pubkey,
+    mint_b: Pubkey,
+    sqrt_price_x64: u128,
+) -> ClmmPool {
+    let (token_mint_0, token_mint_1) = if mint_a < mint_b {
+        (mint_a, mint_b)
+    } else {
+        (mint_b, mint_a)
+    };
+    let pool = ClmmPool::new(
+        raydium_amm_v3::ID,
+        AMM_CONFIG_INDEX,
+        token_mint_0,
+        token_mint_1,
+        token::ID,
+        token::ID,
+        TICK_SPACING,
+    );
+    let ix = Instruction {
+        program_id: raydium_amm_v3::ID,
+        accounts: raydium_amm_v3::accounts::CreatePool {
+            pool_creator: env.payer.pubkey(),
+            amm_config: pool.amm_config,
+            pool_state: pool.pool_state,
+            token_mint_0,
+            token_mint_1,
+            token_vault_0: pool.vault(&token_mint_0),
+            token_vault_1: pool.vault(&token_mint_1),
+            observation_state: pool.observation(),
+            tick_array_bitmap: pool.tick_array_bitmap_extension(),
+            token_program_0: token::ID,
+            token_program_1: token::ID,
+            system_program: system_program::ID,
+            rent: sysvar::rent::ID,
+        }
+        .to_account_metas(None),
+        data: raydium_amm_v3::instruction::CreatePool {
+            sqrt_price_x64,
+            open_time: 0,
+        }
+        .data(),
+    };
+    env.process(&[ix], &[]).await.unwrap();
+    pool
+}
+
+/// Opens a position of the payer between `tick_lower` and `tick_upper`, sized by
+/// `amount_0_max` of token 0 and capped by `amount_1_max` of token 1. Both amounts are
+/// taken from the payer ATAs.
+pub async fn add_liquidity(
+    env: &mut TestEnv,
+    pool: &ClmmPool,
+    tick_lower: i32,
+    tick_upper: i32,
+    amount_0_max: u64,
+    amount_1_max: u64,
+) {
+    let owner = env.payer.pubkey();
+    let nft_mint = Keypair::new();
+    let tick_array_lower_start_index = get_tick_array_start_index(tick_lower, pool.tick_spacing);
+    let tick_array_upper_start_index = get_tick_array_start_index(tick_upper, pool.tick_spacing);
+    let ix = Instruction {
+        program_id: raydium_amm_v3::ID,
+        accounts: raydium_amm_v3::accounts::OpenPositionWithToken22Nft {
+            payer: owner,
+            position_nft_owner: owner,
+            position_nft_mint: nft_mint.pubkey(),
+            position_nft_account: get_associated_token_address_with_program_id(
+                &owner,
+                &nft_mint.pubkey(),
+                &token_2022::ID,
+            ),
+            pool_state: pool.pool_state,
+            protocol_position: get_protocol_position_address(
+                &pool.pool_state,
+                tick_lower,
+                tick_upper,
+                &pool.clmm_program,
+            ),
+            tick_array_lower: pool.tick_array(tick_array_lower_start_index),
+            tick_array_upper: pool.tick_array(tick_array_upper_start_index),
+            personal_position: get_personal_position_address(
+                &nft_mint.pubkey(),
+                &pool.clmm_program,
+            ),
+            token_account_0: get_associated_token_address_with_program_id(
+                &owner,
+                &pool.token_mint_0,
+                &pool.token_program_0,
+            ),
+            token_account_1: get_associated_token_address_with_program_id(
+                &owner,
+                &pool.token_mint_1,
+                &pool.token_program_1,
+            ),
+            token_vault_0: pool.vault(&pool.token_mint_0),
+            token_vault_1: pool.vault(&pool.token_mint_1),
+            rent: sysvar::rent::ID,
+            system_program: system_program::ID,
+            token_program: token::ID,
+            associated_token_program: associated_token::ID,
+            token_program_2022: token_2022::ID,
+            vault_0_mint: pool.token_mint_0,
+            vault_1_mint: pool.token_mint_1,
+        }
+        .to_account_metas(None),
+        data: raydium_amm_v3::instruction::OpenPositionWithToken22Nft {
+            tick_lower_index: tick_lower,
+            tick_upper_index: tick_upper,
+            tick_array_lower_start_index,
+            tick_array_upper_start_index,
+            liquidity: 0,
+            amount_0_max,
+            amount_1_max,
+            with_metadata: false,
+            base_flag: Some(true),
+        }
+        .data(),
+    };
+    env.process(&[ix], &[&nft_mint]).await.unwrap();
+}
+
+/// Creates a 1:1 pool of `mint_a` and `mint_b`, both minted by the payer, with a position
+/// of `amount` of token 0 between [`POSITION_TICK_LOWER`] and [`POSITION_TICK_UPPER`]. The
+/// payer is left with at least `amount` of both mints to trade with.
+pub async fn create_liquid_pool(
+    env: &mut TestEnv,
+    mint_a: Pubkey,
+    mint_b: Pubkey,
+    amount: u64,
+) -> ClmmPool {
+    let payer = env.payer.pubkey();
+    env.mint_to(&mint_a, &payer, 3 * amount).await;
+    env.mint_to(&mint_b, &payer, 3 * amount).await;
+    let pool = create_pool(env, mint_a, mint_b, SQRT_PRICE_ONE_X64).await;
+    add_liquidity(
+        env,
+        &pool,
+        POSITION_TICK_LOWER,
+        POSITION_TICK_UPPER,
+        amount,
+        2 * amount,
+    )
+    .await;
+    pool
+}
+
+/// Tick arrays of a swap on a [`create_liquid_pool`] pool that stays inside the position.
+/// The position bounds are the only initialized ticks, so the swap starts at the tick
+/// array of the bound it moves towards.
+pub fn position_tick_arrays(pool: &ClmmPool, zero_for_one: bool) -> Vec<Pubkey> {
+    let tick = if zero_for_one {
+        POSITION_TICK_LOWER
+    } else {
+        POSITION_TICK_UPPER
+    };
+    vec![pool.tick_array(get_tick_array_start_index(tick, pool.tick_spacing))]
+}
+
+/// Swaps dust of token 0 from the payer so Raydium writes the first observation of a
+/// [`create_liquid_pool`] pool, then moves the clock `elapsed` seconds forward. The pool
+/// TWAP over any window up to `elapsed` is then its current tick.
+pub async fn initialize_observation(env: &mut TestEnv, pool: &ClmmPool, elapsed: i64) {
+    let ix = proxy_swap(
+        pool,
+        &env.payer.pubkey(),
+        &pool.token_mint_0,
+        &position_tick_arrays(pool, true),
+        false,
+        SwapArgs {
+            amount: 1_000,
+            other_amount_threshold: 0,
+            sqrt_price_limit_x64: 0,
+            is_base_input: true,
+        },
+    );
+    env.process(&[ix], &[]).await.unwrap();
+    let now = env.unix_timestamp().await;
+    env.set_unix_timestamp(now + elapsed).await;
+}
@@ -0,0 +1,62 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::InstructionData;
+use anchor_spl::token::spl_token;
+use interact_dapp_client::{initialize_deposit_caps, EarnMarket};
+use solana_sdk::signature::Signer;
+use crate::env::TestEnv;
+
+/// Earn market served by the mock lending program, which is its own liquidity program.
+pub fn mock_earn_market(mint: Pubkey) -> EarnMarket {
+    EarnMarket {
+        lending_program: mock_lending::ID,
+        liquidity_program: mock_lending::ID,
+        mint,
+        token_program: spl_token::ID,
+    }
+}
+
+/// Creates an underlying mint, its mock Earn market and uncapped `DepositCaps`, which the
+/// program vaults holding the mint keep their ledger in.
+pub async fn create_earn_market(env: &mut TestEnv, decimals: u8) -> EarnMarket {
+    let mint = env.create_mint(decimals).await;
+    let market = mock_earn_market(mint);
+    let ix = Instruction {
+        program_id: mock_lending::ID,
+        accounts: mock_lending::accounts::InitLending {
+            signer: env.payer.pubkey(),
+            mint,
+            lending_admin: market.lending_admin(),
+            f_token_mint: market.f_token_mint(),
+            lending: market.lending(),
+            liquidity: market.liquidity(),
+            vault: market.vault(),
+            token_program: spl_token::ID,
+            associated_token_program: anchor_spl::associated_token::ID,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: mock_lending::instruction::InitLending {}.data(),
+    };
+    let admin = env.payer.pubkey();
+    let caps = initialize_deposit_caps(&market, &admin, u64::MAX, u64::MAX);
+    env.process(&[ix, caps], &[]).await.unwrap();
+    market
+}
+
+/// Moves the fToken exchange price of a mock market, in 1e12 precision.
+pub async fn set_token_exchange_price(env: &mut TestEnv, market: &EarnMarket, price: u64) {
+    let ix = Instruction {
+        program_id: mock_lending::ID,
+        accounts: mock_lending::accounts::SetTokenExchangePrice {
+            signer: env.payer.pubkey(),
+            lending: market.lending(),
+        }
+        .to_account_metas(None),
+        data: mock_lending::instruction::SetTokenExchangePrice {
+            token_exchange_price: price,
+        }
+        .data(),
+    };
+    env.process(&[ix], &[]).await.unwrap();
+}
@@ -0,0 +1,418 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::entrypoint::ProgramResult;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::program_pack::Pack;
+use anchor_lang::{AccountDeserialize, AccountSerialize};
+use anchor_spl::associated_token::get_associated_token_address_with_program_id;
+use anchor_spl::associated_token::spl_associated_token_account::instruction::{
+    create_associated_token_account_idempotent,
+};
+use anchor_spl::token::spl_token;
+use interact_dapp_client::{
+    get_config_address, load_zero_copy, AccountFixture, Snapshot, AMM_CONFIG_SEED,
+    METADATA_PROGRAM_ID,
+};
+use raydium_amm_v3::states::AmmConfig;
+use solana_program_test::{processor, BanksClientError, ProgramTest, ProgramTestContext};
+use solana_sdk::account::Account;
+use solana_sdk::instruction::InstructionError;
+use solana_sdk::native_loader;
+use solana_sdk::signature::{Keypair, Signer};
+use solana_sdk::system_instruction;
+use solana_sdk::transaction::{Transaction, TransactionError};
+use crate::admin::set_config;
+
+/// Index of the Raydium AMM config created with the environment
+pub const AMM_CONFIG_INDEX: u16 = 0;
+pub const TICK_SPACING: u16 = 10;
+/// 0.25%, in Raydium's 1e6 fee denominator
+pub const TRADE_FEE_RATE: u32 = 2_500;
+
+// Anchor entrypoints take `&'info [AccountInfo<'info>]`, the leaked copy gives the
+// account infos the lifetime the native processor cannot express.
+fn interact_dapp_entry(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: &[u8],
+) -> ProgramResult {
+    let accounts = Box::leak(Box::new(accounts.to_vec()));
+    interact_dapp::entry(program_id, accounts, data)
+}
+
+fn mock_lending_entry(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: &[u8],
+) -> ProgramResult {
+    let accounts = Box::leak(Box::new(accounts.to_vec()));
+    mock_lending::entry(program_id, accounts, data)
+}
+
+fn raydium_entry(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: &[u8],
+) -> ProgramResult {
+    let accounts = Box::leak(Box::new(accounts.to_vec()));
+    raydium_amm_v3::entry(program_id, accounts, data)
+}
+
+const NATIVE_PROGRAMS: [Pubkey; 3] = [interact_dapp::ID, mock_lending::ID, raydium_amm_v3::ID];
+
+/// With `prefer_bpf`, the programs are loaded from `<name>.so` in `SBF_OUT_DIR` when found,
+/// otherwise they run natively. The Metaplex metadata program, which strategy positions are
+/// opened with, has no native build and is only loaded then.
+fn program_test(prefer_bpf: bool) -> ProgramTest {
+    let mut program_test = ProgramTest::default();
+    program_test.prefer_bpf(prefer_bpf);
+    program_test.add_program(
+        "interact_dapp",
+        interact_dapp::ID,
+        processor!(interact_dapp_entry),
+    );
+    program_test.add_program(
+        "mock_lending",
+        mock_lending::ID,
+        processor!(mock_lending_entry),
+    );
+    program_test.add_program(
+        "raydium_amm_v3",
+        raydium_amm_v3::ID,
+        processor!(raydium_entry),
+    );
+    if prefer_bpf {
+        program_test.add_program("mpl_token_metadata", METADATA_PROGRAM_ID, None);
+    }
+    program_test
+}
+
+pub struct TestEnv {
+    pub context: ProgramTestContext,
+    pub payer: Keypair,
+}
+
+impl TestEnv {
+    /// Starts a bank with the three programs and a Raydium AMM config. The config is
+    /// written directly since `create_amm_config` is restricted to the Raydium admin.
+    pub async fn new() -> Self {
+        Self::with_amm_config(program_test(false)).await
+    }
+
+    /// Starts the same environment with the SBF builds of the programs and the Metaplex
+    /// metadata program, which are metered unlike native ones. Panics when one of them is
+    /// not found in `SBF_OUT_DIR`.
+    pub async fn new_sbf() -> Self {
+        let mut env = Self::with_amm_config(program_test(true)).await;
+        for program_id in NATIVE_PROGRAMS {
+            let program = env.get_account(&program_id).await.unwrap();
+            assert!(
+                program.owner != native_loader::ID,
+                "no SBF build of {program_id} in SBF_OUT_DIR"
+            );
+        }
+        env
+    }
+
+    async fn with_amm_config(mut program_test: ProgramTest) -> Self {
+        let (amm_config_address, bump) = Pubkey::find_program_address(
+            &[AMM_CONFIG_SEED, &AMM_CONFIG_INDEX.to_be_bytes()],
+            &raydium_amm_v3::ID,
+        );
+        let amm_config = AmmConfig {
+            bump,
+            index: AMM_CONFIG_INDEX,
+            trade_fee_rate: TRADE_FEE_RATE,
+            tick_spacing: TICK_SPACING,
+            ..Default::default()
+        };
+        let mut data = Vec::new();
+        amm_config.try_serialize(&mut data).unwrap();
+        program_test.add_account(
+            amm_config_address,
+            Account {
+                lamports: 1_000_000_000,
+                data,
+                owner: raydium_amm_v3::ID,
+                executable: false,
+                rent_epoch: 0,
+            },
+        );
+        Self::start(program_test).await
+    }
+
+    /// Starts a bank seeded with the accounts of `snapshot`, at its clock timestamp.
+    /// Snapshot accounts of the natively loaded programs are skipped. The bank is warped past
+    /// the snapshot slot, since dumped programs only run after the slot they were deployed at.
+    pub async fn with_snapshot(snapshot: &Snapshot) -> Self {
+        let mut program_test = program_test(false);
+        for account in &snapshot.accounts {
+            if NATIVE_PROGRAMS.contains(&account.address) {
+                continue;
+            }
+            program_test.add_account(
+                account.address,
+                Account {
+                    lamports: account.lamports,
+                    data: account.data.clone(),
+                    owner: account.owner,
+                    executable: account.executable,
+                    rent_epoch: account.rent_epoch,
+                },
+            );
+        }
+        let mut env = Self::start(program_test).await;
+        let clock: Clock = env.context.banks_client.get_sysvar().await.unwrap();
+        if snapshot.slot >= clock.slot {
+            env.context.warp_to_slot(snapshot.slot + 1).unwrap();
+        }
+        env.set_unix_timestamp(snapshot.unix_timestamp).await;
+        env
+    }
+
+    /// Starts the bank, with the payer as config admin unless the config is already loaded.
+    async fn start(program_test: ProgramTest) -> Self {
+        let context = program_test.start_with_context().await;
+        let payer = context.payer.insecure_clone();
+        let mut env = Self { context, payer };
+        // Most instructions read the pause flag of the config
+        if env.get_account(&get_config_address()).await.is_none() {
+            let admin = env.payer.pubkey();
+            set_config(&mut env, &admin).await;
+        }
+        env
+    }
+
+    pub async fn unix_timestamp(&mut self) -> i64 {
+        let clock: Clock = self.context.banks_client.get_sysvar().await.unwrap();
+        clock.unix_timestamp
+    }
+
+    pub async fn set_unix_timestamp(&mut self, unix_timestamp: i64) {
+        let mut clock: Clock = self.context.banks_client.get_sysvar().await.unwrap();
+        clock.unix_timestamp = unix_timestamp;
+        self.context.set_sysvar(&clock);
+    }
+
+    /// Writes a rent exempt account holding `data`, for state the programs only let a
+    /// privileged signer create or that belongs to a program the bank does not run.
+    pub async fn set_account_data(&mut self, address: &Pubkey, owner: &Pubkey, data: Vec<u8>) {
+        let rent = self.context.banks_client.get_rent().await.unwrap();
+        let account = Account {
+            lamports: rent.minimum_balance(data.len()),
+            data,
+            owner: *owner,
+            executable: false,
+            rent_epoch: 0,
+        };
+        self.context.set_account(address, &account.into());
+    }
+
+    /// Writes an Anchor account with its discriminator, see [`Self::set_account_data`].
+    pub async fn set_anchor_account<T: AccountSerialize>(
+        &mut self,
+        address: &Pubkey,
+        owner: &Pubkey,
+        account: &T,
+    ) {
+        let mut data = Vec::new();
+        account.try_serialize(&mut data).unwrap();
+        self.set_account_data(address, owner, data).await;
+    }
+
+    /// Reads `addresses` from the bank into a snapshot, skipping missing accounts.
+    pub async fn snapshot(&mut self, addresses: &[Pubkey]) -> Snapshot {
+        let clock: Clock = self.context.banks_client.get_sysvar().await.unwrap();
+        let mut accounts = Vec::with_capacity(addresses.len());
+        for address in addresses {
+            if let Some(account) = self.get_account(address).await {
+                accounts.push(AccountFixture {
+                    address: *address,
+                    owner: account.owner,
+                    lamports: account.lamports,
+                    executable: account.executable,
+                    rent_epoch: account.rent_epoch,
+                    data: account.data,
+                });
+            }
+        }
+        Snapshot {
+            cluster: "program-test".to_string(),
+            slot: clock.slot,
+            unix_timestamp: clock.unix_timestamp,
+            accounts,
+        }
+    }
+
+    /// Signs `instructions` with the payer and `signers` on a fresh blockhash, so identical
+    /// transactions can be sent twice.
+    pub async fn process(
+        &mut self,
+        instructions: &[Instruction],
+        signers: &[&Keypair],
+    ) -> std::result::Result<(), BanksClientError> {
+        let transaction = self.transaction(instructions, signers).await?;
+        self.context.banks_client.process_transaction(transaction).await
+    }
+
+    /// Processes `instructions` like [`Self::process`], returning the compute units the
+    /// transaction consumed. Only programs loaded from SBF builds are metered.
+    pub async fn process_metered(
+        &mut self,
+        instructions: &[Instruction],
+        signers: &[&Keypair],
+    ) -> std::result::Result<u64, BanksClientError> {
+        let transaction = self.transaction(instructions, signers).await?;
+        let result = self
+            .context
+            .banks_client
+            .process_transaction_with_metadata(transaction)
+            .await?;
+        result.result.map_err(BanksClientError::TransactionError)?;
+        Ok(result
+            .metadata
+            .map_or(0, |metadata| metadata.compute_units_consumed))
+    }
+
+    /// Processes `instructions` like [`Self::process`], returning the data set by the last
+    /// instruction, empty when it returned nothing.
+    pub async fn process_with_return_data(
+        &mut self,
+        instructions: &[Instruction],
+        signers: &[&Keypair],
+    ) -> std::result::Result<Vec<u8>, BanksClientError> {
+        let transaction = self.transaction(instructions, signers).await?;
+        let result = self
+            .context
+            .banks_client
+            .process_transaction_with_metadata(transaction)
+            .await?;
+        result.result.map_err(BanksClientError::TransactionError)?;
+        Ok(result
+            .metadata
+            .and_then(|metadata| metadata.return_data)
+            .map_or_else(Vec::new, |return_data| return_data.data))
+    }
+
+    async fn transaction(
+        &mut self,
+        instructions: &[Instruction],
+        signers: &[&Keypair],
+    ) -> std::result::Result<Transaction, BanksClientError> {
+        let blockhash = self.context.get_new_latest_blockhash().await?;
+        let mut all_signers = vec![&self.payer];
+        all_signers.extend(
+            signers
+                .iter()
+                .copied()
+                .filter(|signer| signer.pubkey() != self.payer.pubkey()),
+        );
+        Ok(Transaction::new_signed_with_payer(
+            instructions,
+            Some(&self.payer.pubkey()),
+            &all_signers,
+            blockhash,
+        ))
+    }
+
+    pub async fn get_account(&mut self, address: &Pubkey) -> Option<Account> {
+        self.context.banks_client.get_account(*address).await.unwrap()
+    }
+
+    pub async fn get_anchor_account<T: AccountDeserialize>(&mut self, address: &Pubkey) -> T {
+        let account = self.get_account(address).await.expect("account not found");
+        T::try_deserialize(&mut &account.data[..]).unwrap()
+    }
+
+    pub async fn get_zero_copy<T: bytemuck::Pod + Discriminator>(
+        &mut self,
+        address: &Pubkey,
+    ) -> T {
+        let account = self.get_account(address).await.expect("account not found");
+        load_zero_copy(&account.data).expect("invalid zero copy account")
+    }
+
+    /// Creates an SPL Token mint with the payer as authority.
+    pub async fn create_mint(&mut self, decimals: u8) -> Pubkey {
+        let mint = Keypair::new();
+        let rent = self.context.banks_client.get_rent().await.unwrap();
+        let instructions = [
+            system_instruction::create_account(
+                &self.payer.pubkey(),
+                &mint.pubkey(),
+                rent.minimum_balance(spl_token::state::Mint::LEN),
+                spl_token::state::Mint::LEN as u64,
+                &spl_token::ID,
+            ),
+            spl_token::instruction::initialize_mint2(
+                &spl_token::ID,
+                &mint.pubkey(),
+                &self.payer.pubkey(),
+                None,
+                decimals,
+            )
+            .unwrap(),
+        ];
+        self.process(&instructions, &[&mint]).await.unwrap();
+        mint.pubkey()
+    }
+
+    /// Creates the ATA of `owner` for `mint` if it does not exist yet.
+    pub async fn create_ata(
+        &mut self,
+        owner: &Pubkey,
+        mint: &Pubkey,
+        token_program: &Pubkey,
+    ) -> Pubkey {
+        let ix = create_associated_token_account_idempotent(
+            &self.payer.pubkey(),
+            owner,
+            mint,
+            token_program,
+        );
+        self.process(&[ix], &[]).await.unwrap();
+        get_associated_token_address_with_program_id(owner, mint, token_program)
+    }
+
+    /// Mints `amount` of an SPL Token mint created by [`Self::create_mint`] to the ATA of
+    /// `owner`, returning the ATA.
+    pub async fn mint_to(&mut self, mint: &Pubkey, owner: &Pubkey, amount: u64) -> Pubkey {
+        let ata = self.create_ata(owner, mint, &spl_token::ID).await;
+        let ix = spl_token::instruction::mint_to(
+            &spl_token::ID,
+            mint,
+            &ata,
+            &self.payer.pubkey(),
+            &[],
+            amount,
+        )
+        .unwrap();
+        self.process(&[ix], &[]).await.unwrap();
+        ata
+    }
+
+    /// Balance of a token account, 0 when it does not exist.
+    pub async fn token_balance(&mut self, address: &Pubkey) -> u64 {
+        match self.get_account(address).await {
+            Some(account) => {
+                anchor_spl::token_interface::TokenAccount::try_deserialize(&mut &account.data[..])
+                    .unwrap()
+                    .amount
+            }
+            None => 0,
+        }
+    }
+}
+
+/// Custom program error code a transaction failed with, to compare with
+/// `InteractDappError::Variant.into()` or an Anchor `ErrorCode`.
+pub fn custom_error_code(result: std::result::Result<(), BanksClientError>) -> Option<u32> {
+    let error = match result.err()? {
+        BanksClientError::TransactionError(error)
+        | BanksClientError::SimulationError { err: error, .. } => error,
+        _ => return None,
+    };
+    match error {
+        TransactionError::InstructionError(_, InstructionError::Custom(code)) => Some(code),
+        _ => None,
+    }
+}
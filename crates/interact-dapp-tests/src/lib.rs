@@ -0,0 +1,12 @@
+//! Local test harness: runs interact_dapp, the mock lending program and the Raydium CLMM
+//! program from the `raydium-amm-v3` dependency natively in `solana-program-test`, so the
+//! instructions can be exercised without a cluster.
+pub mod admin;
+pub mod clmm;
+pub mod earn;
+pub mod env;
+
+pub use admin::*;
+pub use clmm::*;
+pub use earn::*;
+pub use env::*;
@@ -0,0 +1,90 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::get_associated_token_address;
+use anchor_spl::token;
+use interact_dapp::error::InteractDappError;
+use interact_dapp::states::{Allocator, DepositCaps, UserDeposits};
+use interact_dapp_client::{
+    add_allocator_market, allocator_deposit, get_allocator_address, get_deposit_caps_address,
+    get_user_deposits_address, initialize_allocator, update_deposit_caps, EarnMarket,
+};
+use interact_dapp_tests::{create_earn_market, custom_error_code, TestEnv};
+use solana_sdk::signature::Signer;
+
+/// Creates an allocator of the payer with `market` allowlisted.
+async fn setup(env: &mut TestEnv, market: &EarnMarket) -> Pubkey {
+    let authority = env.payer.pubkey();
+    let initialize = initialize_allocator(&authority, &Pubkey::new_unique(), 0, 50);
+    let add_market = add_allocator_market(market, &authority);
+    env.process(&[initialize, add_market], &[]).await.unwrap();
+    get_allocator_address(&authority)
+}
+
+#[tokio::test]
+async fn allocator_deposits_into_allowlisted_market() {
+    let mut env = TestEnv::new().await;
+    let market = create_earn_market(&mut env, 6).await;
+    let allocator = setup(&mut env, &market).await;
+    let authority = env.payer.pubkey();
+    let token_account = env.mint_to(&market.mint, &authority, 1_000_000).await;
+
+    env.process(&[allocator_deposit(&market, &authority, 400_000)], &[])
+        .await
+        .unwrap();
+
+    assert_eq!(env.token_balance(&token_account).await, 600_000);
+    let f_token_vault = get_associated_token_address(&allocator, &market.f_token_mint());
+    assert_eq!(env.token_balance(&f_token_vault).await, 400_000);
+    let state: Allocator = env.get_anchor_account(&allocator).await;
+    assert_eq!(state.markets, vec![market.lending()]);
+}
+
+#[tokio::test]
+async fn allocator_deposit_rejects_other_market() {
+    let mut env = TestEnv::new().await;
+    let market = create_earn_market(&mut env, 6).await;
+    let allocator = setup(&mut env, &market).await;
+    let authority = env.payer.pubkey();
+    // The allocator holds accounts of the other market, but never allowlisted it
+    let other = create_earn_market(&mut env, 6).await;
+    env.mint_to(&other.mint, &authority, 1_000_000).await;
+    env.create_ata(&allocator, &other.mint, &token::ID).await;
+    env.create_ata(&allocator, &other.f_token_mint(), &token::ID)
+        .await;
+
+    let result = env
+        .process(&[allocator_deposit(&other, &authority, 400_000)], &[])
+        .await;
+    assert_eq!(
+        custom_error_code(result),
+        Some(InteractDappError::MarketNotAllowlisted.into())
+    );
+}
+
+#[tokio::test]
+async fn allocator_deposit_counts_against_deposit_caps() {
+    let mut env = TestEnv::new().await;
+    let market = create_earn_market(&mut env, 6).await;
+    setup(&mut env, &market).await;
+    let authority = env.payer.pubkey();
+    env.mint_to(&market.mint, &authority, 1_000_000).await;
+    env.process(&[update_deposit_caps(&market.mint, &authority, u64::MAX, 500_000)], &[])
+        .await
+        .unwrap();
+
+    env.process(&[allocator_deposit(&market, &authority, 400_000)], &[])
+        .await
+        .unwrap();
+    let result = env
+        .process(&[allocator_deposit(&market, &authority, 200_000)], &[])
+        .await;
+    assert_eq!(custom_error_code(result), Some(InteractDappError::DepositCapExceeded.into()));
+
+    let caps: DepositCaps = env
+        .get_anchor_account(&get_deposit_caps_address(&market.mint))
+        .await;
+    assert_eq!(caps.ledger.f_token_shares, 400_000);
+    let user: UserDeposits = env
+        .get_anchor_account(&get_user_deposits_address(&market.mint, &authority))
+        .await;
+    assert_eq!((user.user, user.ledger.f_token_shares), (authority, 400_000));
+}
@@ -0,0 +1,95 @@
+//! Replays the fixtures of the cluster the tests are built for against the Jupiter programs
+//! dumped into them. Only built with the real program ids:
+//!
+//! ```text
+//! cargo test -p interact-dapp-tests --no-default-features --features devnet \
+//!     --test cluster_replay
+//! ```
+#![cfg(not(feature = "localnet"))]
+use std::collections::HashSet;
+use std::path::Path;
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program_pack::Pack;
+use anchor_spl::associated_token::get_associated_token_address;
+use anchor_spl::token::spl_token;
+use anchor_spl::token::spl_token::state::{Account as TokenAccount, AccountState, Mint};
+use interact_dapp::programs::CLUSTER;
+use interact_dapp_client::{
+    deposit_earn, initialize_deposit_caps, withdraw_earn, EarnMarket, Snapshot,
+};
+use interact_dapp_tests::TestEnv;
+use solana_sdk::signature::Signer;
+
+/// Earn markets of `snapshot`: its SPL Token mints whose lending account it also holds.
+fn snapshot_earn_markets(snapshot: &Snapshot) -> Vec<EarnMarket> {
+    let addresses: HashSet<Pubkey> =
+        snapshot.accounts.iter().map(|account| account.address).collect();
+    snapshot
+        .accounts
+        .iter()
+        .filter(|account| account.owner == spl_token::ID && account.data.len() == Mint::LEN)
+        .map(|account| EarnMarket::new(account.address, account.owner))
+        .filter(|market| addresses.contains(&market.lending()))
+        .collect()
+}
+
+/// Deposits into and withdraws from `market` of `snapshot`, from an ATA of the payer
+/// written with the underlying since its mint authority is not ours.
+async fn replay_deposit_and_withdraw(snapshot: &Snapshot, market: &EarnMarket) {
+    let mut env = TestEnv::with_snapshot(snapshot).await;
+    let user = env.payer.pubkey();
+    let amount = 1_000_000;
+    let token_account = get_associated_token_address(&user, &market.mint);
+    let mut data = vec![0; TokenAccount::LEN];
+    TokenAccount {
+        mint: market.mint,
+        owner: user,
+        amount,
+        state: AccountState::Initialized,
+        ..Default::default()
+    }
+    .pack_into_slice(&mut data);
+    env.set_account_data(&token_account, &spl_token::ID, data).await;
+    let f_token_account = env
+        .create_ata(&user, &market.f_token_mint(), &market.token_program)
+        .await;
+
+    let instructions = [
+        initialize_deposit_caps(market, &user, u64::MAX, u64::MAX),
+        deposit_earn(market, &user, amount),
+    ];
+    env.process(&instructions, &[]).await.unwrap();
+    assert_eq!(env.token_balance(&token_account).await, 0);
+    let shares = env.token_balance(&f_token_account).await;
+    assert!(shares > 0, "no fTokens minted by {}", market.lending());
+
+    env.process(&[withdraw_earn(market, &user, amount / 2)], &[])
+        .await
+        .unwrap();
+    assert_eq!(env.token_balance(&token_account).await, amount / 2);
+    assert!(env.token_balance(&f_token_account).await < shares);
+}
+
+#[tokio::test]
+async fn cluster_fixtures_deposit_and_withdraw() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures");
+    let mut markets = 0;
+    for entry in std::fs::read_dir(dir).unwrap() {
+        let path = entry.unwrap().path();
+        if path.extension().and_then(|extension| extension.to_str()) != Some("json") {
+            continue;
+        }
+        let snapshot = Snapshot::read(&path).unwrap();
+        if snapshot.cluster != CLUSTER {
+            continue;
+        }
+        for market in snapshot_earn_markets(&snapshot) {
+            replay_deposit_and_withdraw(&snapshot, &market).await;
+            markets += 1;
+        }
+    }
+    assert!(
+        markets > 0,
+        "no {CLUSTER} Earn market under fixtures/, dump one as in fixtures/README.md"
+    );
+}
@@ -0,0 +1,501 @@
+//! Compute unit benchmarks, checking each instruction against its budget in
+//! `interact_dapp_client::compute_budget`. Natively processed programs are not metered, so
+//! these run against the SBF builds:
+//!
+//! ```text
+//! anchor build
+//! solana program dump -u devnet devi51mZmdwUJGU9hjN27vEz64Gps7uUefqxg27EAtH \
+//!     target/deploy/raydium_amm_v3.so
+//! solana program dump -u devnet metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s \
+//!     target/deploy/mpl_token_metadata.so
+//! SBF_OUT_DIR=$PWD/target/deploy cargo test -p interact-dapp-tests --test compute_units \
+//!     -- --ignored --nocapture
+//! ```
+use anchor_lang::prelude::*;
+use anchor_spl::token;
+use interact_dapp::math::get_centered_range;
+use interact_dapp::states::TriggerKind;
+use interact_dapp_client::{
+    add_allocator_market, allocator_deposit, compute_units_with_tick_arrays, crank_compound,
+    deposit_earn, execute_dca, execute_trigger, fill_limit_order, get_tick_array_start_index,
+    initialize_allocator, initialize_strategy, load_zero_copy, open_dca_order,
+    open_limit_order, open_strategy_position, open_trigger_order, proxy_swap,
+    proxy_swap_compute_units, quote_swap, rebalance_range, reallocate, set_rebalance_config,
+    swap_remaining_accounts, with_compute_budget, withdraw_earn, ClmmPool, ClmmStrategy,
+    DcaOrderArgs, QuoteParams, StrategyPosition, SwapArgs, SwapQuote, TriggerOrderArgs,
+    CRANK_COMPOUND_COMPUTE_UNITS, DEPOSIT_EARN_COMPUTE_UNITS, EXECUTE_DCA_COMPUTE_UNITS,
+    EXECUTE_TRIGGER_COMPUTE_UNITS, FILL_LIMIT_ORDER_COMPUTE_UNITS, MAX_COMPUTE_UNIT_LIMIT,
+    PROXY_SWAP_COMPUTE_UNITS_PER_TICK_ARRAY, REALLOCATE_COMPUTE_UNITS,
+    REBALANCE_RANGE_COMPUTE_UNITS, WITHDRAW_EARN_COMPUTE_UNITS,
+};
+use interact_dapp_tests::{
+    add_liquidity, create_earn_market, create_liquid_pool, create_pool, initialize_observation,
+    TestEnv, POSITION_TICK_LOWER, POSITION_TICK_UPPER, SQRT_PRICE_ONE_X64,
+};
+use raydium_amm_v3::libraries::tick_math;
+use raydium_amm_v3::states::{AmmConfig, PoolState, TickArrayState};
+use solana_sdk::instruction::Instruction;
+use solana_sdk::signature::{Keypair, Signer};
+
+/// Ticks covered by one tick array at the environment tick spacing
+const TICK_ARRAY_TICKS: i32 = 600;
+/// Nested positions `[-k * 600, k * 600]`, initializing one tick array per side for each
+const POSITIONS: i32 = 4;
+
+/// Liquidity of the [`create_liquid_pool`] pools the instructions below swap in
+const LIQUIDITY_AMOUNT: u64 = 1_000_000_000;
+const ORDER_ID: u64 = 1;
+const ORDER_AMOUNT: u64 = 1_000_000;
+
+/// Runs `instruction` alone under the maximum limit and checks it against `budget`.
+async fn measure(env: &mut TestEnv, name: &str, instruction: Instruction, budget: u32) -> u64 {
+    measure_signed(env, name, instruction, budget, &[]).await
+}
+
+/// [`measure`] for an instruction with further `signers` than the payer.
+async fn measure_signed(
+    env: &mut TestEnv,
+    name: &str,
+    instruction: Instruction,
+    budget: u32,
+    signers: &[&Keypair],
+) -> u64 {
+    let instructions = with_compute_budget(&[instruction], MAX_COMPUTE_UNIT_LIMIT, None);
+    let units = env.process_metered(&instructions, signers).await.unwrap();
+    println!("{name}: {units} CU (budget {budget})");
+    assert!(units <= budget as u64, "{name} used {units} CU, over its {budget} CU budget");
+    units
+}
+
+#[tokio::test]
+#[ignore = "needs the SBF builds of the programs in SBF_OUT_DIR"]
+async fn earn_instructions_within_budget() {
+    let mut env = TestEnv::new_sbf().await;
+    let market = create_earn_market(&mut env, 6).await;
+    let user = env.payer.pubkey();
+    env.mint_to(&market.mint, &user, 1_000_000).await;
+    env.create_ata(&user, &market.f_token_mint(), &market.token_program)
+        .await;
+
+    measure(
+        &mut env,
+        "deposit_earn",
+        deposit_earn(&market, &user, 1_000_000),
+        DEPOSIT_EARN_COMPUTE_UNITS,
+    )
+    .await;
+    measure(
+        &mut env,
+        "withdraw_earn",
+        withdraw_earn(&market, &user, 500_000),
+        WITHDRAW_EARN_COMPUTE_UNITS,
+    )
+    .await;
+}
+
+async fn setup_pool(env: &mut TestEnv) -> ClmmPool {
+    let user = env.payer.pubkey();
+    let mint_a = env.create_mint(6).await;
+    let mint_b = env.create_mint(6).await;
+    env.mint_to(&mint_a, &user, 100_000_000_000).await;
+    env.mint_to(&mint_b, &user, 100_000_000_000).await;
+    let pool = create_pool(env, mint_a, mint_b, SQRT_PRICE_ONE_X64).await;
+    for k in 1..=POSITIONS {
+        let tick = k * TICK_ARRAY_TICKS;
+        add_liquidity(env, &pool, -tick, tick, 100_000_000, 10_000_000_000).await;
+    }
+    pool
+}
+
+/// Swaps token 0 down to `limit_tick` from the pool price, returning the number of tick
+/// arrays read and the compute units consumed.
+async fn measure_swap(limit_tick: i32) -> (usize, u64) {
+    let mut env = TestEnv::new_sbf().await;
+    let pool = setup_pool(&mut env).await;
+    let user = env.payer.pubkey();
+    let pool_state: PoolState = env.get_zero_copy(&pool.pool_state).await;
+    let amm_config: AmmConfig = env.get_anchor_account(&pool.amm_config).await;
+    let mut tick_arrays: Vec<TickArrayState> = Vec::new();
+    for k in 1..=POSITIONS {
+        let start_index = get_tick_array_start_index(-k * TICK_ARRAY_TICKS, pool.tick_spacing);
+        tick_arrays.push(env.get_zero_copy(&pool.tick_array(start_index)).await);
+    }
+
+    let amount = 10_000_000_000;
+    let sqrt_price_limit_x64 = tick_math::get_sqrt_price_at_tick(limit_tick).unwrap();
+    let quote = quote_swap(
+        &pool,
+        &pool_state,
+        &amm_config,
+        &tick_arrays,
+        None,
+        QuoteParams {
+            amount,
+            zero_for_one: true,
+            is_base_input: true,
+            sqrt_price_limit_x64,
+            block_timestamp: 0,
+        },
+    )
+    .unwrap();
+    let ix = proxy_swap(
+        &pool,
+        &user,
+        &pool.token_mint_0,
+        &quote.tick_arrays,
+        quote.needs_bitmap_extension,
+        SwapArgs {
+            amount,
+            other_amount_threshold: quote.amount_out,
+            sqrt_price_limit_x64,
+            is_base_input: true,
+        },
+    );
+    let tick_array_count = quote.tick_arrays.len();
+    let name = format!("proxy_swap over {tick_array_count} tick arrays");
+    let budget = proxy_swap_compute_units(tick_array_count);
+    let units = measure(&mut env, &name, ix, budget).await;
+    (tick_array_count, units)
+}
+
+#[tokio::test]
+#[ignore = "needs the SBF builds of the programs in SBF_OUT_DIR"]
+async fn proxy_swap_within_budget_per_tick_array() {
+    // Tick 0 is in an uninitialized array, the swap starts in [-600, 0) and crosses into
+    // one more array every 600 ticks
+    let mut results: Vec<(usize, u64)> = Vec::new();
+    for n in 1..=POSITIONS {
+        let limit_tick = -(n - 1) * TICK_ARRAY_TICKS - TICK_ARRAY_TICKS / 2;
+        let (tick_array_count, units) = measure_swap(limit_tick).await;
+        assert_eq!(tick_array_count, n as usize);
+        results.push((tick_array_count, units));
+    }
+
+    let (base_count, base_units) = results[0];
+    for (tick_array_count, units) in &results[1..] {
+        let per_tick_array =
+            units.saturating_sub(base_units) / (tick_array_count - base_count) as u64;
+        println!("proxy_swap: {per_tick_array} CU per tick array, over {tick_array_count}");
+        assert!(
+            per_tick_array <= PROXY_SWAP_COMPUTE_UNITS_PER_TICK_ARRAY as u64,
+            "proxy_swap used {per_tick_array} CU per tick array, over its \
+             {PROXY_SWAP_COMPUTE_UNITS_PER_TICK_ARRAY} CU budget"
+        );
+    }
+}
+
+/// Quotes a swap of `amount` of `input_mint` on a [`create_liquid_pool`] pool, from its tick
+/// arrays within the position bounds.
+async fn quote_in_pool(
+    env: &mut TestEnv,
+    pool: &ClmmPool,
+    input_mint: &Pubkey,
+    amount: u64,
+) -> SwapQuote {
+    let pool_state: PoolState = env.get_zero_copy(&pool.pool_state).await;
+    let amm_config: AmmConfig = env.get_anchor_account(&pool.amm_config).await;
+    let mut tick_arrays: Vec<TickArrayState> = Vec::new();
+    for k in POSITION_TICK_LOWER / TICK_ARRAY_TICKS..=POSITION_TICK_UPPER / TICK_ARRAY_TICKS {
+        if let Some(account) = env.get_account(&pool.tick_array(k * TICK_ARRAY_TICKS)).await {
+            tick_arrays.extend(load_zero_copy::<TickArrayState>(&account.data));
+        }
+    }
+    quote_swap(
+        pool,
+        &pool_state,
+        &amm_config,
+        &tick_arrays,
+        None,
+        QuoteParams {
+            amount,
+            zero_for_one: *input_mint == pool.token_mint_0,
+            is_base_input: true,
+            sqrt_price_limit_x64: 0,
+            block_timestamp: 0,
+        },
+    )
+    .unwrap()
+}
+
+#[tokio::test]
+#[ignore = "needs the SBF builds of the programs in SBF_OUT_DIR"]
+async fn execute_dca_within_budget() {
+    let mut env = TestEnv::new_sbf().await;
+    let market = create_earn_market(&mut env, 6).await;
+    let input_mint = env.create_mint(6).await;
+    let pool = create_liquid_pool(&mut env, input_mint, market.mint, LIQUIDITY_AMOUNT).await;
+    let owner = env.payer.pubkey();
+    // Deposited into Earn, the output takes the costlier path
+    let args = DcaOrderArgs {
+        amount: 2 * ORDER_AMOUNT,
+        amount_per_interval: ORDER_AMOUNT,
+        min_output_per_interval: ORDER_AMOUNT * 99 / 100,
+        interval: 3_600,
+        tip_lamports: 0,
+    };
+    let ix = open_dca_order(&pool, &input_mint, &owner, ORDER_ID, Some(&market), args);
+    env.process(&[ix], &[]).await.unwrap();
+
+    let quote = quote_in_pool(&mut env, &pool, &input_mint, ORDER_AMOUNT).await;
+    let ix = execute_dca(
+        &pool,
+        &input_mint,
+        &owner,
+        &owner,
+        ORDER_ID,
+        Some(&market),
+        &quote.tick_arrays,
+        quote.needs_bitmap_extension,
+    );
+    let budget = compute_units_with_tick_arrays(EXECUTE_DCA_COMPUTE_UNITS, quote.tick_arrays.len());
+    measure(&mut env, "execute_dca", ix, budget).await;
+}
+
+#[tokio::test]
+#[ignore = "needs the SBF builds of the programs in SBF_OUT_DIR"]
+async fn fill_limit_order_within_budget() {
+    let mut env = TestEnv::new_sbf().await;
+    let market = create_earn_market(&mut env, 6).await;
+    let output_mint = env.create_mint(6).await;
+    let pool = create_liquid_pool(&mut env, market.mint, output_mint, LIQUIDITY_AMOUNT).await;
+    let owner = env.payer.pubkey();
+    // Escrowed in Earn with a limit 1 bp of price away, the fill stops after a part of the
+    // order and deposits the rest back
+    let sqrt_price_limit_x64 = if pool.token_mint_0 == market.mint {
+        SQRT_PRICE_ONE_X64 - SQRT_PRICE_ONE_X64 / 20_000
+    } else {
+        SQRT_PRICE_ONE_X64 + SQRT_PRICE_ONE_X64 / 20_000
+    };
+    let ix = open_limit_order(
+        &pool,
+        &market.mint,
+        &owner,
+        ORDER_ID,
+        Some(&market),
+        ORDER_AMOUNT,
+        sqrt_price_limit_x64,
+    );
+    env.process(&[ix], &[]).await.unwrap();
+
+    let quote = quote_in_pool(&mut env, &pool, &market.mint, ORDER_AMOUNT).await;
+    let ix = fill_limit_order(
+        &pool,
+        &market.mint,
+        &owner,
+        &owner,
+        ORDER_ID,
+        Some(&market),
+        &quote.tick_arrays,
+        quote.needs_bitmap_extension,
+    );
+    let budget =
+        compute_units_with_tick_arrays(FILL_LIMIT_ORDER_COMPUTE_UNITS, quote.tick_arrays.len());
+    measure(&mut env, "fill_limit_order", ix, budget).await;
+}
+
+#[tokio::test]
+#[ignore = "needs the SBF builds of the programs in SBF_OUT_DIR"]
+async fn execute_trigger_within_budget() {
+    let mut env = TestEnv::new_sbf().await;
+    let market = create_earn_market(&mut env, 6).await;
+    let output_mint = env.create_mint(6).await;
+    let pool = create_liquid_pool(&mut env, market.mint, output_mint, LIQUIDITY_AMOUNT).await;
+    let owner = env.payer.pubkey();
+    env.create_ata(&owner, &market.f_token_mint(), &market.token_program)
+        .await;
+    env.process(&[deposit_earn(&market, &owner, ORDER_AMOUNT)], &[])
+        .await
+        .unwrap();
+    initialize_observation(&mut env, &pool, 300).await;
+    // A stop loss 100 ticks above the price of the underlying, reached at once
+    let trigger_tick = if pool.token_mint_0 == market.mint {
+        100
+    } else {
+        -100
+    };
+    let args = TriggerOrderArgs {
+        shares: ORDER_AMOUNT,
+        kind: TriggerKind::StopLoss,
+        trigger_tick,
+        twap_window: 300,
+        max_slippage_bps: 100,
+    };
+    let ix = open_trigger_order(&market, &pool, &owner, ORDER_ID, args);
+    env.process(&[ix], &[]).await.unwrap();
+
+    let quote = quote_in_pool(&mut env, &pool, &market.mint, ORDER_AMOUNT).await;
+    let ix = execute_trigger(
+        &market,
+        &pool,
+        &owner,
+        &owner,
+        ORDER_ID,
+        &quote.tick_arrays,
+        quote.needs_bitmap_extension,
+    );
+    let budget =
+        compute_units_with_tick_arrays(EXECUTE_TRIGGER_COMPUTE_UNITS, quote.tick_arrays.len());
+    measure(&mut env, "execute_trigger", ix, budget).await;
+}
+
+#[tokio::test]
+#[ignore = "needs the SBF builds of the programs in SBF_OUT_DIR"]
+async fn reallocate_within_budget() {
+    let mut env = TestEnv::new_sbf().await;
+    let from = create_earn_market(&mut env, 6).await;
+    let to = create_earn_market(&mut env, 6).await;
+    let pool = create_liquid_pool(&mut env, from.mint, to.mint, LIQUIDITY_AMOUNT).await;
+    let authority = env.payer.pubkey();
+    // The payer is its own keeper. Both fresh mock markets pay the same APY, which a zero
+    // gap accepts.
+    let instructions = [
+        initialize_allocator(&authority, &authority, 0, 100),
+        add_allocator_market(&from, &authority),
+        add_allocator_market(&to, &authority),
+        allocator_deposit(&from, &authority, ORDER_AMOUNT),
+    ];
+    env.process(&instructions, &[]).await.unwrap();
+
+    let quote = quote_in_pool(&mut env, &pool, &from.mint, ORDER_AMOUNT).await;
+    let ix = reallocate(
+        &from,
+        &to,
+        &pool,
+        &authority,
+        &authority,
+        &quote.tick_arrays,
+        quote.needs_bitmap_extension,
+        ORDER_AMOUNT,
+    );
+    let budget = compute_units_with_tick_arrays(REALLOCATE_COMPUTE_UNITS, quote.tick_arrays.len());
+    measure(&mut env, "reallocate", ix, budget).await;
+}
+
+/// Liquidity of the strategy positions, small against the pool so the strategy swaps stay
+/// within its slippage
+const STRATEGY_LIQUIDITY: u128 = 100_000_000;
+/// Width of the ranges `rebalance_range` opens
+const RANGE_WIDTH: i32 = 200;
+
+/// A strategy of the payer on a [`create_liquid_pool`] pool of a mock Earn market
+/// underlying, with a TWAP over the default window and a position between `tick_lower` and
+/// `tick_upper` funded by the payer.
+async fn setup_strategy(
+    env: &mut TestEnv,
+    tick_lower: i32,
+    tick_upper: i32,
+) -> (ClmmStrategy, StrategyPosition) {
+    let admin = env.payer.pubkey();
+    let market = create_earn_market(env, 6).await;
+    let pair_mint = env.create_mint(6).await;
+    let pool = create_liquid_pool(env, market.mint, pair_mint, LIQUIDITY_AMOUNT).await;
+    let strategy = ClmmStrategy::new(market, pool);
+    env.process(&[initialize_strategy(&strategy, &admin, 3_600, 100)], &[])
+        .await
+        .unwrap();
+    env.create_ata(&admin, &strategy.share_mint(), &token::ID)
+        .await;
+    initialize_observation(env, &pool, 300).await;
+
+    let nft_mint = Keypair::new();
+    let position = StrategyPosition {
+        nft_mint: nft_mint.pubkey(),
+        tick_lower_index: tick_lower,
+        tick_upper_index: tick_upper,
+    };
+    let ix = open_strategy_position(
+        &strategy,
+        &admin,
+        &position,
+        STRATEGY_LIQUIDITY,
+        LIQUIDITY_AMOUNT,
+        LIQUIDITY_AMOUNT,
+        false,
+    );
+    env.process(&[ix], &[&nft_mint]).await.unwrap();
+    (strategy, position)
+}
+
+#[tokio::test]
+#[ignore = "needs the SBF builds of the programs in SBF_OUT_DIR"]
+async fn crank_compound_steps_within_budget() {
+    let mut env = TestEnv::new_sbf().await;
+    let (strategy, position) =
+        setup_strategy(&mut env, -TICK_ARRAY_TICKS, TICK_ARRAY_TICKS).await;
+    let pool = strategy.pool;
+    let pair_mint = strategy.pair_mint();
+    let cranker = env.payer.pubkey();
+    // Fees of a swap selling the pair token, for the harvest to collect and the swap step to
+    // sell back. The TWAP then settles on the new price.
+    let amount = 10 * ORDER_AMOUNT;
+    let quote = quote_in_pool(&mut env, &pool, &pair_mint, amount).await;
+    let ix = proxy_swap(
+        &pool,
+        &cranker,
+        &pair_mint,
+        &quote.tick_arrays,
+        quote.needs_bitmap_extension,
+        SwapArgs {
+            amount,
+            other_amount_threshold: 0,
+            sqrt_price_limit_x64: 0,
+            is_base_input: true,
+        },
+    );
+    env.process(&[ix], &[]).await.unwrap();
+    let now = env.unix_timestamp().await;
+    env.set_unix_timestamp(now + 3_600).await;
+
+    let harvest = crank_compound(&strategy, &cranker, Some(&position), Vec::new());
+    measure(&mut env, "crank_compound harvest", harvest, CRANK_COMPOUND_COMPUTE_UNITS).await;
+
+    let pair_amount = env.token_balance(&strategy.pair_vault()).await;
+    assert!(pair_amount > 0, "no pair fees to swap");
+    let quote = quote_in_pool(&mut env, &pool, &pair_mint, pair_amount).await;
+    let remaining =
+        swap_remaining_accounts(&pool, &quote.tick_arrays, quote.needs_bitmap_extension);
+    let swap = crank_compound(&strategy, &cranker, Some(&position), remaining);
+    let budget =
+        compute_units_with_tick_arrays(CRANK_COMPOUND_COMPUTE_UNITS, quote.tick_arrays.len());
+    measure(&mut env, "crank_compound swap", swap, budget).await;
+
+    let deposit = crank_compound(&strategy, &cranker, Some(&position), Vec::new());
+    measure(&mut env, "crank_compound deposit", deposit, CRANK_COMPOUND_COMPUTE_UNITS).await;
+}
+
+#[tokio::test]
+#[ignore = "needs the SBF builds of the programs in SBF_OUT_DIR"]
+async fn rebalance_range_within_budget() {
+    let mut env = TestEnv::new_sbf().await;
+    // Below the pool price, the position is out of range from the start
+    let (strategy, position) = setup_strategy(&mut env, -TICK_ARRAY_TICKS, -RANGE_WIDTH).await;
+    let pool = strategy.pool;
+    let keeper = env.payer.pubkey();
+    let ix = set_rebalance_config(&strategy.address(), &keeper, RANGE_WIDTH, 300, 100);
+    env.process(&[ix], &[]).await.unwrap();
+
+    let pool_state: PoolState = env.get_zero_copy(&pool.pool_state).await;
+    let (tick_lower, tick_upper) =
+        get_centered_range(pool_state.tick_current, pool.tick_spacing, RANGE_WIDTH).unwrap();
+    let new_nft_mint = Keypair::new();
+    let new_position = StrategyPosition {
+        nft_mint: new_nft_mint.pubkey(),
+        tick_lower_index: tick_lower,
+        tick_upper_index: tick_upper,
+    };
+    // Closed below the price, the position is all token 1, part of which is swapped into
+    // token 0. The quote is sized over what it holds.
+    let quote = quote_in_pool(&mut env, &pool, &pool.token_mint_1, 10 * ORDER_AMOUNT).await;
+    let ix = rebalance_range(
+        &strategy,
+        &keeper,
+        &position,
+        &new_position,
+        &[],
+        &quote.tick_arrays,
+        quote.needs_bitmap_extension,
+    );
+    let budget =
+        compute_units_with_tick_arrays(REBALANCE_RANGE_COMPUTE_UNITS, quote.tick_arrays.len());
+    measure_signed(&mut env, "rebalance_range", ix, budget, &[&new_nft_mint]).await;
+}
@@ -0,0 +1,58 @@
+use anchor_lang::error::ErrorCode;
+use interact_dapp::error::InteractDappError;
+use interact_dapp::states::Config;
+use interact_dapp_client::{deposit_earn, get_config_address, update_config};
+use interact_dapp_tests::{create_earn_market, custom_error_code, TestEnv};
+use solana_sdk::signature::{Keypair, Signer};
+
+#[tokio::test]
+async fn pause_stops_deposits_until_resumed() {
+    let mut env = TestEnv::new().await;
+    let market = create_earn_market(&mut env, 6).await;
+    let admin = env.payer.pubkey();
+    let token_account = env.mint_to(&market.mint, &admin, 1_000).await;
+    env.create_ata(&admin, &market.f_token_mint(), &market.token_program)
+        .await;
+
+    env.process(&[update_config(&admin, Some(true), None)], &[])
+        .await
+        .unwrap();
+    let result = env.process(&[deposit_earn(&market, &admin, 1_000)], &[]).await;
+    assert_eq!(custom_error_code(result), Some(InteractDappError::ProgramPaused.into()));
+
+    env.process(&[update_config(&admin, Some(false), None)], &[])
+        .await
+        .unwrap();
+    env.process(&[deposit_earn(&market, &admin, 1_000)], &[])
+        .await
+        .unwrap();
+    assert_eq!(env.token_balance(&token_account).await, 0);
+}
+
+#[tokio::test]
+async fn update_config_bounds_the_fee() {
+    let mut env = TestEnv::new().await;
+    let admin = env.payer.pubkey();
+
+    let result = env.process(&[update_config(&admin, None, Some(1_001))], &[]).await;
+    assert_eq!(custom_error_code(result), Some(InteractDappError::InvalidFeeBps.into()));
+
+    env.process(&[update_config(&admin, None, Some(30))], &[])
+        .await
+        .unwrap();
+    let config: Config = env.get_anchor_account(&get_config_address()).await;
+    assert_eq!((config.paused, config.fee_bps), (false, 30));
+}
+
+#[tokio::test]
+async fn update_config_requires_admin() {
+    let mut env = TestEnv::new().await;
+    let intruder = Keypair::new();
+
+    let ix = update_config(&intruder.pubkey(), Some(true), None);
+    let result = env.process(&[ix], &[&intruder]).await;
+    assert_eq!(custom_error_code(result), Some(ErrorCode::ConstraintHasOne.into()));
+
+    let config: Config = env.get_anchor_account(&get_config_address()).await;
+    assert!(!config.paused);
+}
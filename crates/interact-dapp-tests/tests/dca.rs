@@ -0,0 +1,85 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_spl::associated_token::get_associated_token_address;
+use interact_dapp::error::InteractDappError;
+use interact_dapp::states::DcaOrder;
+use interact_dapp_client::{get_dca_order_address, open_dca_order, ClmmPool, DcaOrderArgs};
+use interact_dapp_tests::{create_liquid_pool, custom_error_code, position_tick_arrays, TestEnv};
+use solana_sdk::signature::Signer;
+
+const LIQUIDITY_AMOUNT: u64 = 1_000_000_000;
+const ORDER_ID: u64 = 1;
+const AMOUNT_PER_INTERVAL: u64 = 1_000_000;
+const INTERVAL: i64 = 3_600;
+
+/// Opens a DCA order of the payer selling token 0 of a new pool in three legs.
+async fn setup() -> (TestEnv, ClmmPool) {
+    let mut env = TestEnv::new().await;
+    let mint_a = env.create_mint(6).await;
+    let mint_b = env.create_mint(6).await;
+    let pool = create_liquid_pool(&mut env, mint_a, mint_b, LIQUIDITY_AMOUNT).await;
+    let owner = env.payer.pubkey();
+    let args = DcaOrderArgs {
+        amount: 3 * AMOUNT_PER_INTERVAL,
+        amount_per_interval: AMOUNT_PER_INTERVAL,
+        min_output_per_interval: AMOUNT_PER_INTERVAL * 99 / 100,
+        interval: INTERVAL,
+        tip_lamports: 0,
+    };
+    let ix = open_dca_order(&pool, &pool.token_mint_0, &owner, ORDER_ID, None, args);
+    env.process(&[ix], &[]).await.unwrap();
+    (env, pool)
+}
+
+fn execute_dca(pool: &ClmmPool, cranker: &Pubkey, owner: &Pubkey) -> Instruction {
+    let tick_arrays = position_tick_arrays(pool, true);
+    interact_dapp_client::execute_dca(
+        pool,
+        &pool.token_mint_0,
+        cranker,
+        owner,
+        ORDER_ID,
+        None,
+        &tick_arrays,
+        false,
+    )
+}
+
+#[tokio::test]
+async fn execute_dca_runs_due_leg() {
+    let (mut env, pool) = setup().await;
+    let owner = env.payer.pubkey();
+    let dca_order = get_dca_order_address(&owner, ORDER_ID);
+    let now = env.unix_timestamp().await;
+
+    env.process(&[execute_dca(&pool, &owner, &owner)], &[])
+        .await
+        .unwrap();
+
+    let input_vault = get_associated_token_address(&dca_order, &pool.token_mint_0);
+    let output_vault = get_associated_token_address(&dca_order, &pool.token_mint_1);
+    assert_eq!(env.token_balance(&input_vault).await, 2 * AMOUNT_PER_INTERVAL);
+    let amount_out = env.token_balance(&output_vault).await;
+    assert!(amount_out >= AMOUNT_PER_INTERVAL * 99 / 100 && amount_out < AMOUNT_PER_INTERVAL);
+    let order: DcaOrder = env.get_anchor_account(&dca_order).await;
+    assert_eq!(order.total_input_spent, AMOUNT_PER_INTERVAL);
+    assert_eq!(order.total_output_received, amount_out);
+    assert!(order.next_execution_timestamp >= now + INTERVAL);
+}
+
+#[tokio::test]
+async fn execute_dca_fails_before_interval() {
+    let (mut env, pool) = setup().await;
+    let owner = env.payer.pubkey();
+    env.process(&[execute_dca(&pool, &owner, &owner)], &[])
+        .await
+        .unwrap();
+
+    let result = env.process(&[execute_dca(&pool, &owner, &owner)], &[]).await;
+    assert_eq!(custom_error_code(result), Some(InteractDappError::OrderNotDue.into()));
+    let input_vault = get_associated_token_address(
+        &get_dca_order_address(&owner, ORDER_ID),
+        &pool.token_mint_0,
+    );
+    assert_eq!(env.token_balance(&input_vault).await, 2 * AMOUNT_PER_INTERVAL);
+}
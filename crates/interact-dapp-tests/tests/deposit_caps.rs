@@ -0,0 +1,49 @@
+use anchor_lang::error::ErrorCode;
+use interact_dapp::states::DepositCaps;
+use interact_dapp_client::{get_deposit_caps_address, update_deposit_caps};
+use interact_dapp_tests::{create_earn_market, custom_error_code, set_config, TestEnv};
+use solana_sdk::signature::{Keypair, Signer};
+
+#[tokio::test]
+async fn admin_sets_deposit_caps() {
+    let mut env = TestEnv::new().await;
+    let admin = env.payer.pubkey();
+    set_config(&mut env, &admin).await;
+    // Created uncapped with the market
+    let market = create_earn_market(&mut env, 6).await;
+    let caps: DepositCaps = env
+        .get_anchor_account(&get_deposit_caps_address(&market.mint))
+        .await;
+    assert_eq!((caps.mint, caps.lending), (market.mint, market.lending()));
+    assert_eq!((caps.global_cap, caps.per_user_cap), (u64::MAX, u64::MAX));
+
+    env.process(&[update_deposit_caps(&market.mint, &admin, 2_000_000, 500_000)], &[])
+        .await
+        .unwrap();
+
+    let caps: DepositCaps = env
+        .get_anchor_account(&get_deposit_caps_address(&market.mint))
+        .await;
+    assert_eq!((caps.global_cap, caps.per_user_cap), (2_000_000, 500_000));
+}
+
+#[tokio::test]
+async fn update_deposit_caps_requires_admin() {
+    let mut env = TestEnv::new().await;
+    let admin = env.payer.pubkey();
+    set_config(&mut env, &admin).await;
+    let market = create_earn_market(&mut env, 6).await;
+    env.process(&[update_deposit_caps(&market.mint, &admin, 1_000_000, 100_000)], &[])
+        .await
+        .unwrap();
+
+    let intruder = Keypair::new();
+    let ix = update_deposit_caps(&market.mint, &intruder.pubkey(), u64::MAX, u64::MAX);
+    let result = env.process(&[ix], &[&intruder]).await;
+    assert_eq!(custom_error_code(result), Some(ErrorCode::ConstraintHasOne.into()));
+
+    let caps: DepositCaps = env
+        .get_anchor_account(&get_deposit_caps_address(&market.mint))
+        .await;
+    assert_eq!((caps.global_cap, caps.per_user_cap), (1_000_000, 100_000));
+}
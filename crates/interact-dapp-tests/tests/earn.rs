@@ -0,0 +1,82 @@
+use anchor_lang::prelude::Pubkey;
+use interact_dapp_client::{deposit_earn, withdraw_earn, EarnMarket};
+use interact_dapp_tests::{create_earn_market, set_token_exchange_price, TestEnv};
+use solana_sdk::signature::Signer;
+
+#[tokio::test]
+async fn deposit_earn_mints_f_tokens() {
+    let mut env = TestEnv::new().await;
+    let market = create_earn_market(&mut env, 6).await;
+    let user = env.payer.pubkey();
+    let token_account = env.mint_to(&market.mint, &user, 1_000_000).await;
+    let f_token_account = env
+        .create_ata(&user, &market.f_token_mint(), &market.token_program)
+        .await;
+
+    env.process(&[deposit_earn(&market, &user, 400_000)], &[])
+        .await
+        .unwrap();
+
+    assert_eq!(env.token_balance(&token_account).await, 600_000);
+    assert_eq!(env.token_balance(&f_token_account).await, 400_000);
+    assert_eq!(env.token_balance(&market.vault()).await, 400_000);
+}
+
+#[tokio::test]
+async fn withdraw_earn_burns_shares_at_exchange_price() {
+    let mut env = TestEnv::new().await;
+    let market = create_earn_market(&mut env, 6).await;
+    let user = env.payer.pubkey();
+    let token_account = env.mint_to(&market.mint, &user, 1_000_000).await;
+    let f_token_account = env
+        .create_ata(&user, &market.f_token_mint(), &market.token_program)
+        .await;
+    env.process(&[deposit_earn(&market, &user, 1_000_000)], &[])
+        .await
+        .unwrap();
+    // Seed the vault with the yield the new price pays out
+    env.mint_to(&market.mint, &market.liquidity(), 100_000).await;
+    set_token_exchange_price(&mut env, &market, 1_100_000_000_000).await;
+
+    env.process(&[withdraw_earn(&market, &user, 220_000)], &[])
+        .await
+        .unwrap();
+
+    assert_eq!(env.token_balance(&token_account).await, 220_000);
+    assert_eq!(env.token_balance(&f_token_account).await, 800_000);
+}
+
+#[tokio::test]
+async fn withdraw_earn_fails_above_position() {
+    let mut env = TestEnv::new().await;
+    let market = create_earn_market(&mut env, 6).await;
+    let user = env.payer.pubkey();
+    env.mint_to(&market.mint, &user, 1_000).await;
+    env.create_ata(&user, &market.f_token_mint(), &market.token_program)
+        .await;
+    env.process(&[deposit_earn(&market, &user, 1_000)], &[])
+        .await
+        .unwrap();
+
+    assert!(env
+        .process(&[withdraw_earn(&market, &user, 1_001)], &[])
+        .await
+        .is_err());
+}
+
+#[tokio::test]
+async fn deposit_earn_rejects_other_lending_program() {
+    let mut env = TestEnv::new().await;
+    let market = create_earn_market(&mut env, 6).await;
+    let user = env.payer.pubkey();
+    env.mint_to(&market.mint, &user, 1_000).await;
+    let other = EarnMarket {
+        lending_program: Pubkey::new_unique(),
+        ..market
+    };
+
+    assert!(env
+        .process(&[deposit_earn(&other, &user, 1_000)], &[])
+        .await
+        .is_err());
+}
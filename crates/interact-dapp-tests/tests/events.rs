@@ -0,0 +1,209 @@
+use anchor_lang::prelude::*;
+use anchor_lang::Discriminator;
+use base64::{engine::general_purpose::STANDARD, Engine};
+use interact_dapp::events::{DepositEarnEvent, ProxySwapEvent, WithdrawEarnEvent};
+use interact_dapp::jupiter_lend::events::{LogDeposit, LogWithdraw};
+use interact_dapp_client::{
+    reconcile, DecodedEvent, EarnAction, EventDecoder, InnerInstructionData, InteractDappEvent,
+    JupiterLendEvent, EVENT_IX_TAG_LE,
+};
+
+const LENDING_PROGRAM: Pubkey = interact_dapp::programs::JUPITER_LENDING_PROGRAM_ID;
+
+fn event_data<T: AnchorSerialize + Discriminator>(event: &T) -> Vec<u8> {
+    let mut data = T::DISCRIMINATOR.to_vec();
+    event.serialize(&mut data).unwrap();
+    data
+}
+
+/// Logs of an interact_dapp instruction emitting `ours` after a Jupiter CPI emitting
+/// `theirs`, with an unrelated program emitting data before it.
+fn logs(theirs: Option<&[u8]>, ours: &[u8]) -> Vec<String> {
+    let (dapp, lending, other) = (interact_dapp::ID, LENDING_PROGRAM, Pubkey::new_unique());
+    let mut logs = vec![
+        format!("Program {other} invoke [1]"),
+        format!("Program data: {}", STANDARD.encode(ours)),
+        format!("Program {other} success"),
+        format!("Program {dapp} invoke [1]"),
+    ];
+    if let Some(theirs) = theirs {
+        logs.extend([
+            format!("Program {lending} invoke [2]"),
+            format!("Program data: {}", STANDARD.encode(theirs)),
+            format!("Program {lending} consumed 30000 of 170000 compute units"),
+            format!("Program {lending} success"),
+        ]);
+    }
+    logs.extend([
+        format!("Program data: {}", STANDARD.encode(ours)),
+        format!("Program {dapp} consumed 50000 of 200000 compute units"),
+        format!("Program {dapp} success"),
+    ]);
+    logs
+}
+
+/// The same events emitted through self-CPI instructions.
+fn inner_instructions(theirs: Option<&[u8]>, ours: &[u8]) -> Vec<InnerInstructionData> {
+    let event_ix = |program_id: Pubkey, data: &[u8]| InnerInstructionData {
+        program_id,
+        data: [&EVENT_IX_TAG_LE[..], data].concat(),
+    };
+    let mut instructions = vec![
+        // A token transfer made by the CPI, not an event
+        InnerInstructionData {
+            program_id: anchor_spl::token::ID,
+            data: vec![3, 232, 3, 0, 0, 0, 0, 0, 0],
+        },
+    ];
+    instructions.extend(theirs.map(|theirs| event_ix(LENDING_PROGRAM, theirs)));
+    instructions.push(event_ix(interact_dapp::ID, ours));
+    instructions
+}
+
+/// Events decoded from the logs and from the inner instructions.
+fn decode_both(theirs: Option<&[u8]>, ours: &[u8]) -> [Vec<DecodedEvent>; 2] {
+    let decoder = EventDecoder::new(LENDING_PROGRAM);
+    [
+        decoder.decode_logs(&logs(theirs, ours)),
+        decoder.decode_inner_instructions(&inner_instructions(theirs, ours)),
+    ]
+}
+
+#[test]
+fn deposit_events_round_trip() {
+    let (signer, mint) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let theirs = LogDeposit {
+        sender: signer,
+        receiver: signer,
+        assets: 1_000_000,
+        shares_minted: 990_000,
+    };
+    let ours = DepositEarnEvent {
+        signer,
+        mint,
+        amount: 1_000_000,
+        assets_deposited: 1_000_000,
+        shares_minted: 990_000,
+    };
+
+    for events in decode_both(Some(&event_data(&theirs)), &event_data(&ours)) {
+        let [first, second] = &events[..] else {
+            panic!("expected two events, got {}", events.len());
+        };
+        let DecodedEvent::JupiterLend(JupiterLendEvent::Deposit(theirs)) = first else {
+            panic!("expected a LogDeposit first");
+        };
+        let DecodedEvent::InteractDapp(InteractDappEvent::DepositEarn(ours)) = second else {
+            panic!("expected a DepositEarnEvent second");
+        };
+        assert_eq!((theirs.sender, theirs.receiver), (signer, signer));
+        assert_eq!((theirs.assets, theirs.shares_minted), (1_000_000, 990_000));
+        assert_eq!((ours.signer, ours.mint, ours.amount), (signer, mint, 1_000_000));
+        assert_eq!((ours.assets_deposited, ours.shares_minted), (1_000_000, 990_000));
+
+        let reconciliations = reconcile(&events);
+        assert_eq!(reconciliations.len(), 1);
+        let reconciliation = &reconciliations[0];
+        assert_eq!(reconciliation.action, EarnAction::Deposit);
+        assert_eq!(reconciliation.signer, signer);
+        assert!(reconciliation.is_consistent());
+    }
+}
+
+#[test]
+fn withdraw_events_round_trip() {
+    let (signer, mint) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let theirs = LogWithdraw {
+        sender: signer,
+        receiver: signer,
+        owner: signer,
+        assets: 500_000,
+        shares_burned: 454_546,
+    };
+    let ours = WithdrawEarnEvent {
+        signer,
+        mint,
+        amount: 500_000,
+        assets_withdrawn: 500_000,
+        shares_burned: 454_546,
+    };
+
+    for events in decode_both(Some(&event_data(&theirs)), &event_data(&ours)) {
+        let [first, second] = &events[..] else {
+            panic!("expected two events, got {}", events.len());
+        };
+        let DecodedEvent::JupiterLend(JupiterLendEvent::Withdraw(theirs)) = first else {
+            panic!("expected a LogWithdraw first");
+        };
+        let DecodedEvent::InteractDapp(InteractDappEvent::WithdrawEarn(ours)) = second else {
+            panic!("expected a WithdrawEarnEvent second");
+        };
+        assert_eq!((theirs.sender, theirs.receiver, theirs.owner), (signer, signer, signer));
+        assert_eq!((theirs.assets, theirs.shares_burned), (500_000, 454_546));
+        assert_eq!((ours.signer, ours.mint, ours.amount), (signer, mint, 500_000));
+        assert_eq!((ours.assets_withdrawn, ours.shares_burned), (500_000, 454_546));
+
+        let reconciliations = reconcile(&events);
+        assert_eq!(reconciliations.len(), 1);
+        assert_eq!(reconciliations[0].action, EarnAction::Withdraw);
+        assert!(reconciliations[0].is_consistent());
+    }
+}
+
+#[test]
+fn proxy_swap_event_round_trips() {
+    let ours = ProxySwapEvent {
+        payer: Pubkey::new_unique(),
+        pool_state: Pubkey::new_unique(),
+        input_mint: Pubkey::new_unique(),
+        output_mint: Pubkey::new_unique(),
+        amount_in: 1_000_000,
+        amount_out: 997_000,
+        is_base_input: true,
+    };
+
+    for events in decode_both(None, &event_data(&ours)) {
+        let [DecodedEvent::InteractDapp(InteractDappEvent::ProxySwap(event))] = &events[..] else {
+            panic!("expected a ProxySwapEvent");
+        };
+        assert_eq!((event.payer, event.pool_state), (ours.payer, ours.pool_state));
+        assert_eq!((event.input_mint, event.output_mint), (ours.input_mint, ours.output_mint));
+        assert_eq!((event.amount_in, event.amount_out), (1_000_000, 997_000));
+        assert!(event.is_base_input);
+        assert!(reconcile(&events).is_empty());
+    }
+}
+
+#[test]
+fn reconcile_flags_mismatched_amounts() {
+    let signer = Pubkey::new_unique();
+    let theirs = LogDeposit {
+        sender: signer,
+        receiver: signer,
+        assets: 999_999,
+        shares_minted: 989_999,
+    };
+    // A fee on transfer mint, say, makes the depositor account send more than Jupiter got
+    let ours = DepositEarnEvent {
+        signer,
+        mint: Pubkey::new_unique(),
+        amount: 1_000_000,
+        assets_deposited: 1_000_000,
+        shares_minted: 989_999,
+    };
+
+    for events in decode_both(Some(&event_data(&theirs)), &event_data(&ours)) {
+        let reconciliations = reconcile(&events);
+        assert_eq!(reconciliations.len(), 1);
+        let reconciliation = &reconciliations[0];
+        assert!(!reconciliation.is_consistent());
+        assert_eq!(
+            (reconciliation.reported_assets, reconciliation.protocol_assets),
+            (1_000_000, 999_999)
+        );
+        assert_eq!(
+            (reconciliation.reported_shares, reconciliation.protocol_shares),
+            (989_999, 989_999)
+        );
+    }
+}
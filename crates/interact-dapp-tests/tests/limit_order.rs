@@ -0,0 +1,156 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_spl::associated_token::get_associated_token_address;
+use interact_dapp::error::InteractDappError;
+use interact_dapp::states::{DepositCaps, LimitOrder};
+use interact_dapp_client::{
+    get_deposit_caps_address, get_limit_order_address, open_limit_order, ClmmPool, EarnMarket,
+};
+use interact_dapp_tests::{
+    create_earn_market, create_liquid_pool, custom_error_code, position_tick_arrays, TestEnv,
+    SQRT_PRICE_ONE_X64,
+};
+use solana_sdk::signature::Signer;
+
+const LIQUIDITY_AMOUNT: u64 = 1_000_000_000;
+const ORDER_ID: u64 = 1;
+const ORDER_AMOUNT: u64 = 1_000_000;
+
+/// Opens a limit order of the payer selling token 0 of a new 1:1 pool down to
+/// `sqrt_price_limit_x64`.
+async fn setup(sqrt_price_limit_x64: u128) -> (TestEnv, ClmmPool) {
+    let mut env = TestEnv::new().await;
+    let mint_a = env.create_mint(6).await;
+    let mint_b = env.create_mint(6).await;
+    let pool = create_liquid_pool(&mut env, mint_a, mint_b, LIQUIDITY_AMOUNT).await;
+    let owner = env.payer.pubkey();
+    let ix = open_limit_order(
+        &pool,
+        &pool.token_mint_0,
+        &owner,
+        ORDER_ID,
+        None,
+        ORDER_AMOUNT,
+        sqrt_price_limit_x64,
+    );
+    env.process(&[ix], &[]).await.unwrap();
+    (env, pool)
+}
+
+fn fill_limit_order(pool: &ClmmPool, keeper: &Pubkey, owner: &Pubkey) -> Instruction {
+    let tick_arrays = position_tick_arrays(pool, true);
+    interact_dapp_client::fill_limit_order(
+        pool,
+        &pool.token_mint_0,
+        keeper,
+        owner,
+        ORDER_ID,
+        None,
+        &tick_arrays,
+        false,
+    )
+}
+
+/// Opens a limit order of the payer selling the underlying of a new Earn market, escrowed in
+/// Earn, in a new 1:1 pool. The limit is 1 bp of price away from the pool price, so a fill
+/// stops after a part of the order.
+async fn setup_earn() -> (TestEnv, EarnMarket, ClmmPool) {
+    let mut env = TestEnv::new().await;
+    let market = create_earn_market(&mut env, 6).await;
+    let output_mint = env.create_mint(6).await;
+    let pool = create_liquid_pool(&mut env, market.mint, output_mint, LIQUIDITY_AMOUNT).await;
+    let owner = env.payer.pubkey();
+    let sqrt_price_limit_x64 = if pool.token_mint_0 == market.mint {
+        SQRT_PRICE_ONE_X64 - SQRT_PRICE_ONE_X64 / 20_000
+    } else {
+        SQRT_PRICE_ONE_X64 + SQRT_PRICE_ONE_X64 / 20_000
+    };
+    let ix = open_limit_order(
+        &pool,
+        &market.mint,
+        &owner,
+        ORDER_ID,
+        Some(&market),
+        ORDER_AMOUNT,
+        sqrt_price_limit_x64,
+    );
+    env.process(&[ix], &[]).await.unwrap();
+    (env, market, pool)
+}
+
+fn fill_earn_limit_order(
+    market: &EarnMarket,
+    pool: &ClmmPool,
+    keeper: &Pubkey,
+    owner: &Pubkey,
+) -> Instruction {
+    let tick_arrays = position_tick_arrays(pool, pool.token_mint_0 == market.mint);
+    interact_dapp_client::fill_limit_order(
+        pool,
+        &market.mint,
+        keeper,
+        owner,
+        ORDER_ID,
+        Some(market),
+        &tick_arrays,
+        false,
+    )
+}
+
+#[tokio::test]
+async fn fill_limit_order_sells_above_limit() {
+    // The pool trades at 1, above a limit of about 0.98
+    let (mut env, pool) = setup(SQRT_PRICE_ONE_X64 - SQRT_PRICE_ONE_X64 / 100).await;
+    let owner = env.payer.pubkey();
+    let limit_order = get_limit_order_address(&owner, ORDER_ID);
+    let output_account = get_associated_token_address(&owner, &pool.token_mint_1);
+    let output_before = env.token_balance(&output_account).await;
+
+    env.process(&[fill_limit_order(&pool, &owner, &owner)], &[])
+        .await
+        .unwrap();
+
+    let input_vault = get_associated_token_address(&limit_order, &pool.token_mint_0);
+    assert_eq!(env.token_balance(&input_vault).await, 0);
+    let amount_out = env.token_balance(&output_account).await - output_before;
+    assert!(amount_out > 0 && amount_out < ORDER_AMOUNT);
+    let order: LimitOrder = env.get_anchor_account(&limit_order).await;
+    assert_eq!(order.filled_amount_in, ORDER_AMOUNT);
+    assert_eq!(order.filled_amount_out, amount_out);
+}
+
+#[tokio::test]
+async fn fill_limit_order_fails_below_limit() {
+    // The pool trades at 1, below a limit of about 1.02
+    let (mut env, pool) = setup(SQRT_PRICE_ONE_X64 + SQRT_PRICE_ONE_X64 / 100).await;
+    let owner = env.payer.pubkey();
+
+    let result = env.process(&[fill_limit_order(&pool, &owner, &owner)], &[]).await;
+    assert_eq!(
+        custom_error_code(result),
+        Some(InteractDappError::LimitPriceNotCrossed.into())
+    );
+}
+
+#[tokio::test]
+async fn partial_fill_keeps_remaining_escrow_in_earn() {
+    let (mut env, market, pool) = setup_earn().await;
+    let owner = env.payer.pubkey();
+    let limit_order = get_limit_order_address(&owner, ORDER_ID);
+
+    env.process(&[fill_earn_limit_order(&market, &pool, &owner, &owner)], &[])
+        .await
+        .unwrap();
+
+    let order: LimitOrder = env.get_anchor_account(&limit_order).await;
+    assert!(order.filled_amount_in > 0 && order.filled_amount_in < ORDER_AMOUNT);
+    // The unfilled input is back in Earn and still counted against the deposit caps
+    let input_vault = get_associated_token_address(&limit_order, &market.mint);
+    assert_eq!(env.token_balance(&input_vault).await, 0);
+    let shares = env.token_balance(&market.f_token_account(&limit_order)).await;
+    assert_eq!(shares, ORDER_AMOUNT - order.filled_amount_in);
+    let caps: DepositCaps = env
+        .get_anchor_account(&get_deposit_caps_address(&market.mint))
+        .await;
+    assert_eq!(caps.ledger.f_token_shares, shares);
+}
@@ -0,0 +1,102 @@
+use anchor_lang::prelude::Pubkey;
+use anchor_spl::token;
+use interact_dapp_client::{
+    compile_v0_message, create_market_lookup_table, deposit_earn, get_earn_lookup_table_addresses,
+    get_pool_lookup_table_addresses, proxy_swap, proxy_swap_compute_units, transaction_size,
+    with_compute_budget, ClmmPool, EarnMarket, SwapArgs, DEPOSIT_EARN_COMPUTE_UNITS,
+    MAX_ADDRESSES_PER_EXTEND,
+};
+use solana_sdk::address_lookup_table::AddressLookupTableAccount;
+use solana_sdk::hash::Hash;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::packet::PACKET_DATA_SIZE;
+use solana_sdk::signature::{Keypair, Signer};
+use solana_sdk::transaction::{Transaction, VersionedTransaction};
+
+/// A zap: swaps token 0 of a pool for token 1 and deposits it into the token 1 Earn market.
+fn zap(payer: &Pubkey) -> (ClmmPool, EarnMarket, Vec<Instruction>) {
+    let (mint_a, mint_b) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let pool = ClmmPool::new(
+        raydium_amm_v3::ID,
+        0,
+        mint_a.min(mint_b),
+        mint_a.max(mint_b),
+        token::ID,
+        token::ID,
+        10,
+    );
+    let market = EarnMarket::new(pool.token_mint_1, token::ID);
+    let tick_arrays = pool.swap_tick_arrays(0, true, 3);
+    let swap = proxy_swap(
+        &pool,
+        payer,
+        &pool.token_mint_0,
+        &tick_arrays,
+        true,
+        SwapArgs {
+            amount: 1_000_000,
+            other_amount_threshold: 990_000,
+            sqrt_price_limit_x64: 0,
+            is_base_input: true,
+        },
+    );
+    let deposit = deposit_earn(&market, payer, 990_000);
+    let units = proxy_swap_compute_units(tick_arrays.len()) + DEPOSIT_EARN_COMPUTE_UNITS;
+    let instructions = with_compute_budget(&[swap, deposit], units, None);
+    (pool, market, instructions)
+}
+
+fn market_addresses(pool: &ClmmPool, market: &EarnMarket) -> Vec<Pubkey> {
+    let mut addresses = get_pool_lookup_table_addresses(pool);
+    addresses.extend(get_earn_lookup_table_addresses(market));
+    addresses
+}
+
+#[test]
+fn zap_fits_in_packet_only_as_v0() {
+    let payer = Keypair::new();
+    let (pool, market, instructions) = zap(&payer.pubkey());
+    let blockhash = Hash::new_unique();
+
+    let legacy = Transaction::new_signed_with_payer(
+        &instructions,
+        Some(&payer.pubkey()),
+        &[&payer],
+        blockhash,
+    );
+    let legacy_size = transaction_size(&VersionedTransaction::from(legacy));
+    assert!(legacy_size > PACKET_DATA_SIZE, "legacy zap is {legacy_size} bytes");
+
+    let lookup_table = AddressLookupTableAccount {
+        key: Pubkey::new_unique(),
+        addresses: market_addresses(&pool, &market),
+    };
+    let message = compile_v0_message(&payer.pubkey(), &instructions, &[lookup_table], blockhash)
+        .unwrap();
+    let transaction = VersionedTransaction::try_new(message, &[&payer]).unwrap();
+    let v0_size = transaction_size(&transaction);
+    assert!(v0_size <= PACKET_DATA_SIZE, "v0 zap is {v0_size} bytes");
+}
+
+#[test]
+fn market_lookup_table_is_created_deduplicated_and_chunked() {
+    let authority = Pubkey::new_unique();
+    let (pool, market, _) = zap(&authority);
+    let addresses = market_addresses(&pool, &market);
+    let mut unique = addresses.clone();
+    unique.sort();
+    unique.dedup();
+    // interact_dapp and the token program are in both lists
+    assert!(unique.len() < addresses.len());
+
+    let (_, instructions) = create_market_lookup_table(&authority, &authority, 1, &addresses);
+    let extend_count = unique.len().div_ceil(MAX_ADDRESSES_PER_EXTEND);
+    assert_eq!(instructions.len(), 1 + extend_count);
+    // Each extend instruction lists the table, its authority, the payer and the system
+    // program, followed by the new addresses in its data
+    let extended: usize = instructions[1..]
+        .iter()
+        .map(|ix| (ix.data.len() - 12) / 32)
+        .sum();
+    assert_eq!(extended, unique.len());
+}
@@ -0,0 +1,136 @@
+use interact_dapp::math::{
+    apply_slippage, assets_for_shares, convert_to_assets, preview_deposit, preview_withdraw,
+    shares_for_deposit, EXCHANGE_PRICES_PRECISION, STRATEGY_VIRTUAL_AMOUNT,
+};
+use proptest::prelude::*;
+
+const PRICE_ONE: u64 = EXCHANGE_PRICES_PRECISION as u64;
+const VIRTUAL: u128 = STRATEGY_VIRTUAL_AMOUNT as u128;
+
+/// Bounded so amounts converted at up to 100x stay within u64
+fn amount() -> impl Strategy<Value = u64> {
+    0..u64::MAX / 128
+}
+
+fn exchange_price() -> impl Strategy<Value = u64> {
+    PRICE_ONE / 2..100 * PRICE_ONE
+}
+
+/// Strategy vault totals at a share price between 0.0001 and 100 assets per share, so
+/// minted shares stay within u64
+fn vault_totals() -> impl Strategy<Value = (u64, u64)> {
+    (1..u64::MAX >> 24, 1..1_000_000u64).prop_map(|(total_shares, price_bps)| {
+        let total_value = (total_shares as u128 * price_bps as u128 / 10_000) as u64;
+        (total_shares, total_value.max(1))
+    })
+}
+
+#[derive(Debug, Clone)]
+enum VaultAction {
+    Deposit(u64),
+    /// Burns this fraction of the total shares, in bps
+    Withdraw(u16),
+}
+
+fn vault_action() -> impl Strategy<Value = VaultAction> {
+    prop_oneof![
+        (1..u64::MAX >> 24).prop_map(VaultAction::Deposit),
+        (1..=10_000u16).prop_map(VaultAction::Withdraw),
+    ]
+}
+
+proptest! {
+    /// Redeeming the fTokens of a deposit never returns more than was deposited.
+    #[test]
+    fn earn_deposit_round_trip_never_profits(assets in amount(), price in exchange_price()) {
+        let shares = preview_deposit(assets, price).unwrap();
+        prop_assert!(convert_to_assets(shares, price).unwrap() <= assets);
+    }
+
+    /// A withdrawal burns at least the fTokens worth the assets it pays out.
+    #[test]
+    fn earn_withdraw_burns_enough_shares(assets in amount(), price in exchange_price()) {
+        let shares = preview_withdraw(assets, price).unwrap();
+        prop_assert!(convert_to_assets(shares, price).unwrap() >= assets);
+        // and no more than one share above the exact amount
+        prop_assert!(shares - preview_deposit(assets, price).unwrap() <= 1);
+    }
+
+    /// The strategy share price, total value over total shares counting the virtual ones,
+    /// never decreases through deposits and withdrawals: rounding always favours the
+    /// remaining holders.
+    #[test]
+    fn strategy_share_price_never_decreases(
+        (mut total_shares, mut total_value) in vault_totals(),
+        actions in prop::collection::vec(vault_action(), 1..32),
+    ) {
+        for action in actions {
+            let (shares_after, value_after) = match action {
+                VaultAction::Deposit(assets) => {
+                    let shares = shares_for_deposit(assets, total_shares, total_value).unwrap();
+                    (total_shares + shares, total_value + assets)
+                }
+                VaultAction::Withdraw(bps) => {
+                    let shares = (total_shares as u128 * bps as u128 / 10_000) as u64;
+                    let assets = assets_for_shares(shares, total_shares, total_value).unwrap();
+                    (total_shares - shares, total_value - assets)
+                }
+            };
+            if shares_after == 0 {
+                break;
+            }
+            prop_assert!(
+                (value_after as u128 + VIRTUAL) * (total_shares as u128 + VIRTUAL)
+                    >= (total_value as u128 + VIRTUAL) * (shares_after as u128 + VIRTUAL)
+            );
+            total_shares = shares_after;
+            total_value = value_after;
+        }
+    }
+
+    /// Redeeming the shares minted for a strategy deposit never returns more than it.
+    #[test]
+    fn strategy_deposit_round_trip_never_profits(
+        assets in 1..u64::MAX >> 24,
+        (total_shares, total_value) in vault_totals(),
+    ) {
+        let shares = shares_for_deposit(assets, total_shares, total_value).unwrap();
+        let redeemed =
+            assets_for_shares(shares, total_shares + shares, total_value + assets).unwrap();
+        prop_assert!(redeemed <= assets);
+    }
+
+    /// A first depositor donating to the empty vault to inflate the share price costs the
+    /// next depositor at most a `1 / STRATEGY_VIRTUAL_AMOUNT` fraction of the donation.
+    #[test]
+    fn first_depositor_cannot_inflate_share_price(
+        first in 1..1_000_000u64,
+        donation in 0..u64::MAX >> 24,
+        assets in 1..u64::MAX >> 24,
+    ) {
+        let first_shares = shares_for_deposit(first, 0, 0).unwrap();
+        let total_value = first + donation;
+        let shares = shares_for_deposit(assets, first_shares, total_value).unwrap();
+        let redeemed =
+            assets_for_shares(shares, first_shares + shares, total_value + assets).unwrap();
+        prop_assert!(assets - redeemed <= donation / STRATEGY_VIRTUAL_AMOUNT + 3);
+    }
+
+    /// Burning every share never pays out more than the strategy holds.
+    #[test]
+    fn strategy_redemption_never_exceeds_total_value(
+        (total_shares, total_value) in vault_totals(),
+    ) {
+        let assets = assets_for_shares(total_shares, total_shares, total_value).unwrap();
+        prop_assert!(assets <= total_value);
+    }
+
+    #[test]
+    fn slippage_never_raises_minimum_out(amount in any::<u64>(), slippage_bps in any::<u16>()) {
+        let minimum_out = apply_slippage(amount, slippage_bps);
+        prop_assert!(minimum_out <= amount);
+        if slippage_bps >= 10_000 {
+            prop_assert_eq!(minimum_out, 0);
+        }
+    }
+}
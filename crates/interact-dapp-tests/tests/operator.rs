@@ -0,0 +1,98 @@
+use anchor_spl::token::{self, spl_token};
+use interact_dapp::error::InteractDappError;
+use interact_dapp::states::OperatorDelegation;
+use interact_dapp_client::{
+    create_operator_delegation, get_operator_delegation_address, operator_deposit_earn,
+    update_deposit_caps, DelegationArgs, EarnMarket,
+};
+use interact_dapp_tests::{create_earn_market, custom_error_code, TestEnv};
+use solana_sdk::signature::{Keypair, Signer};
+
+const MAX_AMOUNT_PER_PERIOD: u64 = 500_000;
+const PERIOD: i64 = 86_400;
+
+/// Delegates Earn deposits of the payer to a new operator, with the delegation approved to
+/// pull the whole payer balance of the underlying.
+async fn setup() -> (TestEnv, EarnMarket, Keypair) {
+    let mut env = TestEnv::new().await;
+    let market = create_earn_market(&mut env, 6).await;
+    let owner = env.payer.pubkey();
+    let operator = Keypair::new();
+    let owner_token_account = env.mint_to(&market.mint, &owner, 1_000_000).await;
+    env.create_ata(&owner, &market.f_token_mint(), &market.token_program)
+        .await;
+    let delegation = get_operator_delegation_address(&owner, &operator.pubkey(), &market.mint);
+    let expires_at = env.unix_timestamp().await + PERIOD;
+
+    let args = DelegationArgs {
+        permissions: OperatorDelegation::DEPOSIT_EARN,
+        max_amount_per_period: MAX_AMOUNT_PER_PERIOD,
+        period: PERIOD,
+        expires_at,
+    };
+    let create = create_operator_delegation(&market, &owner, &operator.pubkey(), args);
+    let approve = spl_token::instruction::approve(
+        &token::ID,
+        &owner_token_account,
+        &delegation,
+        &owner,
+        &[],
+        1_000_000,
+    )
+    .unwrap();
+    env.process(&[create, approve], &[]).await.unwrap();
+    (env, market, operator)
+}
+
+#[tokio::test]
+async fn operator_deposits_for_owner() {
+    let (mut env, market, operator) = setup().await;
+    let owner = env.payer.pubkey();
+
+    let ix = operator_deposit_earn(&market, &owner, &operator.pubkey(), 400_000);
+    env.process(&[ix], &[&operator]).await.unwrap();
+
+    assert_eq!(env.token_balance(&market.token_account(&owner)).await, 600_000);
+    assert_eq!(env.token_balance(&market.f_token_account(&owner)).await, 400_000);
+    let delegation: OperatorDelegation = env
+        .get_anchor_account(&get_operator_delegation_address(
+            &owner,
+            &operator.pubkey(),
+            &market.mint,
+        ))
+        .await;
+    assert_eq!(delegation.period_spent, 400_000);
+}
+
+#[tokio::test]
+async fn operator_deposit_fails_above_period_cap() {
+    let (mut env, market, operator) = setup().await;
+    let owner = env.payer.pubkey();
+    let ix = operator_deposit_earn(&market, &owner, &operator.pubkey(), 400_000);
+    env.process(&[ix], &[&operator]).await.unwrap();
+
+    // Within the SPL approval, but past the delegation cap for the period
+    let ix = operator_deposit_earn(&market, &owner, &operator.pubkey(), 200_000);
+    let result = env.process(&[ix], &[&operator]).await;
+    assert_eq!(
+        custom_error_code(result),
+        Some(InteractDappError::DelegationCapExceeded.into())
+    );
+    assert_eq!(env.token_balance(&market.token_account(&owner)).await, 600_000);
+}
+
+#[tokio::test]
+async fn operator_deposit_fails_above_per_user_cap() {
+    let (mut env, market, operator) = setup().await;
+    let owner = env.payer.pubkey();
+    env.process(&[update_deposit_caps(&market.mint, &owner, u64::MAX, 300_000)], &[])
+        .await
+        .unwrap();
+    let ix = operator_deposit_earn(&market, &owner, &operator.pubkey(), 300_000);
+    env.process(&[ix], &[&operator]).await.unwrap();
+
+    // The owner fTokens already hold the per-user cap
+    let ix = operator_deposit_earn(&market, &owner, &operator.pubkey(), 1);
+    let result = env.process(&[ix], &[&operator]).await;
+    assert_eq!(custom_error_code(result), Some(InteractDappError::DepositCapExceeded.into()));
+}
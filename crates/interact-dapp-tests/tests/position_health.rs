@@ -0,0 +1,142 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hash;
+use interact_dapp::error::InteractDappError;
+use interact_dapp::math::{PositionHealth, EXCHANGE_PRICES_PRECISION, ORACLE_PRECISION};
+use interact_dapp::programs::JUPITER_VAULTS_PROGRAM_ID;
+use interact_dapp::states::{Position, Tick, VaultConfig, VaultState};
+use interact_dapp_client::position_health;
+use interact_dapp_tests::{custom_error_code, TestEnv};
+
+const VAULT_ID: u16 = 1;
+const NFT_ID: u32 = 7;
+const POSITION_TICK: i32 = 0;
+/// Collateral of the position, in vault units
+const SUPPLY_AMOUNT: u64 = 1_000_000_000;
+
+/// Writes a Jupiter Vaults account, which only needs the Anchor discriminator of `name`
+/// in front of the mirrored fields.
+async fn set_vaults_account(
+    env: &mut TestEnv,
+    name: &str,
+    account: &impl AnchorSerialize,
+) -> Pubkey {
+    let address = Pubkey::new_unique();
+    let mut data = hash(format!("account:{name}").as_bytes()).to_bytes()[..8].to_vec();
+    account.serialize(&mut data).unwrap();
+    env.set_account_data(&address, &JUPITER_VAULTS_PROGRAM_ID, data)
+        .await;
+    address
+}
+
+/// Accounts of a vault with one borrow position at [`POSITION_TICK`] whose
+/// `current_position_tick` is `tick`, priced by a mock oracle at 2 debt tokens per
+/// collateral token.
+async fn setup(env: &mut TestEnv, tick: i32) -> interact_dapp::accounts::PositionHealthParams {
+    let oracle = Pubkey::new_unique();
+    let oracle_account = mock_lending::Oracle {
+        nonce: 0,
+        price: 2 * ORACLE_PRECISION,
+    };
+    env.set_anchor_account(&oracle, &mock_lending::ID, &oracle_account)
+        .await;
+
+    let vault_config = VaultConfig {
+        vault_id: VAULT_ID,
+        supply_rate_magnifier: 0,
+        borrow_rate_magnifier: 0,
+        collateral_factor: 800,
+        liquidation_threshold: 900,
+        liquidation_max_limit: 950,
+        withdraw_gap: 0,
+        liquidation_penalty: 0,
+        borrow_fee: 0,
+        oracle,
+        rebalancer: Pubkey::default(),
+        liquidity_program: Pubkey::default(),
+        oracle_program: mock_lending::ID,
+        supply_token: Pubkey::default(),
+        borrow_token: Pubkey::default(),
+        bump: 0,
+    };
+    let exchange_price = EXCHANGE_PRICES_PRECISION as u64;
+    let vault_state = VaultState {
+        vault_id: VAULT_ID,
+        branch_liquidated: 0,
+        topmost_tick: POSITION_TICK,
+        current_branch_id: 0,
+        total_branch_id: 0,
+        total_supply: SUPPLY_AMOUNT,
+        total_borrow: SUPPLY_AMOUNT,
+        total_positions: 1,
+        absorbed_debt_amount: 0,
+        absorbed_col_amount: 0,
+        absorbed_dust_debt: 0,
+        liquidity_supply_exchange_price: exchange_price,
+        liquidity_borrow_exchange_price: exchange_price,
+        vault_supply_exchange_price: exchange_price,
+        vault_borrow_exchange_price: exchange_price,
+        next_position_id: NFT_ID + 1,
+        last_update_timestamp: 0,
+    };
+    let position = Position {
+        vault_id: VAULT_ID,
+        nft_id: NFT_ID,
+        position_mint: Pubkey::new_unique(),
+        is_supply_only_position: 0,
+        tick: POSITION_TICK,
+        tick_id: 0,
+        supply_amount: SUPPLY_AMOUNT,
+        dust_debt_amount: 0,
+    };
+    let current_position_tick = Tick {
+        vault_id: VAULT_ID,
+        tick,
+        is_liquidated: 0,
+        total_ids: 1,
+        raw_debt: SUPPLY_AMOUNT,
+        is_fully_liquidated: 0,
+        liquidation_branch_id: 0,
+        debt_factor: 0,
+    };
+    interact_dapp::accounts::PositionHealthParams {
+        vault_config: set_vaults_account(env, "VaultConfig", &vault_config).await,
+        vault_state: set_vaults_account(env, "VaultState", &vault_state).await,
+        position: set_vaults_account(env, "Position", &position).await,
+        current_position_tick: set_vaults_account(env, "Tick", &current_position_tick).await,
+        oracle,
+        oracle_program: mock_lending::ID,
+        vaults_program: JUPITER_VAULTS_PROGRAM_ID,
+    }
+}
+
+#[tokio::test]
+async fn position_health_prices_debt_at_oracle_rate() {
+    let mut env = TestEnv::new().await;
+    let accounts = setup(&mut env, POSITION_TICK).await;
+
+    let data = env
+        .process_with_return_data(&[position_health(accounts, &[])], &[])
+        .await
+        .unwrap();
+    let health = PositionHealth::try_from_slice(&data).unwrap();
+
+    // At tick 0 the debt equals the collateral amount, worth twice as much at the oracle
+    let amount = SUPPLY_AMOUNT as u128;
+    assert_eq!(health.nft_id, NFT_ID);
+    assert_eq!((health.collateral, health.debt), (amount, amount));
+    assert_eq!(health.collateral_value, 2 * amount);
+    assert_eq!(health.ltv_bps, 5_000);
+    assert_eq!(health.health_factor_bps, 18_000);
+    assert_eq!(health.liquidation_distance_bps, 4_000);
+    assert_eq!(health.available_borrow, 6 * amount / 10);
+    assert!(!health.is_liquidated);
+}
+
+#[tokio::test]
+async fn position_health_rejects_tick_of_other_position() {
+    let mut env = TestEnv::new().await;
+    let accounts = setup(&mut env, POSITION_TICK + 1).await;
+
+    let result = env.process(&[position_health(accounts, &[])], &[]).await;
+    assert_eq!(custom_error_code(result), Some(InteractDappError::VaultMismatch.into()));
+}
@@ -0,0 +1,278 @@
+use anchor_spl::associated_token::get_associated_token_address;
+use anchor_spl::token::Mint;
+use interact_dapp::math::{convert_to_assets, EXCHANGE_PRICES_PRECISION};
+use interact_dapp_client::{
+    deposit_earn, get_tick_array_start_index, proxy_swap, quote_swap, withdraw_earn, ClmmPool,
+    EarnMarket, QuoteParams, SwapArgs,
+};
+use interact_dapp_tests::{
+    add_liquidity, create_earn_market, create_pool, set_token_exchange_price, TestEnv,
+    SQRT_PRICE_ONE_X64, TRADE_FEE_RATE,
+};
+use proptest::prelude::*;
+use proptest::test_runner::TestCaseError;
+use raydium_amm_v3::states::{AmmConfig, PoolState, TickArrayState};
+use solana_sdk::signature::{Keypair, Signer};
+
+const USERS: usize = 3;
+const USER_BALANCE: u64 = 100_000_000;
+const LIQUIDITY_AMOUNT: u64 = 1_000_000_000;
+const TICK_LOWER: i32 = -6_000;
+const TICK_UPPER: i32 = 6_000;
+const FEE_RATE_DENOMINATOR: u128 = 1_000_000;
+
+#[derive(Debug, Clone)]
+enum Action {
+    Deposit { user: usize, amount: u64 },
+    /// May exceed the user position, in which case the withdrawal has to fail
+    Withdraw { user: usize, amount: u64 },
+    /// Raises the exchange price by `bps`, funding the vault with the yield
+    Accrue { bps: u64 },
+    /// At least 1_000 so the output is never rounded to zero
+    Swap { zero_for_one: bool, amount: u64 },
+}
+
+fn action() -> impl Strategy<Value = Action> {
+    prop_oneof![
+        (0..USERS, 10..5_000_000u64).prop_map(|(user, amount)| Action::Deposit { user, amount }),
+        (0..USERS, 1..6_000_000u64).prop_map(|(user, amount)| Action::Withdraw { user, amount }),
+        (0..500u64).prop_map(|bps| Action::Accrue { bps }),
+        (any::<bool>(), 1_000..1_000_000u64)
+            .prop_map(|(zero_for_one, amount)| Action::Swap { zero_for_one, amount }),
+    ]
+}
+
+/// Assets a user put in and took out of the Earn market, and the yield their fTokens
+/// earned while they held them.
+#[derive(Debug, Default, Clone, Copy)]
+struct Ledger {
+    deposited: u64,
+    withdrawn: u64,
+    earned: u64,
+}
+
+struct Harness {
+    env: TestEnv,
+    market: EarnMarket,
+    pool: ClmmPool,
+    users: Vec<Keypair>,
+    ledgers: Vec<Ledger>,
+    price: u64,
+}
+
+impl Harness {
+    async fn new() -> Self {
+        let mut env = TestEnv::new().await;
+        let market = create_earn_market(&mut env, 6).await;
+        let users: Vec<Keypair> = (0..USERS).map(|_| Keypair::new()).collect();
+        for user in &users {
+            env.mint_to(&market.mint, &user.pubkey(), USER_BALANCE).await;
+            env.create_ata(&user.pubkey(), &market.f_token_mint(), &market.token_program)
+                .await;
+        }
+
+        let payer = env.payer.pubkey();
+        let mint_a = env.create_mint(6).await;
+        let mint_b = env.create_mint(6).await;
+        env.mint_to(&mint_a, &payer, 10 * LIQUIDITY_AMOUNT).await;
+        env.mint_to(&mint_b, &payer, 10 * LIQUIDITY_AMOUNT).await;
+        let pool = create_pool(&mut env, mint_a, mint_b, SQRT_PRICE_ONE_X64).await;
+        add_liquidity(
+            &mut env,
+            &pool,
+            TICK_LOWER,
+            TICK_UPPER,
+            LIQUIDITY_AMOUNT,
+            2 * LIQUIDITY_AMOUNT,
+        )
+        .await;
+
+        Self {
+            env,
+            market,
+            pool,
+            users,
+            ledgers: vec![Ledger::default(); USERS],
+            price: EXCHANGE_PRICES_PRECISION as u64,
+        }
+    }
+
+    async fn shares(&mut self, user: usize) -> u64 {
+        let owner = self.users[user].pubkey();
+        self.env
+            .token_balance(&self.market.f_token_account(&owner))
+            .await
+    }
+
+    async fn underlying(&mut self, user: usize) -> u64 {
+        let owner = self.users[user].pubkey();
+        self.env.token_balance(&self.market.token_account(&owner)).await
+    }
+
+    async fn apply(&mut self, action: Action) -> Result<(), TestCaseError> {
+        match action {
+            Action::Deposit { user, amount } => {
+                let signer = self.users[user].insecure_clone();
+                let before = self.underlying(user).await;
+                let ix = deposit_earn(&self.market, &signer.pubkey(), amount);
+                let result = self.env.process(&[ix], &[&signer]).await;
+                prop_assert_eq!(result.is_ok(), amount <= before);
+                self.ledgers[user].deposited += before - self.underlying(user).await;
+            }
+            Action::Withdraw { user, amount } => {
+                let signer = self.users[user].insecure_clone();
+                let position = convert_to_assets(self.shares(user).await, self.price).unwrap();
+                let before = self.underlying(user).await;
+                let ix = withdraw_earn(&self.market, &signer.pubkey(), amount);
+                let result = self.env.process(&[ix], &[&signer]).await;
+                prop_assert_eq!(result.is_ok(), amount <= position);
+                self.ledgers[user].withdrawn += self.underlying(user).await - before;
+            }
+            Action::Accrue { bps } => {
+                let price = self.price + self.price * bps / 10_000;
+                for user in 0..USERS {
+                    let shares = self.shares(user).await;
+                    let earned = (shares as u128 * (price - self.price) as u128)
+                        .div_ceil(EXCHANGE_PRICES_PRECISION);
+                    self.ledgers[user].earned += earned as u64;
+                }
+                let supply = self.f_token_supply().await;
+                let owed = convert_to_assets(supply, price).unwrap() + 1;
+                let vault = self.env.token_balance(&self.market.vault()).await;
+                if owed > vault {
+                    let liquidity = self.market.liquidity();
+                    self.env
+                        .mint_to(&self.market.mint, &liquidity, owed - vault)
+                        .await;
+                }
+                set_token_exchange_price(&mut self.env, &self.market, price).await;
+                self.price = price;
+            }
+            Action::Swap {
+                zero_for_one,
+                amount,
+            } => self.swap(zero_for_one, amount).await?,
+        }
+        self.check_earn_invariants().await
+    }
+
+    async fn f_token_supply(&mut self) -> u64 {
+        let mint: Mint = self
+            .env
+            .get_anchor_account(&self.market.f_token_mint())
+            .await;
+        mint.supply
+    }
+
+    /// The vault always backs the fTokens in circulation at the exchange price, and no
+    /// user can get more than they deposited plus the yield of their fTokens.
+    async fn check_earn_invariants(&mut self) -> Result<(), TestCaseError> {
+        let supply = self.f_token_supply().await;
+        let vault = self.env.token_balance(&self.market.vault()).await;
+        prop_assert!(vault >= convert_to_assets(supply, self.price).unwrap());
+
+        for user in 0..USERS {
+            let ledger = self.ledgers[user];
+            let position = convert_to_assets(self.shares(user).await, self.price).unwrap();
+            prop_assert!(
+                ledger.withdrawn + position <= ledger.deposited + ledger.earned,
+                "user {} got {} out of {} deposited and {} earned",
+                user,
+                ledger.withdrawn + position,
+                ledger.deposited,
+                ledger.earned
+            );
+        }
+        Ok(())
+    }
+
+    /// Swaps through `proxy_swap`, checking the execution against the quote and the fee
+    /// charged to the swapper against the configured trade fee rate.
+    async fn swap(&mut self, zero_for_one: bool, amount: u64) -> Result<(), TestCaseError> {
+        let pool = self.pool;
+        let payer = self.env.payer.pubkey();
+        let pool_state: PoolState = self.env.get_zero_copy(&pool.pool_state).await;
+        let amm_config: AmmConfig = self.env.get_anchor_account(&pool.amm_config).await;
+        let mut tick_arrays: Vec<TickArrayState> = Vec::new();
+        for tick in [TICK_LOWER, TICK_UPPER] {
+            let address = pool.tick_array(get_tick_array_start_index(tick, pool.tick_spacing));
+            tick_arrays.push(self.env.get_zero_copy(&address).await);
+        }
+        let quote = quote_swap(
+            &pool,
+            &pool_state,
+            &amm_config,
+            &tick_arrays,
+            None,
+            QuoteParams {
+                amount,
+                zero_for_one,
+                is_base_input: true,
+                sqrt_price_limit_x64: 0,
+                block_timestamp: 0,
+            },
+        )
+        .unwrap();
+
+        let (input_mint, output_mint) = if zero_for_one {
+            (pool.token_mint_0, pool.token_mint_1)
+        } else {
+            (pool.token_mint_1, pool.token_mint_0)
+        };
+        let input_account = get_associated_token_address(&payer, &input_mint);
+        let output_account = get_associated_token_address(&payer, &output_mint);
+        let input_before = self.env.token_balance(&input_account).await;
+        let output_before = self.env.token_balance(&output_account).await;
+        let ix = proxy_swap(
+            &pool,
+            &payer,
+            &input_mint,
+            &quote.tick_arrays,
+            quote.needs_bitmap_extension,
+            SwapArgs {
+                amount,
+                other_amount_threshold: quote.amount_out,
+                sqrt_price_limit_x64: 0,
+                is_base_input: true,
+            },
+        );
+        self.env.process(&[ix], &[]).await.unwrap();
+
+        let amount_in = input_before - self.env.token_balance(&input_account).await;
+        let amount_out = self.env.token_balance(&output_account).await - output_before;
+        prop_assert_eq!(amount_in, quote.amount_in);
+        prop_assert_eq!(amount_out, quote.amount_out);
+
+        // Protocol and fund fees are zero on the test config, the whole trade fee goes to
+        // the fee growth of the in range liquidity
+        let pool_after: PoolState = self.env.get_zero_copy(&pool.pool_state).await;
+        let fee_growth_delta = if zero_for_one {
+            pool_after.fee_growth_global_0_x64 - pool_state.fee_growth_global_0_x64
+        } else {
+            pool_after.fee_growth_global_1_x64 - pool_state.fee_growth_global_1_x64
+        };
+        let fee_charged = (fee_growth_delta * pool_state.liquidity) >> 64;
+        let max_fee = (amount_in as u128 * TRADE_FEE_RATE as u128).div_ceil(FEE_RATE_DENOMINATOR);
+        prop_assert!(quote.fee_amount as u128 <= max_fee);
+        prop_assert!(fee_charged <= max_fee);
+        Ok(())
+    }
+}
+
+async fn run(actions: Vec<Action>) -> Result<(), TestCaseError> {
+    let mut harness = Harness::new().await;
+    for action in actions {
+        harness.apply(action).await?;
+    }
+    Ok(())
+}
+
+proptest! {
+    // Every case starts a bank, keep the case count low
+    #![proptest_config(ProptestConfig::with_cases(24))]
+
+    #[test]
+    fn random_sequences_keep_invariants(actions in prop::collection::vec(action(), 1..24)) {
+        tokio::runtime::Runtime::new().unwrap().block_on(run(actions))?;
+    }
+}
@@ -0,0 +1,186 @@
+use anchor_lang::error::ErrorCode;
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_spl::associated_token::get_associated_token_address;
+use anchor_spl::token;
+use interact_dapp::error::InteractDappError;
+use interact_dapp_client::{
+    deposit_strategy, get_strategy_address, get_strategy_share_mint_address, initialize_strategy,
+    update_deposit_caps, ClmmPool, ClmmStrategy, EarnMarket,
+};
+use interact_dapp_tests::{
+    create_earn_market, create_liquid_pool, custom_error_code, initialize_observation,
+    set_config, TestEnv,
+};
+use solana_sdk::signature::{Keypair, Signer};
+use solana_sdk::system_instruction;
+
+const LIQUIDITY_AMOUNT: u64 = 1_000_000_000;
+
+struct StrategyEnv {
+    env: TestEnv,
+    market: EarnMarket,
+    pool: ClmmPool,
+    strategy: Pubkey,
+    share_mint: Pubkey,
+}
+
+impl StrategyEnv {
+    /// A strategy on a pool of a mock Earn market underlying, with the payer as admin
+    async fn new(global_cap: u64, per_user_cap: u64) -> Self {
+        let mut env = TestEnv::new().await;
+        let admin = env.payer.pubkey();
+        set_config(&mut env, &admin).await;
+        let market = create_earn_market(&mut env, 6).await;
+        let pair_mint = env.create_mint(6).await;
+        let pool = create_liquid_pool(&mut env, market.mint, pair_mint, LIQUIDITY_AMOUNT).await;
+        env.process(
+            &[update_deposit_caps(&market.mint, &admin, global_cap, per_user_cap)],
+            &[],
+        )
+        .await
+        .unwrap();
+        let ix = initialize_strategy(&ClmmStrategy::new(market, pool), &admin, 3_600, 100);
+        env.process(&[ix], &[]).await.unwrap();
+
+        let strategy = get_strategy_address(&pool.pool_state, &market.mint);
+        let share_mint = get_strategy_share_mint_address(&strategy);
+        env.create_ata(&admin, &share_mint, &token::ID).await;
+        Self {
+            env,
+            market,
+            pool,
+            strategy,
+            share_mint,
+        }
+    }
+
+    fn deposit(&self, depositor: &Pubkey, amount: u64) -> Instruction {
+        let share_account = get_associated_token_address(depositor, &self.share_mint);
+        self.deposit_to(depositor, &share_account, amount)
+    }
+
+    fn deposit_to(&self, depositor: &Pubkey, share_account: &Pubkey, amount: u64) -> Instruction {
+        let strategy = ClmmStrategy::new(self.market, self.pool);
+        deposit_strategy(&strategy, depositor, share_account, None, amount)
+    }
+
+    async fn shares_of(&mut self, owner: &Pubkey) -> u64 {
+        let address = get_associated_token_address(owner, &self.share_mint);
+        self.env.token_balance(&address).await
+    }
+}
+
+#[tokio::test]
+async fn deposit_mints_shares_at_strategy_value() {
+    let mut strategy = StrategyEnv::new(u64::MAX, u64::MAX).await;
+    initialize_observation(&mut strategy.env, &strategy.pool, 300).await;
+    let user = strategy.env.payer.pubkey();
+
+    let ix = strategy.deposit(&user, 1_000_000);
+    strategy.env.process(&[ix], &[]).await.unwrap();
+    assert_eq!(strategy.shares_of(&user).await, 1_000_000);
+
+    // Idle base tokens are the whole strategy value, so shares stay 1:1
+    let ix = strategy.deposit(&user, 500_000);
+    strategy.env.process(&[ix], &[]).await.unwrap();
+    assert_eq!(strategy.shares_of(&user).await, 1_500_000);
+    let base_vault = get_associated_token_address(&strategy.strategy, &strategy.market.mint);
+    assert_eq!(strategy.env.token_balance(&base_vault).await, 1_500_000);
+}
+
+#[tokio::test]
+async fn deposit_rejects_pool_without_twap() {
+    let mut strategy = StrategyEnv::new(u64::MAX, u64::MAX).await;
+    let user = strategy.env.payer.pubkey();
+
+    // No swap has written an observation yet
+    let ix = strategy.deposit(&user, 1_000_000);
+    let result = strategy.env.process(&[ix], &[]).await;
+    assert_eq!(custom_error_code(result), Some(InteractDappError::StaleOracle.into()));
+}
+
+#[tokio::test]
+async fn deposit_rejects_observations_shorter_than_window() {
+    let mut strategy = StrategyEnv::new(u64::MAX, u64::MAX).await;
+    initialize_observation(&mut strategy.env, &strategy.pool, 60).await;
+    let user = strategy.env.payer.pubkey();
+
+    // The first observation is younger than the default 300 seconds window
+    let ix = strategy.deposit(&user, 1_000_000);
+    let result = strategy.env.process(&[ix], &[]).await;
+    assert_eq!(custom_error_code(result), Some(InteractDappError::StaleOracle.into()));
+}
+
+#[tokio::test]
+async fn deposit_accepts_pool_without_recent_swap() {
+    let mut strategy = StrategyEnv::new(u64::MAX, u64::MAX).await;
+    initialize_observation(&mut strategy.env, &strategy.pool, 86_400).await;
+    let user = strategy.env.payer.pubkey();
+
+    // No observation falls inside the window, the one before it anchors the TWAP
+    let ix = strategy.deposit(&user, 1_000_000);
+    strategy.env.process(&[ix], &[]).await.unwrap();
+    assert_eq!(strategy.shares_of(&user).await, 1_000_000);
+}
+
+#[tokio::test]
+async fn deposit_is_cut_to_per_user_cap() {
+    let mut strategy = StrategyEnv::new(u64::MAX, 300_000).await;
+    initialize_observation(&mut strategy.env, &strategy.pool, 300).await;
+    let user = strategy.env.payer.pubkey();
+
+    let ix = strategy.deposit(&user, 1_000_000);
+    strategy.env.process(&[ix], &[]).await.unwrap();
+    assert_eq!(strategy.shares_of(&user).await, 300_000);
+}
+
+#[tokio::test]
+async fn per_user_cap_spans_share_accounts() {
+    let mut strategy = StrategyEnv::new(u64::MAX, 300_000).await;
+    initialize_observation(&mut strategy.env, &strategy.pool, 300).await;
+    let user = strategy.env.payer.pubkey();
+    let ix = strategy.deposit(&user, 300_000);
+    strategy.env.process(&[ix], &[]).await.unwrap();
+
+    // Minting to a fresh share account does not reset the depositor position
+    let other = Pubkey::new_unique();
+    let share_mint = strategy.share_mint;
+    strategy.env.create_ata(&other, &share_mint, &token::ID).await;
+    let other_share_account = get_associated_token_address(&other, &share_mint);
+    let ix = strategy.deposit_to(&user, &other_share_account, 1);
+    let result = strategy.env.process(&[ix], &[]).await;
+    assert_eq!(custom_error_code(result), Some(InteractDappError::DepositCapExceeded.into()));
+}
+
+#[tokio::test]
+async fn deposit_fails_once_global_cap_is_reached() {
+    let mut strategy = StrategyEnv::new(500_000, u64::MAX).await;
+    initialize_observation(&mut strategy.env, &strategy.pool, 300).await;
+    let user = strategy.env.payer.pubkey();
+    let ix = strategy.deposit(&user, 500_000);
+    strategy.env.process(&[ix], &[]).await.unwrap();
+
+    let ix = strategy.deposit(&user, 1);
+    let result = strategy.env.process(&[ix], &[]).await;
+    assert_eq!(custom_error_code(result), Some(InteractDappError::DepositCapExceeded.into()));
+}
+
+#[tokio::test]
+async fn initialize_strategy_requires_admin() {
+    let mut env = TestEnv::new().await;
+    let admin = env.payer.pubkey();
+    set_config(&mut env, &admin).await;
+    let market = create_earn_market(&mut env, 6).await;
+    let pair_mint = env.create_mint(6).await;
+    let pool = create_liquid_pool(&mut env, market.mint, pair_mint, LIQUIDITY_AMOUNT).await;
+    // Funded so the rejection comes from the admin check, not from paying for the accounts
+    let intruder = Keypair::new();
+    let fund = system_instruction::transfer(&admin, &intruder.pubkey(), 1_000_000_000);
+    env.process(&[fund], &[]).await.unwrap();
+
+    let strategy = ClmmStrategy::new(market, pool);
+    let ix = initialize_strategy(&strategy, &intruder.pubkey(), 3_600, 100);
+    let result = env.process(&[ix], &[&intruder]).await;
+    assert_eq!(custom_error_code(result), Some(ErrorCode::ConstraintHasOne.into()));
+}
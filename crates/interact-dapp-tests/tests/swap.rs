@@ -0,0 +1,109 @@
+use interact_dapp_client::{
+    get_tick_array_start_index, proxy_swap, quote_swap, ClmmPool, QuoteParams, SwapArgs,
+};
+use interact_dapp_tests::{add_liquidity, create_pool, TestEnv, SQRT_PRICE_ONE_X64};
+use raydium_amm_v3::states::{AmmConfig, PoolState, TickArrayState};
+use solana_sdk::signature::Signer;
+
+const LIQUIDITY_AMOUNT: u64 = 1_000_000_000;
+const TICK_LOWER: i32 = -6_000;
+const TICK_UPPER: i32 = 6_000;
+
+async fn setup() -> (TestEnv, ClmmPool) {
+    let mut env = TestEnv::new().await;
+    let user = env.payer.pubkey();
+    let mint_a = env.create_mint(6).await;
+    let mint_b = env.create_mint(6).await;
+    env.mint_to(&mint_a, &user, 10 * LIQUIDITY_AMOUNT).await;
+    env.mint_to(&mint_b, &user, 10 * LIQUIDITY_AMOUNT).await;
+
+    let pool = create_pool(&mut env, mint_a, mint_b, SQRT_PRICE_ONE_X64).await;
+    add_liquidity(
+        &mut env,
+        &pool,
+        TICK_LOWER,
+        TICK_UPPER,
+        LIQUIDITY_AMOUNT,
+        2 * LIQUIDITY_AMOUNT,
+    )
+    .await;
+    (env, pool)
+}
+
+#[tokio::test]
+async fn proxy_swap_matches_quote() {
+    let (mut env, pool) = setup().await;
+    let user = env.payer.pubkey();
+    let pool_state: PoolState = env.get_zero_copy(&pool.pool_state).await;
+    let amm_config: AmmConfig = env.get_anchor_account(&pool.amm_config).await;
+    // The position bounds are the only initialized ticks, one per tick array
+    let mut tick_arrays: Vec<TickArrayState> = Vec::new();
+    for tick in [TICK_LOWER, TICK_UPPER] {
+        let address = pool.tick_array(get_tick_array_start_index(tick, pool.tick_spacing));
+        tick_arrays.push(env.get_zero_copy(&address).await);
+    }
+
+    let amount = 10_000_000;
+    let quote = quote_swap(
+        &pool,
+        &pool_state,
+        &amm_config,
+        &tick_arrays,
+        None,
+        QuoteParams {
+            amount,
+            zero_for_one: true,
+            is_base_input: true,
+            sqrt_price_limit_x64: 0,
+            block_timestamp: 0,
+        },
+    )
+    .unwrap();
+    assert!(quote.amount_out > 0 && quote.amount_out < amount);
+
+    let output_account = anchor_spl::associated_token::get_associated_token_address(
+        &user,
+        &pool.token_mint_1,
+    );
+    let output_before = env.token_balance(&output_account).await;
+    let ix = proxy_swap(
+        &pool,
+        &user,
+        &pool.token_mint_0,
+        &quote.tick_arrays,
+        quote.needs_bitmap_extension,
+        SwapArgs {
+            amount,
+            other_amount_threshold: quote.amount_out,
+            sqrt_price_limit_x64: 0,
+            is_base_input: true,
+        },
+    );
+    env.process(&[ix], &[]).await.unwrap();
+
+    assert_eq!(env.token_balance(&output_account).await - output_before, quote.amount_out);
+    let pool_after: PoolState = env.get_zero_copy(&pool.pool_state).await;
+    assert_eq!({ pool_after.sqrt_price_x64 }, quote.sqrt_price_after_x64);
+}
+
+#[tokio::test]
+async fn proxy_swap_fails_below_threshold() {
+    let (mut env, pool) = setup().await;
+    let user = env.payer.pubkey();
+    let tick_arrays = [pool.tick_array(get_tick_array_start_index(TICK_UPPER, pool.tick_spacing))];
+
+    let ix = proxy_swap(
+        &pool,
+        &user,
+        &pool.token_mint_1,
+        &tick_arrays,
+        false,
+        SwapArgs {
+            amount: 1_000,
+            other_amount_threshold: 1_000,
+            sqrt_price_limit_x64: 0,
+            is_base_input: true,
+        },
+    );
+    assert!(env.process(&[ix], &[]).await.is_err());
+}
@@ -0,0 +1,113 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_spl::associated_token::get_associated_token_address;
+use interact_dapp::error::InteractDappError;
+use interact_dapp::states::{DepositCaps, TriggerKind, TriggerOrder};
+use interact_dapp_client::{
+    deposit_earn, get_deposit_caps_address, get_trigger_order_address, open_trigger_order,
+    ClmmPool, EarnMarket, TriggerOrderArgs,
+};
+use interact_dapp_tests::{
+    create_earn_market, create_liquid_pool, custom_error_code, initialize_observation,
+    position_tick_arrays, TestEnv,
+};
+use solana_sdk::signature::Signer;
+
+const LIQUIDITY_AMOUNT: u64 = 1_000_000_000;
+const ORDER_ID: u64 = 1;
+const SHARES: u64 = 1_000_000;
+/// Ticks between the pool TWAP and the trigger of the orders below
+const TRIGGER_DISTANCE: i32 = 100;
+
+/// Escrows fTokens of the payer in a trigger order of `kind` on a pool of the Earn
+/// underlying, with the trigger set so a stop loss is already reached and a take profit
+/// is not.
+async fn setup(kind: TriggerKind) -> (TestEnv, EarnMarket, ClmmPool) {
+    let mut env = TestEnv::new().await;
+    let market = create_earn_market(&mut env, 6).await;
+    let output_mint = env.create_mint(6).await;
+    let pool = create_liquid_pool(&mut env, market.mint, output_mint, LIQUIDITY_AMOUNT).await;
+    let owner = env.payer.pubkey();
+    env.create_ata(&owner, &market.f_token_mint(), &market.token_program)
+        .await;
+    env.process(&[deposit_earn(&market, &owner, SHARES)], &[])
+        .await
+        .unwrap();
+    initialize_observation(&mut env, &pool, 300).await;
+
+    // Pool ticks price token 0, so they move against the price of a token 1 underlying
+    let trigger_tick = if pool.token_mint_0 == market.mint {
+        TRIGGER_DISTANCE
+    } else {
+        -TRIGGER_DISTANCE
+    };
+    let args = TriggerOrderArgs {
+        shares: SHARES,
+        kind,
+        trigger_tick,
+        twap_window: 300,
+        max_slippage_bps: 100,
+    };
+    let ix = open_trigger_order(&market, &pool, &owner, ORDER_ID, args);
+    env.process(&[ix], &[]).await.unwrap();
+    (env, market, pool)
+}
+
+fn execute_trigger(
+    market: &EarnMarket,
+    pool: &ClmmPool,
+    keeper: &Pubkey,
+    owner: &Pubkey,
+) -> Instruction {
+    let tick_arrays = position_tick_arrays(pool, pool.token_mint_0 == market.mint);
+    interact_dapp_client::execute_trigger(
+        market,
+        pool,
+        keeper,
+        owner,
+        ORDER_ID,
+        &tick_arrays,
+        false,
+    )
+}
+
+#[tokio::test]
+async fn execute_trigger_exits_reached_stop_loss() {
+    let (mut env, market, pool) = setup(TriggerKind::StopLoss).await;
+    let owner = env.payer.pubkey();
+    let trigger_order = get_trigger_order_address(&owner, ORDER_ID);
+    let output_mint = pool.other_mint(&market.mint);
+    let output_account = get_associated_token_address(&owner, &output_mint);
+    let output_before = env.token_balance(&output_account).await;
+
+    env.process(&[execute_trigger(&market, &pool, &owner, &owner)], &[])
+        .await
+        .unwrap();
+
+    let f_token_vault = get_associated_token_address(&trigger_order, &market.f_token_mint());
+    assert_eq!(env.token_balance(&f_token_vault).await, 0);
+    let caps: DepositCaps = env
+        .get_anchor_account(&get_deposit_caps_address(&market.mint))
+        .await;
+    assert_eq!(caps.ledger.f_token_shares, 0);
+    // Within the order slippage of the 1:1 TWAP price
+    let amount_out = env.token_balance(&output_account).await - output_before;
+    assert!(amount_out >= SHARES * 99 / 100 && amount_out < SHARES);
+    let order: TriggerOrder = env.get_anchor_account(&trigger_order).await;
+    assert!(order.executed);
+}
+
+#[tokio::test]
+async fn execute_trigger_fails_before_take_profit() {
+    let (mut env, market, pool) = setup(TriggerKind::TakeProfit).await;
+    let owner = env.payer.pubkey();
+
+    let result = env
+        .process(&[execute_trigger(&market, &pool, &owner, &owner)], &[])
+        .await;
+    assert_eq!(custom_error_code(result), Some(InteractDappError::TriggerNotReached.into()));
+    let order: TriggerOrder = env
+        .get_anchor_account(&get_trigger_order_address(&owner, ORDER_ID))
+        .await;
+    assert!(!order.executed);
+}
@@ -1,3 +1,25 @@
 pub const LENDING_SEED: &[u8] = b"LENDING_SEED";
 pub const F_TOKEN_MINT_SEED: &[u8] = b"F_TOKEN_MINT_SEED";
 pub const LENDING_ADMIN_SEED: &[u8] = b"LENDING_ADMIN_SEED";
+
+pub const STRATEGY_SEED: &[u8] = b"strategy";
+pub const STRATEGY_SHARE_MINT_SEED: &[u8] = b"strategy_share_mint";
+/// Upper bound of `Strategy::max_slippage_bps`
+pub const MAX_STRATEGY_SLIPPAGE_BPS: u16 = 500;
+/// TWAP window and maximum tick deviation of a new strategy, until `set_rebalance_config`
+pub const DEFAULT_STRATEGY_TWAP_WINDOW: u32 = 300;
+pub const DEFAULT_STRATEGY_MAX_TICK_DEVIATION: u32 = 100;
+
+pub const DCA_ORDER_SEED: &[u8] = b"dca_order";
+pub const LIMIT_ORDER_SEED: &[u8] = b"limit_order";
+pub const TRIGGER_ORDER_SEED: &[u8] = b"trigger_order";
+
+pub const OPERATOR_DELEGATION_SEED: &[u8] = b"operator_delegation";
+
+pub const CONFIG_SEED: &[u8] = b"config";
+pub const DEPOSIT_CAPS_SEED: &[u8] = b"deposit_caps";
+pub const USER_DEPOSITS_SEED: &[u8] = b"user_deposits";
+/// Upper bound of `Config::fee_bps`
+pub const MAX_FEE_BPS: u16 = 1_000;
+
+pub const ALLOCATOR_SEED: &[u8] = b"allocator";
@@ -10,4 +10,118 @@ pub enum InteractDappError {
 
     #[msg("CPI to lending program failed.")]
     CpiToLendingProgramFailed,
-}
\ No newline at end of file
+
+    #[msg("CPI to oracle program failed.")]
+    CpiToOracleProgramFailed,
+
+    #[msg("Invalid remaining accounts indices.")]
+    InvalidRemainingAccountsIndices,
+
+    #[msg("Account is not owned by the expected program.")]
+    InvalidAccountOwner,
+
+    #[msg("Account data could not be deserialized.")]
+    InvalidAccountData,
+
+    #[msg("Vault accounts do not belong to the same vault.")]
+    VaultMismatch,
+
+    #[msg("Oracle does not match the vault config.")]
+    InvalidOracle,
+
+    #[msg("Oracle returned an invalid price.")]
+    InvalidOraclePrice,
+
+    #[msg("Math overflow.")]
+    MathOverflow,
+
+    #[msg("Invalid strategy configuration.")]
+    InvalidStrategyConfig,
+
+    #[msg("Token account does not belong to the pool.")]
+    InvalidPoolVault,
+
+    #[msg("Strategy has no open position.")]
+    StrategyPositionMissing,
+
+    #[msg("Strategy already has an open position.")]
+    StrategyPositionExists,
+
+    #[msg("Compound interval has not elapsed.")]
+    CompoundTooEarly,
+
+    #[msg("Not enough liquid assets in the strategy.")]
+    InsufficientStrategyLiquidity,
+
+    #[msg("Amount is too small.")]
+    ZeroAmount,
+
+    #[msg("Pool tick is still inside the position range.")]
+    PositionInRange,
+
+    #[msg("Pool tick deviates too much from its TWAP.")]
+    PriceDeviationTooHigh,
+
+    #[msg("Invalid order configuration.")]
+    InvalidOrderConfig,
+
+    #[msg("Order is not due yet.")]
+    OrderNotDue,
+
+    #[msg("Order output is below the minimum.")]
+    OrderOutputTooLow,
+
+    #[msg("Pool price has not crossed the order limit.")]
+    LimitPriceNotCrossed,
+
+    #[msg("Trigger condition does not hold.")]
+    TriggerNotReached,
+
+    #[msg("Order was already executed.")]
+    OrderAlreadyExecuted,
+
+    #[msg("Operator is not permitted to call this instruction.")]
+    OperatorNotPermitted,
+
+    #[msg("Operator delegation has expired.")]
+    DelegationExpired,
+
+    #[msg("Operator delegation cap for the period is exceeded.")]
+    DelegationCapExceeded,
+
+    #[msg("Signer is not the program upgrade authority.")]
+    NotUpgradeAuthority,
+
+    #[msg("Deposit cap is reached.")]
+    DepositCapExceeded,
+
+    #[msg("CPI to liquidity program failed.")]
+    CpiToLiquidityProgramFailed,
+
+    #[msg("Lending market is not allowlisted.")]
+    MarketNotAllowlisted,
+
+    #[msg("APY gap between the markets is too small.")]
+    ApyGapTooSmall,
+
+    #[msg("Fee is above the maximum.")]
+    InvalidFeeBps,
+
+    #[msg("Lending program is not the Jupiter lending program.")]
+    InvalidLendingProgram,
+
+    #[msg("Liquidity program is not the Jupiter liquidity program.")]
+    InvalidLiquidityProgram,
+
+    #[msg("Vaults program is not the Jupiter vaults program.")]
+    InvalidVaultsProgram,
+
+    #[msg("Pool observations do not cover the TWAP window.")]
+    StaleOracle,
+
+    #[msg("Program is paused.")]
+    ProgramPaused,
+
+    #[msg("Withdrawal would be queued on a claim account the program cannot claim.")]
+    WithdrawalQueued,
+}
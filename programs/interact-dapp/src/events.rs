@@ -0,0 +1,219 @@
+use anchor_lang::prelude::*;
+use crate::states::CompoundStep;
+
+/// Emitted by `deposit_earn`; amounts are measured from token balance changes.
+#[event]
+pub struct DepositEarnEvent {
+    pub signer: Pubkey,
+    pub mint: Pubkey,
+    /// Amount passed to the instruction
+    pub amount: u64,
+    /// Underlying tokens that left the depositor account
+    pub assets_deposited: u64,
+    /// fTokens received by the recipient account
+    pub shares_minted: u64,
+}
+
+/// Emitted by `withdraw_earn`; amounts are measured from token balance changes.
+#[event]
+pub struct WithdrawEarnEvent {
+    pub signer: Pubkey,
+    pub mint: Pubkey,
+    /// Amount passed to the instruction
+    pub amount: u64,
+    /// Underlying tokens received by the recipient account
+    pub assets_withdrawn: u64,
+    /// fTokens burned from the owner account
+    pub shares_burned: u64,
+}
+
+/// Emitted by `proxy_swap`; amounts are measured from token balance changes.
+#[event]
+pub struct ProxySwapEvent {
+    pub payer: Pubkey,
+    pub pool_state: Pubkey,
+    pub input_mint: Pubkey,
+    pub output_mint: Pubkey,
+    pub amount_in: u64,
+    pub amount_out: u64,
+    pub is_base_input: bool,
+}
+
+/// Emitted when base tokens are deposited into a strategy for shares.
+#[event]
+pub struct StrategyDepositEvent {
+    pub strategy: Pubkey,
+    pub depositor: Pubkey,
+    /// Value deposited, in base token units
+    pub assets: u64,
+    pub shares: u64,
+    /// Strategy value before the deposit, in base token units
+    pub total_value: u64,
+}
+
+/// Emitted when strategy shares are redeemed.
+#[event]
+pub struct StrategyWithdrawEvent {
+    pub strategy: Pubkey,
+    pub owner: Pubkey,
+    /// Value paid out, in base token units
+    pub assets: u64,
+    pub shares: u64,
+    /// Strategy value before the withdrawal, in base token units
+    pub total_value: u64,
+    /// Base tokens paid out
+    pub base_amount: u64,
+    /// Pair tokens paid out for the part of `assets` the base balance could not cover
+    pub pair_amount: u64,
+}
+
+/// Emitted by `crank_compound` for the step it ran.
+#[event]
+pub struct CompoundStepEvent {
+    pub strategy: Pubkey,
+    pub cranker: Pubkey,
+    pub step: CompoundStep,
+    /// Base tokens harvested, received from the swap or deposited into Earn
+    pub base_amount: u64,
+    /// Pair tokens harvested or swapped
+    pub pair_amount: u64,
+}
+
+/// Emitted by `rebalance_range` once the strategy position has been moved.
+#[event]
+pub struct RebalanceRangeEvent {
+    pub strategy: Pubkey,
+    pub keeper: Pubkey,
+    pub old_tick_lower: i32,
+    pub old_tick_upper: i32,
+    pub tick_lower: i32,
+    pub tick_upper: i32,
+    /// Pool tick the new range is centered on
+    pub tick_current: i32,
+    /// Pool TWAP tick the swap was quoted against
+    pub twap_tick: i32,
+    /// Liquidity of the new position
+    pub liquidity: u128,
+}
+
+/// Emitted by `execute_dca` for each executed leg.
+#[event]
+pub struct DcaExecutedEvent {
+    pub dca_order: Pubkey,
+    pub cranker: Pubkey,
+    pub amount_in: u64,
+    pub amount_out: u64,
+    /// Whether the output was deposited into Jupiter Earn
+    pub deposited_to_earn: bool,
+    pub tip_lamports: u64,
+}
+
+/// Emitted by `fill_limit_order`; amounts are measured from token balance changes.
+#[event]
+pub struct LimitOrderFilledEvent {
+    pub limit_order: Pubkey,
+    pub keeper: Pubkey,
+    pub amount_in: u64,
+    /// Output tokens sent to the order owner
+    pub amount_out: u64,
+    /// Input tokens left in escrow after a partial fill
+    pub remaining: u64,
+}
+
+/// Emitted by `execute_trigger`; amounts are measured from token balance changes.
+#[event]
+pub struct TriggerExecutedEvent {
+    pub trigger_order: Pubkey,
+    pub keeper: Pubkey,
+    /// Pool TWAP tick that met the trigger
+    pub twap_tick: i32,
+    pub shares_redeemed: u64,
+    /// Underlying tokens swapped
+    pub amount_in: u64,
+    /// Output tokens sent to the order owner
+    pub amount_out: u64,
+}
+
+/// Emitted by the operator variants of the Earn instructions.
+#[event]
+pub struct OperatorEarnEvent {
+    pub delegation: Pubkey,
+    pub owner: Pubkey,
+    pub operator: Pubkey,
+    /// `OperatorDelegation` permission bit that was used
+    pub permission: u8,
+    /// Underlying amount deposited or withdrawn
+    pub assets: u64,
+    /// fTokens minted or burned
+    pub shares: u64,
+    /// Amount counted against the delegation cap in the current period
+    pub period_spent: u64,
+}
+
+/// Emitted when a deposit into a program vault is cut down to its `DepositCaps`.
+#[event]
+pub struct DepositCapReachedEvent {
+    pub deposit_caps: Pubkey,
+    pub vault: Pubkey,
+    pub depositor: Pubkey,
+    pub requested: u64,
+    pub accepted: u64,
+    /// Value held in the program vaults of the mint before the deposit
+    pub tvl: u64,
+    /// Value of the depositor position before the deposit
+    pub user_value: u64,
+}
+
+/// Emitted by `withdraw_earn_max_available` when the liquidity layer withdrawal limit cuts
+/// the withdrawal short.
+#[event]
+pub struct WithdrawalLimitedEvent {
+    pub signer: Pubkey,
+    pub mint: Pubkey,
+    pub requested: u64,
+    pub withdrawn: u64,
+    /// Part of `requested` to withdraw once the limit has expanded
+    pub remainder: u64,
+}
+
+/// Emitted when part of a withdrawal is credited to the liquidity claim account instead of
+/// being transferred.
+#[event]
+pub struct WithdrawalQueuedEvent {
+    pub signer: Pubkey,
+    pub mint: Pubkey,
+    pub claim_account: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct ClaimWithdrawalEvent {
+    pub signer: Pubkey,
+    pub mint: Pubkey,
+    pub recipient_token_account: Pubkey,
+    /// Underlying tokens delivered by the claim
+    pub amount: u64,
+}
+
+/// Emitted by `reallocate`; APYs are read before the move, in `RATE_PRECISION`.
+#[event]
+pub struct ReallocateEvent {
+    pub allocator: Pubkey,
+    pub keeper: Pubkey,
+    pub from_lending: Pubkey,
+    pub to_lending: Pubkey,
+    /// Underlying withdrawn from the source market and swapped
+    pub amount_in: u64,
+    /// Underlying deposited into the destination market
+    pub amount_out: u64,
+    pub apy_from: u64,
+    pub apy_to: u64,
+}
+
+#[event]
+pub struct ConfigUpdatedEvent {
+    pub config: Pubkey,
+    pub admin: Pubkey,
+    pub paused: bool,
+    pub fee_bps: u16,
+}
@@ -0,0 +1,42 @@
+use anchor_lang::prelude::*;
+use crate::constant::CONFIG_SEED;
+use crate::error::InteractDappError;
+use crate::program::InteractDapp;
+use crate::states::Config;
+
+#[derive(Accounts)]
+pub struct InitializeConfig<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + Config::INIT_SPACE,
+        seeds = [CONFIG_SEED],
+        bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+
+    #[account(constraint = program.programdata_address()? == Some(program_data.key()))]
+    pub program: Program<'info, InteractDapp>,
+    #[account(
+        constraint = program_data.upgrade_authority_address == Some(authority.key())
+            @ InteractDappError::NotUpgradeAuthority
+    )]
+    pub program_data: Account<'info, ProgramData>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> InitializeConfig<'info> {
+    pub fn initialize_config(&mut self, bumps: &InitializeConfigBumps, admin: Pubkey) -> Result<()> {
+        self.config.set_inner(Config {
+            admin,
+            paused: false,
+            fee_bps: 0,
+            bump: bumps.config,
+        });
+        Ok(())
+    }
+}
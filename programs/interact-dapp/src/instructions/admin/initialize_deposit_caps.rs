@@ -0,0 +1,56 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+use crate::constant::{CONFIG_SEED, DEPOSIT_CAPS_SEED};
+use crate::error::InteractDappError;
+use crate::programs::JUPITER_LENDING_PROGRAM_ID;
+use crate::states::{Config, DepositCaps, DepositLedger, Lending};
+
+#[derive(Accounts)]
+pub struct InitializeDepositCaps<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(seeds = [CONFIG_SEED], bump = config.bump, has_one = admin)]
+    pub config: Box<Account<'info, Config>>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + DepositCaps::INIT_SPACE,
+        seeds = [DEPOSIT_CAPS_SEED, mint.key().as_ref()],
+        bump,
+    )]
+    pub deposit_caps: Box<Account<'info, DepositCaps>>,
+
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+
+    ///CHECK: deserialized and checked in the handler
+    pub lending: AccountInfo<'info>,
+    ///CHECK:
+    #[account(address = JUPITER_LENDING_PROGRAM_ID @ InteractDappError::InvalidLendingProgram)]
+    pub lending_program: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> InitializeDepositCaps<'info> {
+    pub fn initialize_deposit_caps(
+        &mut self,
+        bumps: &InitializeDepositCapsBumps,
+        global_cap: u64,
+        per_user_cap: u64,
+    ) -> Result<()> {
+        let lending = Lending::load(&self.lending, self.lending_program.key)?;
+        require_keys_eq!(lending.mint, self.mint.key(), InteractDappError::InvalidAccountData);
+
+        self.deposit_caps.set_inner(DepositCaps {
+            mint: self.mint.key(),
+            lending: self.lending.key(),
+            global_cap,
+            per_user_cap,
+            ledger: DepositLedger::default(),
+            bump: bumps.deposit_caps,
+        });
+        Ok(())
+    }
+}
@@ -0,0 +1,9 @@
+pub mod initialize_config;
+pub mod initialize_deposit_caps;
+pub mod update_config;
+pub mod update_deposit_caps;
+
+pub use initialize_config::*;
+pub use initialize_deposit_caps::*;
+pub use update_config::*;
+pub use update_deposit_caps::*;
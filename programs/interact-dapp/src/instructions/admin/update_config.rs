@@ -0,0 +1,35 @@
+use anchor_lang::prelude::*;
+use crate::constant::{CONFIG_SEED, MAX_FEE_BPS};
+use crate::error::InteractDappError;
+use crate::events::ConfigUpdatedEvent;
+use crate::states::Config;
+
+#[derive(Accounts)]
+pub struct UpdateConfig<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(mut, seeds = [CONFIG_SEED], bump = config.bump, has_one = admin)]
+    pub config: Box<Account<'info, Config>>,
+}
+
+impl<'info> UpdateConfig<'info> {
+    /// Updates the settings that are passed, leaving the others untouched.
+    pub fn update_config(&mut self, paused: Option<bool>, fee_bps: Option<u16>) -> Result<()> {
+        let config = &mut self.config;
+        if let Some(paused) = paused {
+            config.paused = paused;
+        }
+        if let Some(fee_bps) = fee_bps {
+            require!(fee_bps <= MAX_FEE_BPS, InteractDappError::InvalidFeeBps);
+            config.fee_bps = fee_bps;
+        }
+
+        emit!(ConfigUpdatedEvent {
+            config: config.key(),
+            admin: self.admin.key(),
+            paused: config.paused,
+            fee_bps: config.fee_bps,
+        });
+        Ok(())
+    }
+}
@@ -0,0 +1,23 @@
+use anchor_lang::prelude::*;
+use crate::constant::CONFIG_SEED;
+use crate::states::{Config, DepositCaps};
+
+#[derive(Accounts)]
+pub struct UpdateDepositCaps<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(seeds = [CONFIG_SEED], bump = config.bump, has_one = admin)]
+    pub config: Box<Account<'info, Config>>,
+
+    #[account(mut)]
+    pub deposit_caps: Box<Account<'info, DepositCaps>>,
+}
+
+impl<'info> UpdateDepositCaps<'info> {
+    pub fn update_deposit_caps(&mut self, global_cap: u64, per_user_cap: u64) -> Result<()> {
+        let deposit_caps = &mut self.deposit_caps;
+        deposit_caps.global_cap = global_cap;
+        deposit_caps.per_user_cap = per_user_cap;
+        Ok(())
+    }
+}
@@ -0,0 +1,69 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::error::InteractDappError;
+use crate::programs::JUPITER_LENDING_PROGRAM_ID;
+use crate::states::{Allocator, Lending, MAX_ALLOCATOR_MARKETS};
+
+/// Allowlists a lending market and creates the allocator ATAs used with it.
+#[derive(Accounts)]
+pub struct AddAllocatorMarket<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(mut, has_one = authority)]
+    pub allocator: Box<Account<'info, Allocator>>,
+
+    #[account(mint::token_program = token_program)]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(mint::token_program = token_program)]
+    pub f_token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        init,
+        payer = authority,
+        associated_token::mint = mint,
+        associated_token::authority = allocator,
+        associated_token::token_program = token_program,
+    )]
+    pub token_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        init,
+        payer = authority,
+        associated_token::mint = f_token_mint,
+        associated_token::authority = allocator,
+        associated_token::token_program = token_program,
+    )]
+    pub f_token_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    ///CHECK: deserialized and checked in the handler
+    pub lending: AccountInfo<'info>,
+    ///CHECK:
+    #[account(address = JUPITER_LENDING_PROGRAM_ID @ InteractDappError::InvalidLendingProgram)]
+    pub lending_program: UncheckedAccount<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> AddAllocatorMarket<'info> {
+    pub fn add_allocator_market(&mut self) -> Result<()> {
+        let lending = Lending::load(&self.lending, self.lending_program.key)?;
+        require_keys_eq!(lending.mint, self.mint.key(), InteractDappError::InvalidAccountData);
+        require_keys_eq!(
+            lending.f_token_mint,
+            self.f_token_mint.key(),
+            InteractDappError::InvalidAccountData
+        );
+
+        let allocator = &mut self.allocator;
+        require!(
+            !allocator.is_allowlisted(self.lending.key)
+                && allocator.markets.len() < MAX_ALLOCATOR_MARKETS,
+            InteractDappError::InvalidOrderConfig
+        );
+        allocator.markets.push(self.lending.key());
+        Ok(())
+    }
+}
@@ -0,0 +1,91 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked};
+use crate::constant::{CONFIG_SEED, DEPOSIT_CAPS_SEED, USER_DEPOSITS_SEED};
+use crate::error::InteractDappError;
+use crate::instructions::{EarnAccounts, EarnUser};
+use crate::states::{Allocator, Config, DepositCaps, UserDeposits};
+use super::check_allocator_market;
+
+/// Withdraws treasury funds from an allowlisted market back to the authority.
+#[derive(Accounts)]
+pub struct AllocatorWithdraw<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Box<Account<'info, Config>>,
+
+    #[account(has_one = authority)]
+    pub allocator: Box<Account<'info, Allocator>>,
+
+    #[account(mint::token_program = token_program)]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(mut, token::mint = mint)]
+    pub authority_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut, token::mint = mint, token::authority = allocator)]
+    pub token_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(mut, token::mint = earn.f_token_mint, token::authority = allocator)]
+    pub f_token_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub earn: EarnAccounts<'info>,
+    ///CHECK: Earn claim account of the allocator
+    #[account(mut)]
+    pub claim_account: UncheckedAccount<'info>,
+
+    #[account(mut, seeds = [DEPOSIT_CAPS_SEED, mint.key().as_ref()], bump = deposit_caps.bump)]
+    pub deposit_caps: Box<Account<'info, DepositCaps>>,
+    #[account(
+        mut,
+        seeds = [USER_DEPOSITS_SEED, mint.key().as_ref(), authority.key().as_ref()],
+        bump = user_deposits.bump,
+    )]
+    pub user_deposits: Box<Account<'info, UserDeposits>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> AllocatorWithdraw<'info> {
+    pub fn allocator_withdraw(&mut self, assets: u64) -> Result<()> {
+        require!(!self.config.paused, InteractDappError::ProgramPaused);
+        require!(assets > 0, InteractDappError::ZeroAmount);
+        let lending = check_allocator_market(&self.allocator, &self.earn)?;
+        require_keys_eq!(lending.mint, self.mint.key(), InteractDappError::InvalidAccountData);
+
+        let seeds = self.allocator.signer_seeds();
+        let user = EarnUser {
+            signer: self.allocator.to_account_info(),
+            token_account: self.token_vault.to_account_info(),
+            f_token_account: self.f_token_vault.to_account_info(),
+            mint: self.mint.to_account_info(),
+            token_program: self.token_program.to_account_info(),
+            associated_token_program: self.associated_token_program.to_account_info(),
+            system_program: self.system_program.to_account_info(),
+        };
+        let shares_before = self.f_token_vault.amount;
+        self.earn
+            .withdraw(&user, &self.claim_account, assets, &[&seeds])?;
+        self.f_token_vault.reload()?;
+        let shares = shares_before.saturating_sub(self.f_token_vault.amount);
+        self.deposit_caps
+            .remove_shares(&mut self.user_deposits, shares);
+        self.token_vault.reload()?;
+
+        token_interface::transfer_checked(
+            CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                TransferChecked {
+                    from: self.token_vault.to_account_info(),
+                    mint: self.mint.to_account_info(),
+                    to: self.authority_token_account.to_account_info(),
+                    authority: self.allocator.to_account_info(),
+                },
+                &[&seeds],
+            ),
+            self.token_vault.amount,
+            self.mint.decimals,
+        )
+    }
+}
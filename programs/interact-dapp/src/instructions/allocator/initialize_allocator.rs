@@ -0,0 +1,46 @@
+use anchor_lang::prelude::*;
+use crate::constant::ALLOCATOR_SEED;
+use crate::error::InteractDappError;
+use crate::math::BPS_PRECISION;
+use crate::states::Allocator;
+
+#[derive(Accounts)]
+pub struct InitializeAllocator<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + Allocator::INIT_SPACE,
+        seeds = [ALLOCATOR_SEED, authority.key().as_ref()],
+        bump,
+    )]
+    pub allocator: Box<Account<'info, Allocator>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> InitializeAllocator<'info> {
+    pub fn initialize_allocator(
+        &mut self,
+        bumps: &InitializeAllocatorBumps,
+        keeper: Pubkey,
+        min_apy_gap: u64,
+        max_slippage_bps: u16,
+    ) -> Result<()> {
+        require!(
+            (max_slippage_bps as u128) < BPS_PRECISION,
+            InteractDappError::InvalidOrderConfig
+        );
+        self.allocator.set_inner(Allocator {
+            authority: self.authority.key(),
+            keeper,
+            markets: Vec::new(),
+            min_apy_gap,
+            max_slippage_bps,
+            bump: bumps.allocator,
+        });
+        Ok(())
+    }
+}
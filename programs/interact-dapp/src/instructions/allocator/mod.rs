@@ -0,0 +1,64 @@
+pub mod add_allocator_market;
+pub mod allocator_deposit;
+pub mod allocator_withdraw;
+pub mod initialize_allocator;
+pub mod reallocate;
+
+pub use add_allocator_market::*;
+pub use allocator_deposit::*;
+pub use allocator_withdraw::*;
+pub use initialize_allocator::*;
+pub use reallocate::*;
+
+use anchor_lang::prelude::*;
+use crate::error::InteractDappError;
+use crate::instructions::EarnAccounts;
+use crate::math::get_earn_apy;
+use crate::states::{Allocator, Lending, LendingRewardsRateModel, TokenReserve};
+use crate::utils::get_mint_supply;
+
+/// Checks that the Earn accounts target an allowlisted market and returns its `Lending`.
+pub(crate) fn check_allocator_market(
+    allocator: &Allocator,
+    earn: &EarnAccounts,
+) -> Result<Lending> {
+    require!(
+        allocator.is_allowlisted(earn.lending.key),
+        InteractDappError::MarketNotAllowlisted
+    );
+    require_keys_eq!(
+        earn.lending_program.key(),
+        *earn.lending.owner,
+        InteractDappError::InvalidAccountOwner
+    );
+    let lending = Lending::load(&earn.lending, earn.lending_program.key)?;
+    require_keys_eq!(
+        lending.f_token_mint,
+        earn.f_token_mint.key(),
+        InteractDappError::InvalidAccountData
+    );
+    Ok(lending)
+}
+
+/// Total APY of a lending market, read from its own accounts so it cannot be spoofed.
+pub(crate) fn get_market_apy(earn: &EarnAccounts, now: i64) -> Result<u64> {
+    let lending = Lending::load(&earn.lending, earn.lending_program.key)?;
+    require_keys_eq!(
+        lending.token_reserves_liquidity,
+        earn.supply_token_reserves_liquidity.key(),
+        InteractDappError::InvalidAccountData
+    );
+    require_keys_eq!(
+        lending.rewards_rate_model,
+        earn.rewards_rate_model.key(),
+        InteractDappError::InvalidAccountData
+    );
+    let reserve =
+        TokenReserve::load(&earn.supply_token_reserves_liquidity, earn.liquidity_program.key)?;
+    let model =
+        LendingRewardsRateModel::load(&earn.rewards_rate_model, earn.lending_program.key)?;
+    let f_token_supply = get_mint_supply(&earn.f_token_mint)?;
+    let apy = get_earn_apy(&lending, &reserve, &model, f_token_supply, now)
+        .ok_or(InteractDappError::MathOverflow)?;
+    Ok(apy.total_apy)
+}
@@ -0,0 +1,232 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_interface::TokenAccount;
+use crate::constant::{CONFIG_SEED, DEPOSIT_CAPS_SEED, USER_DEPOSITS_SEED};
+use crate::error::InteractDappError;
+use crate::events::ReallocateEvent;
+use crate::instructions::{ClmmSwapAccounts, EarnAccounts, EarnUser};
+use crate::math::apply_slippage;
+use crate::states::{Allocator, Config, DepositCaps, UserDeposits};
+use super::{check_allocator_market, get_market_apy};
+
+/// Keeper move of allocator funds from one allowlisted market to a higher yielding one:
+/// withdraw from `from_earn`, swap on a Raydium CLMM pool and deposit into `to_earn`.
+#[derive(Accounts)]
+pub struct Reallocate<'info> {
+    /// Pays for the deposit record of the allocator authority in the `to_earn` mint
+    #[account(mut)]
+    pub keeper: Signer<'info>,
+
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Box<Account<'info, Config>>,
+
+    #[account(has_one = keeper)]
+    pub allocator: Box<Account<'info, Allocator>>,
+
+    pub from_earn: EarnAccounts<'info>,
+    #[account(mut, token::authority = allocator)]
+    pub from_token_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(mut, token::mint = from_earn.f_token_mint, token::authority = allocator)]
+    pub from_f_token_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+    ///CHECK: Earn claim account of the allocator
+    #[account(mut)]
+    pub from_claim_account: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [DEPOSIT_CAPS_SEED, from_token_vault.mint.as_ref()],
+        bump = from_deposit_caps.bump,
+    )]
+    pub from_deposit_caps: Box<Account<'info, DepositCaps>>,
+    #[account(
+        mut,
+        seeds = [
+            USER_DEPOSITS_SEED,
+            from_token_vault.mint.as_ref(),
+            allocator.authority.as_ref(),
+        ],
+        bump = from_user_deposits.bump,
+    )]
+    pub from_user_deposits: Box<Account<'info, UserDeposits>>,
+
+    pub to_earn: EarnAccounts<'info>,
+    #[account(mut, token::authority = allocator)]
+    pub to_token_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(mut, token::mint = to_earn.f_token_mint, token::authority = allocator)]
+    pub to_f_token_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        mut,
+        seeds = [DEPOSIT_CAPS_SEED, to_token_vault.mint.as_ref()],
+        bump = to_deposit_caps.bump,
+    )]
+    pub to_deposit_caps: Box<Account<'info, DepositCaps>>,
+    #[account(
+        init_if_needed,
+        payer = keeper,
+        space = 8 + UserDeposits::INIT_SPACE,
+        seeds = [USER_DEPOSITS_SEED, to_token_vault.mint.as_ref(), allocator.authority.as_ref()],
+        bump,
+    )]
+    pub to_user_deposits: Box<Account<'info, UserDeposits>>,
+
+    pub swap: ClmmSwapAccounts<'info>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+    // remaining accounts
+    // tickarray_bitmap_extension and tick arrays for `swap_v2`
+}
+
+/// `amount` of a token with `from_decimals` at a 1:1 price in a token with `to_decimals`.
+fn get_par_amount(amount: u64, from_decimals: u8, to_decimals: u8) -> Option<u64> {
+    let amount = amount as u128;
+    let par = if to_decimals >= from_decimals {
+        amount.checked_mul(10u128.checked_pow((to_decimals - from_decimals) as u32)?)?
+    } else {
+        amount / 10u128.checked_pow((from_decimals - to_decimals) as u32)?
+    };
+    u64::try_from(par).ok()
+}
+
+pub fn reallocate<'a, 'b, 'c: 'info, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, Reallocate<'info>>,
+    amount: u64,
+) -> Result<()> {
+    require!(!ctx.accounts.config.paused, InteractDappError::ProgramPaused);
+    require!(amount > 0, InteractDappError::ZeroAmount);
+    let accounts = &mut ctx.accounts;
+    let allocator = &accounts.allocator;
+    require_keys_neq!(
+        accounts.from_earn.lending.key(),
+        accounts.to_earn.lending.key(),
+        InteractDappError::InvalidOrderConfig
+    );
+    let from_lending = check_allocator_market(allocator, &accounts.from_earn)?;
+    let to_lending = check_allocator_market(allocator, &accounts.to_earn)?;
+    require_keys_eq!(
+        accounts.swap.input_vault_mint.key(),
+        from_lending.mint,
+        InteractDappError::InvalidOrderConfig
+    );
+    require_keys_eq!(
+        accounts.swap.output_vault_mint.key(),
+        to_lending.mint,
+        InteractDappError::InvalidOrderConfig
+    );
+    require_keys_eq!(
+        accounts.from_token_vault.mint,
+        from_lending.mint,
+        InteractDappError::InvalidOrderConfig
+    );
+    require_keys_eq!(
+        accounts.to_token_vault.mint,
+        to_lending.mint,
+        InteractDappError::InvalidOrderConfig
+    );
+
+    // The move must be worth it before, and must not overshoot after
+    let now = Clock::get()?.unix_timestamp;
+    let apy_from = get_market_apy(&accounts.from_earn, now)?;
+    let apy_to = get_market_apy(&accounts.to_earn, now)?;
+    require!(
+        apy_to >= apy_from.saturating_add(allocator.min_apy_gap),
+        InteractDappError::ApyGapTooSmall
+    );
+
+    let seeds = allocator.signer_seeds();
+    let signer_seeds: &[&[&[u8]]] = &[&seeds];
+
+    let from_before = accounts.from_token_vault.amount;
+    let from_shares_before = accounts.from_f_token_vault.amount;
+    let from_user = EarnUser {
+        signer: allocator.to_account_info(),
+        token_account: accounts.from_token_vault.to_account_info(),
+        f_token_account: accounts.from_f_token_vault.to_account_info(),
+        mint: accounts.swap.input_vault_mint.to_account_info(),
+        token_program: accounts
+            .swap
+            .token_program_of(&accounts.from_token_vault.to_account_info()),
+        associated_token_program: accounts.associated_token_program.to_account_info(),
+        system_program: accounts.system_program.to_account_info(),
+    };
+    accounts
+        .from_earn
+        .withdraw(&from_user, &accounts.from_claim_account, amount, signer_seeds)?;
+    accounts.from_f_token_vault.reload()?;
+    let from_shares = from_shares_before.saturating_sub(accounts.from_f_token_vault.amount);
+    accounts
+        .from_deposit_caps
+        .remove_shares(&mut accounts.from_user_deposits, from_shares);
+    accounts.from_token_vault.reload()?;
+    let amount_in = accounts.from_token_vault.amount.saturating_sub(from_before);
+    require!(amount_in > 0, InteractDappError::ZeroAmount);
+
+    // Allowlisted markets are stablecoins, the swap is bounded against a 1:1 price
+    // rather than the pool price the keeper could move
+    let par_out = get_par_amount(
+        amount_in,
+        accounts.swap.input_vault_mint.decimals,
+        accounts.swap.output_vault_mint.decimals,
+    )
+    .ok_or(InteractDappError::MathOverflow)?;
+    let minimum_amount_out = apply_slippage(par_out, allocator.max_slippage_bps);
+
+    let to_before = accounts.to_token_vault.amount;
+    accounts.swap.swap(
+        allocator.to_account_info(),
+        accounts.from_token_vault.to_account_info(),
+        accounts.to_token_vault.to_account_info(),
+        ctx.remaining_accounts,
+        amount_in,
+        minimum_amount_out,
+        0,
+        true,
+        signer_seeds,
+    )?;
+    accounts.to_token_vault.reload()?;
+    let amount_out = accounts.to_token_vault.amount.saturating_sub(to_before);
+    accounts.to_user_deposits.initialize(
+        to_lending.mint,
+        allocator.authority,
+        ctx.bumps.to_user_deposits,
+    );
+    accounts.to_deposit_caps.check_deposit(
+        &accounts.to_user_deposits,
+        amount_out,
+        to_lending.token_exchange_price,
+    )?;
+
+    let to_user = EarnUser {
+        signer: allocator.to_account_info(),
+        token_account: accounts.to_token_vault.to_account_info(),
+        f_token_account: accounts.to_f_token_vault.to_account_info(),
+        mint: accounts.swap.output_vault_mint.to_account_info(),
+        token_program: accounts
+            .swap
+            .token_program_of(&accounts.to_token_vault.to_account_info()),
+        associated_token_program: accounts.associated_token_program.to_account_info(),
+        system_program: accounts.system_program.to_account_info(),
+    };
+    let to_shares_before = accounts.to_f_token_vault.amount;
+    accounts.to_earn.deposit(&to_user, amount_out, signer_seeds)?;
+    accounts.to_f_token_vault.reload()?;
+    let to_shares = accounts.to_f_token_vault.amount.saturating_sub(to_shares_before);
+    accounts
+        .to_deposit_caps
+        .add_shares(&mut accounts.to_user_deposits, to_shares)?;
+
+    let apy_from_after = get_market_apy(&accounts.from_earn, now)?;
+    let apy_to_after = get_market_apy(&accounts.to_earn, now)?;
+    require!(apy_to_after >= apy_from_after, InteractDappError::ApyGapTooSmall);
+
+    emit!(ReallocateEvent {
+        allocator: allocator.key(),
+        keeper: accounts.keeper.key(),
+        from_lending: accounts.from_earn.lending.key(),
+        to_lending: accounts.to_earn.lending.key(),
+        amount_in,
+        amount_out,
+        apy_from,
+        apy_to,
+    });
+    Ok(())
+}
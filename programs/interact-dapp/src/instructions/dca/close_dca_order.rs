@@ -0,0 +1,112 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::constant::{DEPOSIT_CAPS_SEED, USER_DEPOSITS_SEED};
+use crate::error::InteractDappError;
+use crate::states::{DcaOrder, DepositCaps, UserDeposits};
+use crate::utils::drain_token_account;
+
+/// Cancels or settles a DCA order: returns the unspent input, the output and its fTokens
+/// to the owner, then closes the order with its remaining tips.
+#[derive(Accounts)]
+pub struct CloseDcaOrder<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(mut, close = owner, has_one = owner, has_one = input_mint, has_one = output_mint)]
+    pub dca_order: Box<Account<'info, DcaOrder>>,
+
+    #[account(mint::token_program = input_token_program)]
+    pub input_mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(mint::token_program = output_token_program)]
+    pub output_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(mut, token::mint = input_mint, token::authority = dca_order)]
+    pub input_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(mut, token::mint = output_mint, token::authority = dca_order)]
+    pub output_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut, token::mint = input_mint)]
+    pub owner_input_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(mut, token::mint = output_mint)]
+    pub owner_output_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    // Required when the order deposits into Earn
+    pub f_token_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+    #[account(mut, token::mint = f_token_mint, token::authority = dca_order)]
+    pub f_token_vault: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    #[account(mut, token::mint = f_token_mint)]
+    pub owner_f_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    #[account(
+        mut,
+        seeds = [DEPOSIT_CAPS_SEED, output_mint.key().as_ref()],
+        bump = deposit_caps.bump,
+    )]
+    pub deposit_caps: Option<Box<Account<'info, DepositCaps>>>,
+    #[account(
+        mut,
+        seeds = [USER_DEPOSITS_SEED, output_mint.key().as_ref(), owner.key().as_ref()],
+        bump = user_deposits.bump,
+    )]
+    pub user_deposits: Option<Box<Account<'info, UserDeposits>>>,
+
+    pub input_token_program: Interface<'info, TokenInterface>,
+    pub output_token_program: Interface<'info, TokenInterface>,
+}
+
+impl<'info> CloseDcaOrder<'info> {
+    pub fn close_dca_order(&mut self) -> Result<()> {
+        let order = &self.dca_order;
+        let order_id = order.order_id.to_le_bytes();
+        let seeds = order.signer_seeds(&order_id);
+        let signer_seeds: &[&[&[u8]]] = &[&seeds];
+
+        drain_token_account(
+            self.input_token_program.to_account_info(),
+            &self.input_vault,
+            &self.input_mint,
+            self.owner_input_account.to_account_info(),
+            order.to_account_info(),
+            self.owner.to_account_info(),
+            signer_seeds,
+        )?;
+        drain_token_account(
+            self.output_token_program.to_account_info(),
+            &self.output_vault,
+            &self.output_mint,
+            self.owner_output_account.to_account_info(),
+            order.to_account_info(),
+            self.owner.to_account_info(),
+            signer_seeds,
+        )?;
+
+        if order.deposits_to_earn() {
+            let (
+                Some(f_token_mint),
+                Some(f_token_vault),
+                Some(owner_f_token_account),
+                Some(deposit_caps),
+                Some(user_deposits),
+            ) = (
+                &self.f_token_mint,
+                &self.f_token_vault,
+                &self.owner_f_token_account,
+                &mut self.deposit_caps,
+                &mut self.user_deposits,
+            )
+            else {
+                return Err(InteractDappError::InvalidOrderConfig.into());
+            };
+            deposit_caps.remove_shares(user_deposits, f_token_vault.amount);
+            drain_token_account(
+                self.output_token_program.to_account_info(),
+                f_token_vault,
+                f_token_mint,
+                owner_f_token_account.to_account_info(),
+                order.to_account_info(),
+                self.owner.to_account_info(),
+                signer_seeds,
+            )?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,184 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_interface::TokenAccount;
+use crate::constant::{CONFIG_SEED, DEPOSIT_CAPS_SEED, USER_DEPOSITS_SEED};
+use crate::error::InteractDappError;
+use crate::events::{DcaExecutedEvent, DepositCapReachedEvent};
+use crate::instructions::{ClmmSwapAccounts, EarnAccounts, EarnUser};
+use crate::states::{Config, DcaOrder, DepositCaps, Lending, UserDeposits};
+use crate::utils::get_token_balance;
+
+/// Permissionless crank running the next due leg of a DCA order.
+#[derive(Accounts)]
+pub struct ExecuteDca<'info> {
+    #[account(mut)]
+    pub cranker: Signer<'info>,
+
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Box<Account<'info, Config>>,
+
+    #[account(mut)]
+    pub dca_order: Box<Account<'info, DcaOrder>>,
+
+    #[account(mut, token::mint = dca_order.input_mint, token::authority = dca_order)]
+    pub input_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(mut, token::mint = dca_order.output_mint, token::authority = dca_order)]
+    pub output_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+    /// fToken account of the order, required when the order deposits into Earn
+    #[account(mut, token::authority = dca_order)]
+    pub f_token_vault: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    #[account(
+        mut,
+        seeds = [DEPOSIT_CAPS_SEED, dca_order.output_mint.as_ref()],
+        bump = deposit_caps.bump,
+    )]
+    pub deposit_caps: Option<Box<Account<'info, DepositCaps>>>,
+    #[account(
+        mut,
+        seeds = [USER_DEPOSITS_SEED, dca_order.output_mint.as_ref(), dca_order.owner.as_ref()],
+        bump = user_deposits.bump,
+    )]
+    pub user_deposits: Option<Box<Account<'info, UserDeposits>>>,
+
+    pub swap: ClmmSwapAccounts<'info>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+    // remaining accounts, split by remaining_accounts_indices:
+    // tickarray_bitmap_extension and tick arrays for `swap_v2`,
+    // `EarnAccounts` of the order lending market when the order deposits into Earn
+}
+
+pub fn execute_dca<'a, 'b, 'c: 'info, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, ExecuteDca<'info>>,
+    remaining_accounts_indices: Vec<u8>,
+) -> Result<()> {
+    require!(!ctx.accounts.config.paused, InteractDappError::ProgramPaused);
+    if remaining_accounts_indices.len() != 2 {
+        return Err(InteractDappError::InvalidRemainingAccountsIndices.into());
+    }
+    let swap_count = remaining_accounts_indices[0] as usize;
+    let earn_count = remaining_accounts_indices[1] as usize;
+    if ctx.remaining_accounts.len() < swap_count + earn_count {
+        return Err(InteractDappError::InvalidRemainingAccountsIndices.into());
+    }
+    let (swap_accounts, rest) = ctx.remaining_accounts.split_at(swap_count);
+    let earn_accounts = &rest[..earn_count];
+
+    let accounts = &mut ctx.accounts;
+    let order = &accounts.dca_order;
+    let now = Clock::get()?.unix_timestamp;
+    require!(now >= order.next_execution_timestamp, InteractDappError::OrderNotDue);
+    require_keys_eq!(
+        accounts.swap.pool_state.key(),
+        order.pool_state,
+        InteractDappError::InvalidOrderConfig
+    );
+    require_keys_eq!(
+        accounts.swap.input_vault_mint.key(),
+        order.input_mint,
+        InteractDappError::InvalidOrderConfig
+    );
+    require_keys_eq!(
+        accounts.swap.output_vault_mint.key(),
+        order.output_mint,
+        InteractDappError::InvalidOrderConfig
+    );
+
+    let amount_in = order.amount_per_interval.min(accounts.input_vault.amount);
+    require!(amount_in > 0, InteractDappError::ZeroAmount);
+    let min_amount_out = (order.min_output_per_interval as u128 * amount_in as u128
+        / order.amount_per_interval as u128) as u64;
+
+    let order_id = order.order_id.to_le_bytes();
+    let seeds = order.signer_seeds(&order_id);
+    let signer_seeds: &[&[&[u8]]] = &[&seeds];
+    let output_before = accounts.output_vault.amount;
+    accounts.swap.swap(
+        order.to_account_info(),
+        accounts.input_vault.to_account_info(),
+        accounts.output_vault.to_account_info(),
+        swap_accounts,
+        amount_in,
+        min_amount_out,
+        0,
+        true,
+        signer_seeds,
+    )?;
+    accounts.output_vault.reload()?;
+    let amount_out = accounts.output_vault.amount.saturating_sub(output_before);
+    require!(amount_out >= min_amount_out, InteractDappError::OrderOutputTooLow);
+
+    let mut deposited_to_earn = false;
+    if order.deposits_to_earn() {
+        let earn = EarnAccounts::from_remaining_accounts(ctx.program_id, earn_accounts)?;
+        require_keys_eq!(earn.lending.key(), order.lending, InteractDappError::InvalidOrderConfig);
+        let (Some(f_token_vault), Some(deposit_caps), Some(user_deposits)) = (
+            &accounts.f_token_vault,
+            &mut accounts.deposit_caps,
+            &mut accounts.user_deposits,
+        ) else {
+            return Err(InteractDappError::InvalidOrderConfig.into());
+        };
+        let lending = Lending::load(&earn.lending, earn.lending_program.key)?;
+        let tvl = deposit_caps.ledger.get_value(lending.token_exchange_price)?;
+        let user_value = user_deposits.ledger.get_value(lending.token_exchange_price)?;
+        // Over the caps the output stays in the order vault, it goes back on close
+        if amount_out <= deposit_caps.get_deposit_headroom(tvl, user_value) {
+            let user = EarnUser {
+                signer: order.to_account_info(),
+                token_account: accounts.output_vault.to_account_info(),
+                f_token_account: f_token_vault.to_account_info(),
+                mint: accounts.swap.output_vault_mint.to_account_info(),
+                token_program: accounts
+                    .swap
+                    .token_program_of(&accounts.output_vault.to_account_info()),
+                associated_token_program: accounts.associated_token_program.to_account_info(),
+                system_program: accounts.system_program.to_account_info(),
+            };
+            earn.deposit(&user, amount_out, signer_seeds)?;
+            let shares = get_token_balance(&f_token_vault.to_account_info())?
+                .saturating_sub(f_token_vault.amount);
+            deposit_caps.add_shares(user_deposits, shares)?;
+            deposited_to_earn = true;
+        } else {
+            emit!(DepositCapReachedEvent {
+                deposit_caps: deposit_caps.key(),
+                vault: order.key(),
+                depositor: order.owner,
+                requested: amount_out,
+                accepted: 0,
+                tvl,
+                user_value,
+            });
+        }
+    }
+
+    // The tip never dips into the rent of the order account
+    let order_info = order.to_account_info();
+    let rent_exempt = Rent::get()?.minimum_balance(order_info.data_len());
+    let tip = order
+        .tip_lamports
+        .min(order_info.lamports().saturating_sub(rent_exempt));
+
+    let order = &mut accounts.dca_order;
+    order.next_execution_timestamp = now
+        .checked_add(order.interval)
+        .ok_or(InteractDappError::MathOverflow)?;
+    order.total_input_spent = order.total_input_spent.saturating_add(amount_in);
+    order.total_output_received = order.total_output_received.saturating_add(amount_out);
+    if tip > 0 {
+        order.sub_lamports(tip)?;
+        accounts.cranker.add_lamports(tip)?;
+    }
+
+    emit!(DcaExecutedEvent {
+        dca_order: order.key(),
+        cranker: accounts.cranker.key(),
+        amount_in,
+        amount_out,
+        deposited_to_earn,
+        tip_lamports: tip,
+    });
+    Ok(())
+}
@@ -0,0 +1,7 @@
+pub mod close_dca_order;
+pub mod execute_dca;
+pub mod open_dca_order;
+
+pub use close_dca_order::*;
+pub use execute_dca::*;
+pub use open_dca_order::*;
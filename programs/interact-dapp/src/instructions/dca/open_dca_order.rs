@@ -0,0 +1,202 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{self, Transfer};
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked};
+use raydium_amm_v3::states::PoolState;
+use crate::constant::{CONFIG_SEED, DCA_ORDER_SEED, DEPOSIT_CAPS_SEED, USER_DEPOSITS_SEED};
+use crate::error::InteractDappError;
+use crate::programs::JUPITER_LENDING_PROGRAM_ID;
+use crate::states::{Config, DcaOrder, DepositCaps, Lending, UserDeposits};
+
+#[derive(Accounts)]
+#[instruction(order_id: u64)]
+pub struct OpenDcaOrder<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Box<Account<'info, Config>>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + DcaOrder::INIT_SPACE,
+        seeds = [DCA_ORDER_SEED, owner.key().as_ref(), &order_id.to_le_bytes()],
+        bump,
+    )]
+    pub dca_order: Box<Account<'info, DcaOrder>>,
+
+    #[account(mint::token_program = input_token_program)]
+    pub input_mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(mint::token_program = output_token_program)]
+    pub output_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(mut, token::mint = input_mint, token::authority = owner)]
+    pub owner_input_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Escrow of the input tokens
+    #[account(
+        init,
+        payer = owner,
+        associated_token::mint = input_mint,
+        associated_token::authority = dca_order,
+        associated_token::token_program = input_token_program,
+    )]
+    pub input_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+    /// Output of the executed legs, also the Earn depositor account
+    #[account(
+        init,
+        payer = owner,
+        associated_token::mint = output_mint,
+        associated_token::authority = dca_order,
+        associated_token::token_program = output_token_program,
+    )]
+    pub output_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The Raydium CLMM pool the legs are swapped on
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// Jupiter Earn lending market of the output token, to deposit the output into Earn
+    ///CHECK: deserialized and checked in the handler
+    pub lending: Option<UncheckedAccount<'info>>,
+    ///CHECK:
+    #[account(address = JUPITER_LENDING_PROGRAM_ID @ InteractDappError::InvalidLendingProgram)]
+    pub lending_program: Option<UncheckedAccount<'info>>,
+    #[account(mint::token_program = output_token_program)]
+    pub f_token_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+    /// fTokens received for the deposited output
+    #[account(
+        init,
+        payer = owner,
+        associated_token::mint = f_token_mint,
+        associated_token::authority = dca_order,
+        associated_token::token_program = output_token_program,
+    )]
+    pub f_token_vault: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    /// Risk limits of the output mint, the Earn deposits count against them
+    #[account(seeds = [DEPOSIT_CAPS_SEED, output_mint.key().as_ref()], bump = deposit_caps.bump)]
+    pub deposit_caps: Option<Box<Account<'info, DepositCaps>>>,
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = 8 + UserDeposits::INIT_SPACE,
+        seeds = [USER_DEPOSITS_SEED, output_mint.key().as_ref(), owner.key().as_ref()],
+        bump,
+    )]
+    pub user_deposits: Option<Box<Account<'info, UserDeposits>>>,
+
+    pub input_token_program: Interface<'info, TokenInterface>,
+    pub output_token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> OpenDcaOrder<'info> {
+    #[allow(clippy::too_many_arguments)]
+    pub fn open_dca_order(
+        &mut self,
+        bumps: &OpenDcaOrderBumps,
+        order_id: u64,
+        amount: u64,
+        amount_per_interval: u64,
+        min_output_per_interval: u64,
+        interval: i64,
+        tip_lamports: u64,
+    ) -> Result<()> {
+        require!(!self.config.paused, InteractDappError::ProgramPaused);
+        require!(
+            amount > 0 && amount_per_interval > 0 && interval > 0,
+            InteractDappError::InvalidOrderConfig
+        );
+        let input_mint = self.input_mint.key();
+        let output_mint = self.output_mint.key();
+        {
+            let pool_state = self.pool_state.load()?;
+            let pool_mints = (pool_state.token_mint_0, pool_state.token_mint_1);
+            require!(
+                pool_mints == (input_mint, output_mint) || pool_mints == (output_mint, input_mint),
+                InteractDappError::InvalidOrderConfig
+            );
+        }
+
+        let lending = match (
+            &self.lending,
+            &self.lending_program,
+            &self.f_token_vault,
+            &self.deposit_caps,
+            &mut self.user_deposits,
+            bumps.user_deposits,
+        ) {
+            (
+                Some(lending),
+                Some(lending_program),
+                Some(f_token_vault),
+                Some(_),
+                Some(user_deposits),
+                Some(bump),
+            ) => {
+                let market = Lending::load(lending, lending_program.key)?;
+                require_keys_eq!(market.mint, output_mint, InteractDappError::InvalidOrderConfig);
+                require_keys_eq!(
+                    market.f_token_mint,
+                    f_token_vault.mint,
+                    InteractDappError::InvalidOrderConfig
+                );
+                user_deposits.initialize(output_mint, self.owner.key(), bump);
+                lending.key()
+            }
+            (None, None, None, None, None, None) => Pubkey::default(),
+            _ => return Err(InteractDappError::InvalidOrderConfig.into()),
+        };
+
+        token_interface::transfer_checked(
+            CpiContext::new(
+                self.input_token_program.to_account_info(),
+                TransferChecked {
+                    from: self.owner_input_account.to_account_info(),
+                    mint: self.input_mint.to_account_info(),
+                    to: self.input_vault.to_account_info(),
+                    authority: self.owner.to_account_info(),
+                },
+            ),
+            amount,
+            self.input_mint.decimals,
+        )?;
+
+        // Tips for every leg are prepaid, what is left is refunded when the order is closed
+        let legs = amount.div_ceil(amount_per_interval);
+        let tips = tip_lamports
+            .checked_mul(legs)
+            .ok_or(InteractDappError::MathOverflow)?;
+        if tips > 0 {
+            system_program::transfer(
+                CpiContext::new(
+                    self.system_program.to_account_info(),
+                    Transfer {
+                        from: self.owner.to_account_info(),
+                        to: self.dca_order.to_account_info(),
+                    },
+                ),
+                tips,
+            )?;
+        }
+
+        self.dca_order.set_inner(DcaOrder {
+            owner: self.owner.key(),
+            order_id,
+            input_mint,
+            output_mint,
+            pool_state: self.pool_state.key(),
+            amount_per_interval,
+            min_output_per_interval,
+            interval,
+            next_execution_timestamp: Clock::get()?.unix_timestamp,
+            tip_lamports,
+            lending,
+            total_input_spent: 0,
+            total_output_received: 0,
+            bump: bumps.dca_order,
+        });
+        Ok(())
+    }
+}
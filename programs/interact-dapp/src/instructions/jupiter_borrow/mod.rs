@@ -0,0 +1,3 @@
+pub mod position_health;
+
+pub use position_health::*;
@@ -0,0 +1,147 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{
+    account_info::AccountInfo,
+    instruction::{AccountMeta, Instruction},
+    program::{get_return_data, invoke},
+};
+use crate::error::InteractDappError;
+use crate::math::{compute_position_health, get_position_amounts, PositionHealth};
+use crate::programs::JUPITER_VAULTS_PROGRAM_ID;
+use crate::states::{Oracle, Position, Tick, VaultConfig, VaultState};
+
+fn get_exchange_rate_operate_discriminator() -> Vec<u8> {
+    // discriminator = sha256("global:get_exchange_rate_operate")[0..8]
+    vec![174, 166, 126, 10, 122, 153, 94, 203]
+}
+
+#[derive(Accounts)]
+pub struct PositionHealthParams<'info> {
+    ///CHECK: validated against the vaults program
+    pub vault_config: AccountInfo<'info>,
+    ///CHECK: validated against the vaults program
+    pub vault_state: AccountInfo<'info>,
+    ///CHECK: validated against the vaults program
+    pub position: AccountInfo<'info>,
+    ///CHECK: validated against the vaults program
+    pub current_position_tick: AccountInfo<'info>,
+    ///CHECK: must match vault_config.oracle
+    pub oracle: AccountInfo<'info>,
+    ///CHECK: must match vault_config.oracle_program
+    pub oracle_program: AccountInfo<'info>,
+
+    ///CHECK: owner of the vault accounts
+    #[account(address = JUPITER_VAULTS_PROGRAM_ID @ InteractDappError::InvalidVaultsProgram)]
+    pub vaults_program: UncheckedAccount<'info>,
+    // remaining accounts, split by remaining_accounts_indices as in `operate`
+    // oracle sources
+    // branch accounts and tick has debt arrays, unused: counts of 0 are expected
+}
+
+impl<'info> PositionHealthParams<'info> {
+    /// Asks the Jupiter oracle for the collateral/debt rate used by `operate`.
+    fn get_oracle_price(&self, nonce: u16, sources: &[AccountInfo<'info>]) -> Result<u128> {
+        let mut instruction_data = get_exchange_rate_operate_discriminator();
+        instruction_data.extend_from_slice(&nonce.to_le_bytes());
+
+        let mut account_metas = vec![AccountMeta::new_readonly(*self.oracle.key, false)];
+        let mut account_infos = vec![self.oracle.clone()];
+        for source in sources {
+            account_metas.push(AccountMeta::new_readonly(*source.key, false));
+            account_infos.push(source.clone());
+        }
+
+        let instruction = Instruction {
+            program_id: *self.oracle_program.key,
+            accounts: account_metas,
+            data: instruction_data,
+        };
+        invoke(&instruction, &account_infos)
+            .map_err(|_| InteractDappError::CpiToOracleProgramFailed)?;
+
+        let (program_id, data) =
+            get_return_data().ok_or(InteractDappError::InvalidOraclePrice)?;
+        require_keys_eq!(
+            program_id,
+            *self.oracle_program.key,
+            InteractDappError::InvalidOraclePrice
+        );
+        let price: [u8; 16] = data
+            .get(..16)
+            .and_then(|bytes| bytes.try_into().ok())
+            .ok_or(InteractDappError::InvalidOraclePrice)?;
+        let price = u128::from_le_bytes(price);
+        require!(price > 0, InteractDappError::InvalidOraclePrice);
+        Ok(price)
+    }
+}
+
+pub fn position_health<'a, 'b, 'c: 'info, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, PositionHealthParams<'info>>,
+    remaining_accounts_indices: Vec<u8>,
+) -> Result<PositionHealth> {
+    if remaining_accounts_indices.len() != 3 {
+        return Err(InteractDappError::InvalidRemainingAccountsIndices.into());
+    }
+    // Only the oracle sources are read, the branches and tick has debt arrays `operate`
+    // takes after them are not required
+    let oracle_sources_count = remaining_accounts_indices[0] as usize;
+    if ctx.remaining_accounts.len() < oracle_sources_count {
+        return Err(InteractDappError::InvalidRemainingAccountsIndices.into());
+    }
+
+    let accounts = &ctx.accounts;
+    let vaults_program = accounts.vaults_program.key;
+    let vault_config = VaultConfig::load(&accounts.vault_config, vaults_program)?;
+    let vault_state = VaultState::load(&accounts.vault_state, vaults_program)?;
+    let position = Position::load(&accounts.position, vaults_program)?;
+    let tick = Tick::load(&accounts.current_position_tick, vaults_program)?;
+
+    require!(
+        vault_state.vault_id == vault_config.vault_id && position.vault_id == vault_config.vault_id,
+        InteractDappError::VaultMismatch
+    );
+    require!(
+        tick.vault_id == position.vault_id && tick.tick == position.tick,
+        InteractDappError::VaultMismatch
+    );
+    require_keys_eq!(
+        *accounts.oracle.key,
+        vault_config.oracle,
+        InteractDappError::InvalidOracle
+    );
+    require_keys_eq!(
+        *accounts.oracle_program.key,
+        vault_config.oracle_program,
+        InteractDappError::InvalidOracle
+    );
+
+    // A liquidated tick means the stored amounts are stale until the position is
+    // next touched through `operate`, which walks the branches for us. They are returned
+    // as stored, flagged by `is_liquidated`.
+    let is_liquidated = position.is_supply_only_position == 0 && tick.is_liquidated == 1;
+
+    let oracle = Oracle::load(&accounts.oracle, accounts.oracle_program.key)?;
+    let oracle_price =
+        accounts.get_oracle_price(oracle.nonce, &ctx.remaining_accounts[..oracle_sources_count])?;
+
+    let (collateral, debt) = get_position_amounts(&position, &vault_state)
+        .ok_or(InteractDappError::MathOverflow)?;
+    let mut health = compute_position_health(
+        position.nft_id,
+        collateral,
+        debt,
+        oracle_price,
+        vault_config.collateral_factor,
+        vault_config.liquidation_threshold,
+    )
+    .ok_or(InteractDappError::MathOverflow)?;
+    health.is_liquidated = is_liquidated;
+
+    msg!(
+        "position {} ltv {} bps, liquidation distance {} bps",
+        health.nft_id,
+        health.ltv_bps,
+        health.liquidation_distance_bps
+    );
+    Ok(health)
+}
@@ -0,0 +1,118 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{
+    instruction::{AccountMeta, Instruction},
+    program::invoke,
+};
+use crate::error::InteractDappError;
+use crate::events::ClaimWithdrawalEvent;
+use crate::programs::JUPITER_LIQUIDITY_PROGRAM_ID;
+use crate::states::UserClaim;
+use crate::utils::get_token_balance;
+
+fn get_claim_discriminator() -> Vec<u8> {
+    // discriminator = sha256("global:claim")[0..8]
+    vec![62, 198, 214, 193, 213, 159, 108, 210]
+}
+
+/// Delivers the withdrawals queued on the liquidity layer claim account of `signer`. Only
+/// wallets have queued claims: withdrawals of program vaults fail instead of queueing, see
+/// `EarnAccounts::withdraw`.
+#[derive(Accounts)]
+pub struct ClaimWithdrawalParams<'info> {
+    ///CHECK:
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    ///CHECK:
+    #[account(mut)]
+    pub recipient_token_account: AccountInfo<'info>,
+
+    // Liquidity protocol accounts
+    ///CHECK:
+    pub liquidity: AccountInfo<'info>,
+    ///CHECK:
+    #[account(mut)]
+    pub supply_token_reserves_liquidity: AccountInfo<'info>,
+    ///CHECK:
+    pub mint: AccountInfo<'info>,
+    ///CHECK:
+    #[account(mut)]
+    pub vault: AccountInfo<'info>,
+    ///CHECK: checked against the liquidity program and `signer` in the handler
+    #[account(mut)]
+    pub claim_account: AccountInfo<'info>,
+
+    // Programs
+    ///CHECK:
+    pub token_program: AccountInfo<'info>,
+    ///CHECK:
+    pub associated_token_program: AccountInfo<'info>,
+
+    // Target liquidity program
+    ///CHECK:
+    #[account(address = JUPITER_LIQUIDITY_PROGRAM_ID @ InteractDappError::InvalidLiquidityProgram)]
+    pub liquidity_program: UncheckedAccount<'info>,
+}
+
+impl<'info> ClaimWithdrawalParams<'info> {
+    pub fn claim_withdrawal(&self) -> Result<()> {
+        let claim = UserClaim::load(&self.claim_account, self.liquidity_program.key)?;
+        require_keys_eq!(claim.user, self.signer.key(), InteractDappError::InvalidAccountData);
+        require_keys_eq!(claim.mint, self.mint.key(), InteractDappError::InvalidAccountData);
+        require!(claim.amount > 0, InteractDappError::ZeroAmount);
+
+        let mut instruction_data = get_claim_discriminator();
+        instruction_data.extend_from_slice(self.signer.key.as_ref());
+
+        let account_metas = vec![
+            // user (signer)
+            AccountMeta::new_readonly(*self.signer.key, true),
+            // liquidity
+            AccountMeta::new_readonly(*self.liquidity.key, false),
+            // token_reserve (mutable)
+            AccountMeta::new(*self.supply_token_reserves_liquidity.key, false),
+            // mint
+            AccountMeta::new_readonly(*self.mint.key, false),
+            // recipient_token_account (mutable)
+            AccountMeta::new(*self.recipient_token_account.key, false),
+            // vault (mutable)
+            AccountMeta::new(*self.vault.key, false),
+            // claim_account (mutable)
+            AccountMeta::new(*self.claim_account.key, false),
+            // token_program
+            AccountMeta::new_readonly(*self.token_program.key, false),
+            // associated_token_program
+            AccountMeta::new_readonly(*self.associated_token_program.key, false),
+        ];
+        let instruction = Instruction {
+            program_id: *self.liquidity_program.key,
+            accounts: account_metas,
+            data: instruction_data,
+        };
+
+        let assets_before = get_token_balance(&self.recipient_token_account)?;
+        invoke(
+            &instruction,
+            &[
+                self.signer.to_account_info(),
+                self.liquidity.clone(),
+                self.supply_token_reserves_liquidity.clone(),
+                self.mint.clone(),
+                self.recipient_token_account.clone(),
+                self.vault.clone(),
+                self.claim_account.clone(),
+                self.token_program.clone(),
+                self.associated_token_program.clone(),
+            ],
+        )
+        .map_err(|_| InteractDappError::CpiToLiquidityProgramFailed)?;
+
+        let assets_after = get_token_balance(&self.recipient_token_account)?;
+        emit!(ClaimWithdrawalEvent {
+            signer: self.signer.key(),
+            mint: self.mint.key(),
+            recipient_token_account: self.recipient_token_account.key(),
+            amount: assets_after.saturating_sub(assets_before),
+        });
+        Ok(())
+    }
+}
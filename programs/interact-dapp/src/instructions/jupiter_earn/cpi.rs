@@ -0,0 +1,298 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::solana_program::program::invoke_signed;
+use anchor_lang::Discriminator;
+use std::collections::BTreeSet;
+use crate::error::InteractDappError;
+use crate::jupiter_lend;
+use crate::programs::{JUPITER_LENDING_PROGRAM_ID, JUPITER_LIQUIDITY_PROGRAM_ID};
+use crate::states::UserClaim;
+
+/// Accounts of the Jupiter `deposit` instruction
+pub const DEPOSIT_ACCOUNTS_LEN: usize = 17;
+/// Accounts of the Jupiter `withdraw` and `redeem` instructions, which share their list
+pub const WITHDRAW_ACCOUNTS_LEN: usize = 18;
+
+/// Instruction data of the lending instructions: discriminator and a single `u64` argument
+const LENDING_DATA_LEN: usize = 16;
+
+// Writable flags of the instruction accounts in IDL order. Only the first account, the
+// depositor or withdrawer, signs.
+const DEPOSIT_WRITABLE: [bool; DEPOSIT_ACCOUNTS_LEN] = [
+    true,  // signer
+    true,  // depositor_token_account
+    true,  // recipient_token_account
+    false, // mint
+    false, // lending_admin
+    true,  // lending
+    true,  // f_token_mint
+    true,  // supply_token_reserves_liquidity
+    true,  // lending_supply_position_on_liquidity
+    false, // rate_model
+    true,  // vault
+    true,  // liquidity
+    true,  // liquidity_program
+    false, // rewards_rate_model
+    false, // token_program
+    false, // associated_token_program
+    false, // system_program
+];
+/// Shared by `withdraw` and `redeem`
+const WITHDRAW_WRITABLE: [bool; WITHDRAW_ACCOUNTS_LEN] = [
+    true,  // signer
+    true,  // owner_token_account
+    true,  // recipient_token_account
+    false, // lending_admin
+    true,  // lending
+    false, // mint
+    true,  // f_token_mint
+    true,  // supply_token_reserves_liquidity
+    true,  // lending_supply_position_on_liquidity
+    false, // rate_model
+    true,  // vault
+    true,  // claim_account
+    true,  // liquidity
+    true,  // liquidity_program
+    false, // rewards_rate_model
+    false, // token_program
+    false, // associated_token_program
+    false, // system_program
+];
+
+/// Invokes the lending instruction `T` with its `u64` argument. The metas and data are
+/// built on the stack and copied once into the `Instruction`, instead of growing through
+/// the client `ToAccountMetas` and `InstructionData` impls. `account_infos` is handed to
+/// the runtime as it is.
+fn invoke_lending<T: Discriminator, const N: usize>(
+    lending_program: &AccountInfo,
+    account_infos: &[AccountInfo; N],
+    writable: &[bool; N],
+    argument: u64,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let metas: [AccountMeta; N] = std::array::from_fn(|i| AccountMeta {
+        pubkey: *account_infos[i].key,
+        is_signer: i == 0,
+        is_writable: writable[i],
+    });
+    let mut data = [0; LENDING_DATA_LEN];
+    data[..8].copy_from_slice(T::DISCRIMINATOR);
+    data[8..].copy_from_slice(&argument.to_le_bytes());
+
+    let instruction = Instruction {
+        program_id: *lending_program.key,
+        accounts: metas.to_vec(),
+        data: data.to_vec(),
+    };
+    invoke_signed(&instruction, account_infos, signer_seeds)
+        .map_err(|_| InteractDappError::CpiToLendingProgramFailed.into())
+}
+
+/// `deposit` with the accounts in IDL order, see [`EarnAccounts::deposit`].
+pub fn deposit_cpi(
+    lending_program: &AccountInfo,
+    account_infos: &[AccountInfo; DEPOSIT_ACCOUNTS_LEN],
+    assets: u64,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    invoke_lending::<jupiter_lend::client::args::Deposit, DEPOSIT_ACCOUNTS_LEN>(
+        lending_program,
+        account_infos,
+        &DEPOSIT_WRITABLE,
+        assets,
+        signer_seeds,
+    )
+}
+
+/// `withdraw` with the accounts in IDL order, see [`EarnAccounts::withdraw`].
+pub fn withdraw_cpi(
+    lending_program: &AccountInfo,
+    account_infos: &[AccountInfo; WITHDRAW_ACCOUNTS_LEN],
+    amount: u64,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    invoke_lending::<jupiter_lend::client::args::Withdraw, WITHDRAW_ACCOUNTS_LEN>(
+        lending_program,
+        account_infos,
+        &WITHDRAW_WRITABLE,
+        amount,
+        signer_seeds,
+    )
+}
+
+/// `redeem` with the accounts in IDL order, see [`EarnAccounts::redeem`].
+pub fn redeem_cpi(
+    lending_program: &AccountInfo,
+    account_infos: &[AccountInfo; WITHDRAW_ACCOUNTS_LEN],
+    shares: u64,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    invoke_lending::<jupiter_lend::client::args::Redeem, WITHDRAW_ACCOUNTS_LEN>(
+        lending_program,
+        account_infos,
+        &WITHDRAW_WRITABLE,
+        shares,
+        signer_seeds,
+    )
+}
+
+/// Lending market accounts for Earn CPIs made on behalf of a program-owned account.
+#[derive(Accounts)]
+pub struct EarnAccounts<'info> {
+    ///CHECK:
+    pub lending_admin: AccountInfo<'info>,
+    ///CHECK:
+    #[account(mut)]
+    pub lending: AccountInfo<'info>,
+    ///CHECK:
+    #[account(mut)]
+    pub f_token_mint: AccountInfo<'info>,
+    ///CHECK:
+    #[account(mut)]
+    pub supply_token_reserves_liquidity: AccountInfo<'info>,
+    ///CHECK:
+    #[account(mut)]
+    pub lending_supply_position_on_liquidity: AccountInfo<'info>,
+    ///CHECK:
+    pub rate_model: AccountInfo<'info>,
+    ///CHECK:
+    #[account(mut)]
+    pub vault: AccountInfo<'info>,
+    ///CHECK:
+    #[account(mut)]
+    pub liquidity: AccountInfo<'info>,
+    ///CHECK:
+    #[account(
+        mut,
+        address = JUPITER_LIQUIDITY_PROGRAM_ID @ InteractDappError::InvalidLiquidityProgram
+    )]
+    pub liquidity_program: AccountInfo<'info>,
+    ///CHECK:
+    pub rewards_rate_model: AccountInfo<'info>,
+    ///CHECK:
+    #[account(address = JUPITER_LENDING_PROGRAM_ID @ InteractDappError::InvalidLendingProgram)]
+    pub lending_program: UncheckedAccount<'info>,
+}
+
+/// The depositing / withdrawing side of an Earn CPI.
+pub struct EarnUser<'info> {
+    pub signer: AccountInfo<'info>,
+    /// Underlying token account, ATA of `signer`
+    pub token_account: AccountInfo<'info>,
+    /// fToken account, ATA of `signer`
+    pub f_token_account: AccountInfo<'info>,
+    pub mint: AccountInfo<'info>,
+    pub token_program: AccountInfo<'info>,
+    pub associated_token_program: AccountInfo<'info>,
+    pub system_program: AccountInfo<'info>,
+}
+
+impl<'info> EarnAccounts<'info> {
+    /// Reads the accounts from a slice of remaining accounts, for instructions where the
+    /// Earn leg is optional.
+    pub fn from_remaining_accounts(
+        program_id: &Pubkey,
+        accounts: &'info [AccountInfo<'info>],
+    ) -> Result<Self> {
+        let mut accounts = accounts;
+        Self::try_accounts(
+            program_id,
+            &mut accounts,
+            &[],
+            &mut EarnAccountsBumps::default(),
+            &mut BTreeSet::new(),
+        )
+    }
+
+    pub fn deposit(
+        &self,
+        user: &EarnUser<'info>,
+        assets: u64,
+        signer_seeds: &[&[&[u8]]],
+    ) -> Result<()> {
+        let accounts = [
+            user.signer.clone(),
+            user.token_account.clone(),
+            user.f_token_account.clone(),
+            user.mint.clone(),
+            self.lending_admin.clone(),
+            self.lending.clone(),
+            self.f_token_mint.clone(),
+            self.supply_token_reserves_liquidity.clone(),
+            self.lending_supply_position_on_liquidity.clone(),
+            self.rate_model.clone(),
+            self.vault.clone(),
+            self.liquidity.clone(),
+            self.liquidity_program.clone(),
+            self.rewards_rate_model.clone(),
+            user.token_program.clone(),
+            user.associated_token_program.clone(),
+            user.system_program.clone(),
+        ];
+        deposit_cpi(&self.lending_program, &accounts, assets, signer_seeds)
+    }
+
+    /// Withdraws `amount` underlying of `user`. A program-owned user cannot sign the
+    /// liquidity `claim`, so a withdrawal the liquidity layer queues on `claim_account`
+    /// fails instead of stranding the assets there.
+    pub fn withdraw(
+        &self,
+        user: &EarnUser<'info>,
+        claim_account: &AccountInfo<'info>,
+        amount: u64,
+        signer_seeds: &[&[&[u8]]],
+    ) -> Result<()> {
+        let accounts = self.withdraw_accounts(user, claim_account);
+        let claim_before = UserClaim::get_amount(claim_account, self.liquidity_program.key)?;
+        withdraw_cpi(&self.lending_program, &accounts, amount, signer_seeds)?;
+        self.check_not_queued(claim_account, claim_before)
+    }
+
+    /// Burns `shares` fTokens of `user` for the underlying, failing like `withdraw` if the
+    /// liquidity layer queues it.
+    pub fn redeem(
+        &self,
+        user: &EarnUser<'info>,
+        claim_account: &AccountInfo<'info>,
+        shares: u64,
+        signer_seeds: &[&[&[u8]]],
+    ) -> Result<()> {
+        let accounts = self.withdraw_accounts(user, claim_account);
+        let claim_before = UserClaim::get_amount(claim_account, self.liquidity_program.key)?;
+        redeem_cpi(&self.lending_program, &accounts, shares, signer_seeds)?;
+        self.check_not_queued(claim_account, claim_before)
+    }
+
+    fn check_not_queued(&self, claim_account: &AccountInfo, claim_before: u64) -> Result<()> {
+        let claim_after = UserClaim::get_amount(claim_account, self.liquidity_program.key)?;
+        require!(claim_after <= claim_before, InteractDappError::WithdrawalQueued);
+        Ok(())
+    }
+
+    fn withdraw_accounts(
+        &self,
+        user: &EarnUser<'info>,
+        claim_account: &AccountInfo<'info>,
+    ) -> [AccountInfo<'info>; WITHDRAW_ACCOUNTS_LEN] {
+        [
+            user.signer.clone(),
+            user.f_token_account.clone(),
+            user.token_account.clone(),
+            self.lending_admin.clone(),
+            self.lending.clone(),
+            user.mint.clone(),
+            self.f_token_mint.clone(),
+            self.supply_token_reserves_liquidity.clone(),
+            self.lending_supply_position_on_liquidity.clone(),
+            self.rate_model.clone(),
+            self.vault.clone(),
+            claim_account.clone(),
+            self.liquidity.clone(),
+            self.liquidity_program.clone(),
+            self.rewards_rate_model.clone(),
+            user.token_program.clone(),
+            user.associated_token_program.clone(),
+            user.system_program.clone(),
+        ]
+    }
+}
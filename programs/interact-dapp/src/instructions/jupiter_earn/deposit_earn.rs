@@ -1,22 +1,21 @@
 use anchor_lang::prelude::*;
-use anchor_lang::solana_program::program::invoke_signed;
-use anchor_lang::solana_program::{
-    account_info::AccountInfo,
-    instruction::{AccountMeta, Instruction},
-    program::invoke,
-};
+use crate::constant::CONFIG_SEED;
 use crate::error::InteractDappError;
-
-fn get_deposit_discriminator() -> Vec<u8> {
-    // discriminator = sha256("global:deposit")[0..8]
-    vec![242, 35, 198, 137, 82, 225, 242, 182]
-}
+use crate::events::DepositEarnEvent;
+use crate::programs::{JUPITER_LENDING_PROGRAM_ID, JUPITER_LIQUIDITY_PROGRAM_ID};
+use crate::states::Config;
+use crate::utils::get_token_balance;
+use super::deposit_cpi;
 
 #[derive(Accounts)]
 pub struct DepositParams<'info> {
     ///CHECK:
     #[account(mut)]
     pub signer: Signer<'info>,
+
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Box<Account<'info, Config>>,
+
     ///CHECK:
     #[account(mut)]
     pub depositor_token_account: AccountInfo<'info>,
@@ -52,7 +51,10 @@ pub struct DepositParams<'info> {
     #[account(mut)]
     pub liquidity: AccountInfo<'info>,
     ///CHECK:
-    #[account(mut)]
+    #[account(
+        mut,
+        address = JUPITER_LIQUIDITY_PROGRAM_ID @ InteractDappError::InvalidLiquidityProgram
+    )]
     pub liquidity_program: AccountInfo<'info>,
 
     // Rewards and programs
@@ -67,81 +69,48 @@ pub struct DepositParams<'info> {
 
     // Target lending program
     ///CHECK:
+    #[account(address = JUPITER_LENDING_PROGRAM_ID @ InteractDappError::InvalidLendingProgram)]
     pub lending_program: UncheckedAccount<'info>,
 }
 
 impl<'info> DepositParams<'info> {
-    
     pub fn deposit_earn(&self, amount: u64) -> Result<()> {
-        let mut instruction_data = get_deposit_discriminator();
-        instruction_data.extend_from_slice(&amount.to_le_bytes());
-
-        let account_metas = vec![
-            // signer (mutable, signer)
-            AccountMeta::new(*self.signer.key, true),
-            // depositor_token_account (mutable)
-            AccountMeta::new(*self.depositor_token_account.key, false),
-            // recipient_token_account (mutable)
-            AccountMeta::new(*self.recipient_token_account.key, false),
-            // mint
-            AccountMeta::new_readonly(*self.mint.key, false),
-            // lending_admin (readonly)
-            AccountMeta::new_readonly(*self.lending_admin.key, false),
-            // lending (mutable)
-            AccountMeta::new(*self.lending.key, false),
-            // f_token_mint (mutable)
-            AccountMeta::new(*self.f_token_mint.key, false),
-            // supply_token_reserves_liquidity (mutable)
-            AccountMeta::new(*self.supply_token_reserves_liquidity.key, false),
-            // lending_supply_position_on_liquidity (mutable)
-            AccountMeta::new(*self.lending_supply_position_on_liquidity.key, false),
-            // rate_model (readonly)
-            AccountMeta::new_readonly(*self.rate_model.key, false),
-            // vault (mutable)
-            AccountMeta::new(*self.vault.key, false),
-            // liquidity (mutable)
-            AccountMeta::new(*self.liquidity.key, false),
-            // liquidity_program (mutable)
-            AccountMeta::new(*self.liquidity_program.key, false),
-            // rewards_rate_model (readonly)
-            AccountMeta::new_readonly(*self.rewards_rate_model.key, false),
-            // token_program
-            AccountMeta::new_readonly(*self.token_program.key, false),
-            // associated_token_program
-            AccountMeta::new_readonly(*self.associated_token_program.key, false),
-            // system_program
-            AccountMeta::new_readonly(*self.system_program.key, false),
+        require!(!self.config.paused, InteractDappError::ProgramPaused);
+        // Jupiter `deposit` accounts, in IDL order
+        let accounts = [
+            self.signer.to_account_info(),
+            self.depositor_token_account.to_account_info(),
+            self.recipient_token_account.to_account_info(),
+            self.mint.to_account_info(),
+            self.lending_admin.to_account_info(),
+            self.lending.to_account_info(),
+            self.f_token_mint.to_account_info(),
+            self.supply_token_reserves_liquidity.to_account_info(),
+            self.lending_supply_position_on_liquidity.to_account_info(),
+            self.rate_model.to_account_info(),
+            self.vault.to_account_info(),
+            self.liquidity.to_account_info(),
+            self.liquidity_program.to_account_info(),
+            self.rewards_rate_model.to_account_info(),
+            self.token_program.to_account_info(),
+            self.associated_token_program.to_account_info(),
+            self.system_program.to_account_info(),
         ];
 
-        let instruction = Instruction {
-            program_id: *self.lending_program.key,
-            accounts: account_metas,
-            data: instruction_data,
-        };
-        msg!("hehe");
+        let assets_before = get_token_balance(&self.depositor_token_account)?;
+        let shares_before = get_token_balance(&self.recipient_token_account)?;
 
-        invoke(
-            &instruction,
-            &[
-                self.signer.to_account_info(),
-                self.depositor_token_account.clone(),
-                self.recipient_token_account.clone(),
-                self.mint.clone(),
-                self.lending_admin.clone(),
-                self.lending.clone(),
-                self.f_token_mint.clone(),
-                self.supply_token_reserves_liquidity.clone(),
-                self.lending_supply_position_on_liquidity.clone(),
-                self.rate_model.clone(),
-                self.vault.clone(),
-                self.liquidity.clone(),
-                self.liquidity_program.clone(),
-                self.rewards_rate_model.clone(),
-                self.token_program.clone(),
-                self.associated_token_program.clone(),
-                self.system_program.clone(),
-            ],
-        )
-        .map_err(|_| InteractDappError::CpiToLendingProgramFailed.into())
+        deposit_cpi(&self.lending_program, &accounts, amount, &[])?;
+
+        let assets_after = get_token_balance(&self.depositor_token_account)?;
+        let shares_after = get_token_balance(&self.recipient_token_account)?;
+        emit!(DepositEarnEvent {
+            signer: self.signer.key(),
+            mint: self.mint.key(),
+            amount,
+            assets_deposited: assets_before.saturating_sub(assets_after),
+            shares_minted: shares_after.saturating_sub(shares_before),
+        });
+        Ok(())
     }
-}
\ No newline at end of file
+}
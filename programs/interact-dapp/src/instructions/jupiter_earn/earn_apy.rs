@@ -0,0 +1,56 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+use crate::error::InteractDappError;
+use crate::math::{get_earn_apy, EarnApy};
+use crate::programs::{JUPITER_LENDING_PROGRAM_ID, JUPITER_LIQUIDITY_PROGRAM_ID};
+use crate::states::{Lending, LendingRewardsRateModel, TokenReserve};
+
+#[derive(Accounts)]
+pub struct EarnApyParams<'info> {
+    ///CHECK: validated against the lending program
+    pub lending: AccountInfo<'info>,
+    /// Must match lending.f_token_mint
+    pub f_token_mint: Box<InterfaceAccount<'info, Mint>>,
+    ///CHECK: must match lending.token_reserves_liquidity
+    pub supply_token_reserves_liquidity: AccountInfo<'info>,
+    ///CHECK: must match lending.rewards_rate_model
+    pub rewards_rate_model: AccountInfo<'info>,
+
+    ///CHECK: owner of the lending and rewards rate model accounts
+    #[account(address = JUPITER_LENDING_PROGRAM_ID @ InteractDappError::InvalidLendingProgram)]
+    pub lending_program: UncheckedAccount<'info>,
+    ///CHECK: owner of the token reserve account
+    #[account(address = JUPITER_LIQUIDITY_PROGRAM_ID @ InteractDappError::InvalidLiquidityProgram)]
+    pub liquidity_program: UncheckedAccount<'info>,
+}
+
+impl<'info> EarnApyParams<'info> {
+    pub fn earn_apy(&self) -> Result<EarnApy> {
+        let lending = Lending::load(&self.lending, self.lending_program.key)?;
+        let reserve = TokenReserve::load(
+            &self.supply_token_reserves_liquidity,
+            self.liquidity_program.key,
+        )?;
+        let model =
+            LendingRewardsRateModel::load(&self.rewards_rate_model, self.lending_program.key)?;
+        require_keys_eq!(
+            lending.token_reserves_liquidity,
+            *self.supply_token_reserves_liquidity.key,
+            InteractDappError::InvalidAccountData
+        );
+        require_keys_eq!(
+            lending.rewards_rate_model,
+            *self.rewards_rate_model.key,
+            InteractDappError::InvalidAccountData
+        );
+        require_keys_eq!(
+            lending.f_token_mint,
+            self.f_token_mint.key(),
+            InteractDappError::InvalidAccountData
+        );
+
+        let now = Clock::get()?.unix_timestamp;
+        get_earn_apy(&lending, &reserve, &model, self.f_token_mint.supply, now)
+            .ok_or(InteractDappError::MathOverflow.into())
+    }
+}
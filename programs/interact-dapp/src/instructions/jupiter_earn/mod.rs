@@ -1,5 +1,14 @@
+pub mod claim_withdrawal;
+pub mod cpi;
 pub mod deposit_earn;
+pub mod earn_apy;
+pub mod preview_earn_position;
 pub mod withdraw_earn;
+pub mod withdraw_earn_max_available;
 
+pub use claim_withdrawal::*;
+pub use cpi::*;
 pub use deposit_earn::*;
-pub use withdraw_earn::*;
\ No newline at end of file
+pub use earn_apy::*;
+pub use preview_earn_position::*;
+pub use withdraw_earn::*;
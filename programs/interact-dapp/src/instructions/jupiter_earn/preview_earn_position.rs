@@ -0,0 +1,90 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount};
+use crate::error::InteractDappError;
+use crate::math::{convert_to_assets, get_rewards_rate, preview_earn_position, EarnPositionPreview};
+use crate::programs::{JUPITER_LENDING_PROGRAM_ID, JUPITER_LIQUIDITY_PROGRAM_ID};
+use crate::states::{Lending, LendingRewardsRateModel, TokenReserve};
+
+#[derive(Accounts)]
+pub struct PreviewEarnPositionParams<'info> {
+    /// The fToken account of the position owner
+    pub owner_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    ///CHECK: validated against the lending program
+    pub lending: AccountInfo<'info>,
+    ///CHECK: must match lending.token_reserves_liquidity
+    pub supply_token_reserves_liquidity: AccountInfo<'info>,
+
+    /// Optional, to include the rewards in the exchange price; requires `f_token_mint`
+    ///CHECK: must match lending.rewards_rate_model
+    pub rewards_rate_model: Option<AccountInfo<'info>>,
+    /// Must match lending.f_token_mint
+    pub f_token_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+
+    ///CHECK: owner of the lending account
+    #[account(address = JUPITER_LENDING_PROGRAM_ID @ InteractDappError::InvalidLendingProgram)]
+    pub lending_program: UncheckedAccount<'info>,
+    ///CHECK: owner of the token reserve account
+    #[account(address = JUPITER_LIQUIDITY_PROGRAM_ID @ InteractDappError::InvalidLiquidityProgram)]
+    pub liquidity_program: UncheckedAccount<'info>,
+}
+
+impl<'info> PreviewEarnPositionParams<'info> {
+    pub fn preview_earn_position(
+        &self,
+        principal: u64,
+        deposit_assets: u64,
+        withdraw_assets: u64,
+    ) -> Result<EarnPositionPreview> {
+        let lending = Lending::load(&self.lending, self.lending_program.key)?;
+        let reserve = TokenReserve::load(
+            &self.supply_token_reserves_liquidity,
+            self.liquidity_program.key,
+        )?;
+        require_keys_eq!(
+            lending.token_reserves_liquidity,
+            *self.supply_token_reserves_liquidity.key,
+            InteractDappError::InvalidAccountData
+        );
+        require_keys_eq!(
+            self.owner_token_account.mint,
+            lending.f_token_mint,
+            InteractDappError::InvalidAccountData
+        );
+
+        let now = Clock::get()?.unix_timestamp;
+        let rewards_rate = match (&self.rewards_rate_model, &self.f_token_mint) {
+            (Some(rewards_rate_model), Some(f_token_mint)) => {
+                require_keys_eq!(
+                    lending.rewards_rate_model,
+                    *rewards_rate_model.key,
+                    InteractDappError::InvalidAccountData
+                );
+                require_keys_eq!(
+                    lending.f_token_mint,
+                    f_token_mint.key(),
+                    InteractDappError::InvalidAccountData
+                );
+                let model =
+                    LendingRewardsRateModel::load(rewards_rate_model, self.lending_program.key)?;
+                let total_assets =
+                    convert_to_assets(f_token_mint.supply, lending.token_exchange_price)
+                        .ok_or(InteractDappError::MathOverflow)?;
+                get_rewards_rate(&model, total_assets, now).ok_or(InteractDappError::MathOverflow)?
+            }
+            // without the rewards rate model only the liquidity yield is included
+            _ => 0,
+        };
+        preview_earn_position(
+            &lending,
+            &reserve,
+            self.owner_token_account.amount,
+            principal,
+            deposit_assets,
+            withdraw_assets,
+            rewards_rate,
+            now,
+        )
+        .ok_or(InteractDappError::MathOverflow.into())
+    }
+}
@@ -1,15 +1,11 @@
 use anchor_lang::prelude::*;
-use anchor_lang::solana_program::{
-    account_info::AccountInfo,
-    instruction::{AccountMeta, Instruction},
-    program::invoke,
-};
+use crate::constant::CONFIG_SEED;
 use crate::error::InteractDappError;
-
-fn get_withdraw_discriminator() -> Vec<u8> {
-    // discriminator = sha256("global:withdraw")[0..8]
-    vec![183, 18, 70, 156, 148, 109, 161, 34]
-}
+use crate::events::{WithdrawEarnEvent, WithdrawalQueuedEvent};
+use crate::programs::{JUPITER_LENDING_PROGRAM_ID, JUPITER_LIQUIDITY_PROGRAM_ID};
+use crate::states::{Config, UserClaim};
+use crate::utils::get_token_balance;
+use super::withdraw_cpi;
 
 #[derive(Accounts)]
 pub struct WithdrawParams<'info> {
@@ -17,6 +13,10 @@ pub struct WithdrawParams<'info> {
     ///CHECK:
     #[account(mut)]
     pub signer: Signer<'info>,
+
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Box<Account<'info, Config>>,
+
     ///CHECK:
     #[account(mut)]
     pub owner_token_account: AccountInfo<'info>,
@@ -55,7 +55,10 @@ pub struct WithdrawParams<'info> {
     #[account(mut)]
     pub liquidity: AccountInfo<'info>,
     ///CHECK:
-    #[account(mut)]
+    #[account(
+        mut,
+        address = JUPITER_LIQUIDITY_PROGRAM_ID @ InteractDappError::InvalidLiquidityProgram
+    )]
     pub liquidity_program: AccountInfo<'info>,
 
     // Rewards and programs
@@ -70,82 +73,61 @@ pub struct WithdrawParams<'info> {
 
     // Target lending program
     ///CHECK:
+    #[account(address = JUPITER_LENDING_PROGRAM_ID @ InteractDappError::InvalidLendingProgram)]
     pub lending_program: UncheckedAccount<'info>,
 }
 
 impl<'info> WithdrawParams<'info> {
     pub fn withdraw_earn(&self, assets: u64) -> Result<()> {
-        let mut instruction_data = get_withdraw_discriminator();
-        instruction_data.extend_from_slice(&assets.to_le_bytes());
-
-        let account_metas = vec![
-            // signer (mutable, signer)
-            AccountMeta::new(*self.signer.key, true),
-            // owner_token_account (mutable) - user's fToken account
-            AccountMeta::new(*self.owner_token_account.key, false),
-            // recipient_token_account (mutable) - user's underlying token account
-            AccountMeta::new(*self.recipient_token_account.key, false),
-            // lending_admin (readonly)
-            AccountMeta::new_readonly(*self.lending_admin.key, false),
-            // lending (mutable)
-            AccountMeta::new(*self.lending.key, false),
-            // mint (readonly) - underlying token mint
-            AccountMeta::new_readonly(*self.mint.key, false),
-            // f_token_mint (mutable)
-            AccountMeta::new(*self.f_token_mint.key, false),
-            // supply_token_reserves_liquidity (mutable)
-            AccountMeta::new(*self.supply_token_reserves_liquidity.key, false),
-            // lending_supply_position_on_liquidity (mutable)
-            AccountMeta::new(*self.lending_supply_position_on_liquidity.key, false),
-            // rate_model (readonly)
-            AccountMeta::new_readonly(*self.rate_model.key, false),
-            // vault (mutable)
-            AccountMeta::new(*self.vault.key, false),
-            // claim_account (mutable)
-            AccountMeta::new(*self.claim_account.key, false),
-            // liquidity (mutable)
-            AccountMeta::new(*self.liquidity.key, false),
-            // liquidity_program (mutable)
-            AccountMeta::new(*self.liquidity_program.key, false),
-            // rewards_rate_model (readonly)
-            AccountMeta::new_readonly(*self.rewards_rate_model.key, false),
-            // token_program
-            AccountMeta::new_readonly(*self.token_program.key, false),
-            // associated_token_program
-            AccountMeta::new_readonly(*self.associated_token_program.key, false),
-            // system_program
-            AccountMeta::new_readonly(*self.system_program.key, false),
+        require!(!self.config.paused, InteractDappError::ProgramPaused);
+        // Jupiter `withdraw` accounts, in IDL order
+        let accounts = [
+            self.signer.to_account_info(),
+            self.owner_token_account.to_account_info(),
+            self.recipient_token_account.to_account_info(),
+            self.lending_admin.to_account_info(),
+            self.lending.to_account_info(),
+            self.mint.to_account_info(),
+            self.f_token_mint.to_account_info(),
+            self.supply_token_reserves_liquidity.to_account_info(),
+            self.lending_supply_position_on_liquidity.to_account_info(),
+            self.rate_model.to_account_info(),
+            self.vault.to_account_info(),
+            self.claim_account.to_account_info(),
+            self.liquidity.to_account_info(),
+            self.liquidity_program.to_account_info(),
+            self.rewards_rate_model.to_account_info(),
+            self.token_program.to_account_info(),
+            self.associated_token_program.to_account_info(),
+            self.system_program.to_account_info(),
         ];
 
-        let instruction = Instruction {
-            program_id: *self.lending_program.key,
-            accounts: account_metas,
-            data: instruction_data,
-        };
+        let shares_before = get_token_balance(&self.owner_token_account)?;
+        let assets_before = get_token_balance(&self.recipient_token_account)?;
+        let claim_before = UserClaim::get_amount(&self.claim_account, self.liquidity_program.key)?;
 
-        invoke(
-            &instruction,
-            &[
-                self.signer.to_account_info(),
-                self.owner_token_account.clone(),
-                self.recipient_token_account.clone(),
-                self.lending_admin.clone(),
-                self.lending.clone(),
-                self.mint.clone(),
-                self.f_token_mint.clone(),
-                self.supply_token_reserves_liquidity.clone(),
-                self.lending_supply_position_on_liquidity.clone(),
-                self.rate_model.clone(),
-                self.vault.clone(),
-                self.claim_account.clone(),
-                self.liquidity.clone(),
-                self.liquidity_program.clone(),
-                self.rewards_rate_model.clone(),
-                self.token_program.clone(),
-                self.associated_token_program.clone(),
-                self.system_program.clone(),
-            ],
-        )
-        .map_err(|_| InteractDappError::CpiToLendingProgramFailed.into())
+        withdraw_cpi(&self.lending_program, &accounts, assets, &[])?;
+
+        let shares_after = get_token_balance(&self.owner_token_account)?;
+        let assets_after = get_token_balance(&self.recipient_token_account)?;
+        let claim_after = UserClaim::get_amount(&self.claim_account, self.liquidity_program.key)?;
+        // Assets the liquidity layer could not transfer directly are queued on the claim
+        // account, to be delivered by `claim_withdrawal`
+        if claim_after > claim_before {
+            emit!(WithdrawalQueuedEvent {
+                signer: self.signer.key(),
+                mint: self.mint.key(),
+                claim_account: self.claim_account.key(),
+                amount: claim_after - claim_before,
+            });
+        }
+        emit!(WithdrawEarnEvent {
+            signer: self.signer.key(),
+            mint: self.mint.key(),
+            amount: assets,
+            assets_withdrawn: assets_after.saturating_sub(assets_before),
+            shares_burned: shares_before.saturating_sub(shares_after),
+        });
+        Ok(())
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,58 @@
+use anchor_lang::prelude::*;
+use crate::error::InteractDappError;
+use crate::events::WithdrawalLimitedEvent;
+use crate::math::get_withdrawable_amount;
+use crate::states::{Lending, TokenReserve, UserSupplyPosition};
+use crate::utils::get_token_balance;
+use super::WithdrawParams;
+
+impl<'info> WithdrawParams<'info> {
+    /// Underlying amount the lending market can withdraw from the liquidity layer right now.
+    pub fn get_withdrawable_amount(&self) -> Result<u64> {
+        let lending = Lending::load(&self.lending, self.lending_program.key)?;
+        require_keys_eq!(
+            lending.token_reserves_liquidity,
+            *self.supply_token_reserves_liquidity.key,
+            InteractDappError::InvalidAccountData
+        );
+        require_keys_eq!(
+            lending.supply_position_on_liquidity,
+            *self.lending_supply_position_on_liquidity.key,
+            InteractDappError::InvalidAccountData
+        );
+        let reserve = TokenReserve::load(
+            &self.supply_token_reserves_liquidity,
+            self.liquidity_program.key,
+        )?;
+        require_keys_eq!(reserve.vault, *self.vault.key, InteractDappError::InvalidAccountData);
+        let position = UserSupplyPosition::load(
+            &self.lending_supply_position_on_liquidity,
+            self.liquidity_program.key,
+        )?;
+
+        let now = Clock::get()?.unix_timestamp;
+        get_withdrawable_amount(&position, &reserve, get_token_balance(&self.vault)?, now)
+            .ok_or(InteractDappError::MathOverflow.into())
+    }
+
+    /// Withdraws `min(assets, withdrawable)` and returns the part of `assets` left unwithdrawn.
+    pub fn withdraw_earn_max_available(&self, assets: u64) -> Result<u64> {
+        let available = self.get_withdrawable_amount()?;
+        let amount = assets.min(available);
+        require!(amount > 0, InteractDappError::ZeroAmount);
+
+        self.withdraw_earn(amount)?;
+
+        let remainder = assets - amount;
+        if remainder > 0 {
+            emit!(WithdrawalLimitedEvent {
+                signer: self.signer.key(),
+                mint: self.mint.key(),
+                requested: assets,
+                withdrawn: amount,
+                remainder,
+            });
+        }
+        Ok(remainder)
+    }
+}
@@ -0,0 +1,93 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::constant::{DEPOSIT_CAPS_SEED, USER_DEPOSITS_SEED};
+use crate::error::InteractDappError;
+use crate::states::{DepositCaps, LimitOrder, UserDeposits};
+use crate::utils::drain_token_account;
+
+/// Closes a limit order, returning the unfilled escrow and its fTokens to the owner.
+#[derive(Accounts)]
+pub struct CancelLimitOrder<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(mut, close = owner, has_one = owner, has_one = input_mint)]
+    pub limit_order: Box<Account<'info, LimitOrder>>,
+
+    #[account(mint::token_program = input_token_program)]
+    pub input_mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(mut, token::mint = input_mint, token::authority = limit_order)]
+    pub input_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(mut, token::mint = input_mint)]
+    pub owner_input_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    // Required when the escrow is kept in Earn
+    pub f_token_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+    #[account(mut, token::mint = f_token_mint, token::authority = limit_order)]
+    pub f_token_vault: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    #[account(mut, token::mint = f_token_mint)]
+    pub owner_f_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    #[account(
+        mut,
+        seeds = [DEPOSIT_CAPS_SEED, input_mint.key().as_ref()],
+        bump = deposit_caps.bump,
+    )]
+    pub deposit_caps: Option<Box<Account<'info, DepositCaps>>>,
+    #[account(
+        mut,
+        seeds = [USER_DEPOSITS_SEED, input_mint.key().as_ref(), owner.key().as_ref()],
+        bump = user_deposits.bump,
+    )]
+    pub user_deposits: Option<Box<Account<'info, UserDeposits>>>,
+
+    pub input_token_program: Interface<'info, TokenInterface>,
+}
+
+impl<'info> CancelLimitOrder<'info> {
+    pub fn cancel_limit_order(&mut self) -> Result<()> {
+        let order = &self.limit_order;
+        let order_id = order.order_id.to_le_bytes();
+        let seeds = order.signer_seeds(&order_id);
+        let signer_seeds: &[&[&[u8]]] = &[&seeds];
+
+        drain_token_account(
+            self.input_token_program.to_account_info(),
+            &self.input_vault,
+            &self.input_mint,
+            self.owner_input_account.to_account_info(),
+            order.to_account_info(),
+            self.owner.to_account_info(),
+            signer_seeds,
+        )?;
+
+        if order.escrows_in_earn() {
+            let (
+                Some(f_token_mint),
+                Some(f_token_vault),
+                Some(owner_f_token_account),
+                Some(deposit_caps),
+                Some(user_deposits),
+            ) = (
+                &self.f_token_mint,
+                &self.f_token_vault,
+                &self.owner_f_token_account,
+                &mut self.deposit_caps,
+                &mut self.user_deposits,
+            )
+            else {
+                return Err(InteractDappError::InvalidOrderConfig.into());
+            };
+            deposit_caps.remove_shares(user_deposits, f_token_vault.amount);
+            drain_token_account(
+                self.input_token_program.to_account_info(),
+                f_token_vault,
+                f_token_mint,
+                owner_f_token_account.to_account_info(),
+                order.to_account_info(),
+                self.owner.to_account_info(),
+                signer_seeds,
+            )?;
+        }
+        Ok(())
+    }
+}
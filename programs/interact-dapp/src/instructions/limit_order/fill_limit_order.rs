@@ -0,0 +1,208 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_interface::TokenAccount;
+use crate::constant::{CONFIG_SEED, DEPOSIT_CAPS_SEED, USER_DEPOSITS_SEED};
+use crate::error::InteractDappError;
+use crate::events::LimitOrderFilledEvent;
+use crate::instructions::{ClmmSwapAccounts, EarnAccounts, EarnUser};
+use crate::states::{Config, DepositCaps, LimitOrder, UserDeposits};
+
+/// Fills a limit order once its pool has traded through the order price. The swap stops
+/// at the order price, so the order may be filled partially; an escrow kept in Earn is
+/// redeemed for the swap and its unfilled part deposited back.
+#[derive(Accounts)]
+pub struct FillLimitOrder<'info> {
+    pub keeper: Signer<'info>,
+
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Box<Account<'info, Config>>,
+
+    #[account(mut)]
+    pub limit_order: Box<Account<'info, LimitOrder>>,
+
+    #[account(mut, token::mint = limit_order.input_mint, token::authority = limit_order)]
+    pub input_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+    /// Output token account of the order owner, receives the proceeds
+    #[account(
+        mut,
+        token::mint = limit_order.output_mint,
+        token::authority = limit_order.owner,
+    )]
+    pub owner_output_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    // Required when the escrow is kept in Earn
+    #[account(mut, token::authority = limit_order)]
+    pub f_token_vault: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    ///CHECK: Earn claim account of the order
+    #[account(mut)]
+    pub claim_account: Option<UncheckedAccount<'info>>,
+    #[account(
+        mut,
+        seeds = [DEPOSIT_CAPS_SEED, limit_order.input_mint.as_ref()],
+        bump = deposit_caps.bump,
+    )]
+    pub deposit_caps: Option<Box<Account<'info, DepositCaps>>>,
+    #[account(
+        mut,
+        seeds = [
+            USER_DEPOSITS_SEED,
+            limit_order.input_mint.as_ref(),
+            limit_order.owner.as_ref(),
+        ],
+        bump = user_deposits.bump,
+    )]
+    pub user_deposits: Option<Box<Account<'info, UserDeposits>>>,
+
+    pub swap: ClmmSwapAccounts<'info>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+    // remaining accounts, split by remaining_accounts_indices:
+    // tickarray_bitmap_extension and tick arrays for `swap_v2`,
+    // `EarnAccounts` of the order lending market when the escrow is kept in Earn
+}
+
+pub fn fill_limit_order<'a, 'b, 'c: 'info, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, FillLimitOrder<'info>>,
+    remaining_accounts_indices: Vec<u8>,
+) -> Result<()> {
+    require!(!ctx.accounts.config.paused, InteractDappError::ProgramPaused);
+    if remaining_accounts_indices.len() != 2 {
+        return Err(InteractDappError::InvalidRemainingAccountsIndices.into());
+    }
+    let swap_count = remaining_accounts_indices[0] as usize;
+    let earn_count = remaining_accounts_indices[1] as usize;
+    if ctx.remaining_accounts.len() < swap_count + earn_count {
+        return Err(InteractDappError::InvalidRemainingAccountsIndices.into());
+    }
+    let (swap_accounts, rest) = ctx.remaining_accounts.split_at(swap_count);
+    let earn_accounts = &rest[..earn_count];
+
+    let accounts = &mut ctx.accounts;
+    let order = &accounts.limit_order;
+    require_keys_eq!(
+        accounts.swap.pool_state.key(),
+        order.pool_state,
+        InteractDappError::InvalidOrderConfig
+    );
+    require_keys_eq!(
+        accounts.swap.input_vault_mint.key(),
+        order.input_mint,
+        InteractDappError::InvalidOrderConfig
+    );
+    require_keys_eq!(
+        accounts.swap.output_vault_mint.key(),
+        order.output_mint,
+        InteractDappError::InvalidOrderConfig
+    );
+    let sqrt_price_x64 = accounts.swap.pool_state.load()?.sqrt_price_x64;
+    require!(order.is_crossed(sqrt_price_x64), InteractDappError::LimitPriceNotCrossed);
+
+    let order_id = order.order_id.to_le_bytes();
+    let seeds = order.signer_seeds(&order_id);
+    let signer_seeds: &[&[&[u8]]] = &[&seeds];
+    let input_token_program = accounts
+        .swap
+        .token_program_of(&accounts.input_vault.to_account_info());
+
+    // The fill size is only known after the swap: the whole escrow is redeemed and what the
+    // swap leaves is deposited back
+    let earn = if order.escrows_in_earn() {
+        let (
+            Some(f_token_vault),
+            Some(claim_account),
+            Some(deposit_caps),
+            Some(user_deposits),
+        ) = (
+            &mut accounts.f_token_vault,
+            &accounts.claim_account,
+            &mut accounts.deposit_caps,
+            &mut accounts.user_deposits,
+        )
+        else {
+            return Err(InteractDappError::InvalidOrderConfig.into());
+        };
+        let earn = EarnAccounts::from_remaining_accounts(ctx.program_id, earn_accounts)?;
+        require_keys_eq!(earn.lending.key(), order.lending, InteractDappError::InvalidOrderConfig);
+        let shares = f_token_vault.amount;
+        if shares > 0 {
+            let user = EarnUser {
+                signer: order.to_account_info(),
+                token_account: accounts.input_vault.to_account_info(),
+                f_token_account: f_token_vault.to_account_info(),
+                mint: accounts.swap.input_vault_mint.to_account_info(),
+                token_program: input_token_program.clone(),
+                associated_token_program: accounts.associated_token_program.to_account_info(),
+                system_program: accounts.system_program.to_account_info(),
+            };
+            earn.redeem(&user, claim_account, shares, signer_seeds)?;
+            f_token_vault.reload()?;
+            deposit_caps.remove_shares(user_deposits, shares.saturating_sub(f_token_vault.amount));
+            accounts.input_vault.reload()?;
+        }
+        Some(earn)
+    } else {
+        None
+    };
+
+    let input_before = accounts.input_vault.amount;
+    require!(input_before > 0, InteractDappError::ZeroAmount);
+    let output_before = accounts.owner_output_account.amount;
+    // The price limit bounds the execution price, no minimum amount out is needed
+    accounts.swap.swap(
+        order.to_account_info(),
+        accounts.input_vault.to_account_info(),
+        accounts.owner_output_account.to_account_info(),
+        swap_accounts,
+        input_before,
+        0,
+        order.sqrt_price_limit_x64,
+        true,
+        signer_seeds,
+    )?;
+    accounts.input_vault.reload()?;
+    accounts.owner_output_account.reload()?;
+    let remaining = accounts.input_vault.amount;
+    let amount_in = input_before.saturating_sub(remaining);
+    let amount_out = accounts
+        .owner_output_account
+        .amount
+        .saturating_sub(output_before);
+
+    if let (Some(earn), Some(f_token_vault), Some(deposit_caps), Some(user_deposits)) = (
+        &earn,
+        &mut accounts.f_token_vault,
+        &mut accounts.deposit_caps,
+        &mut accounts.user_deposits,
+    ) {
+        if remaining > 0 {
+            let user = EarnUser {
+                signer: order.to_account_info(),
+                token_account: accounts.input_vault.to_account_info(),
+                f_token_account: f_token_vault.to_account_info(),
+                mint: accounts.swap.input_vault_mint.to_account_info(),
+                token_program: input_token_program,
+                associated_token_program: accounts.associated_token_program.to_account_info(),
+                system_program: accounts.system_program.to_account_info(),
+            };
+            let shares_before = f_token_vault.amount;
+            earn.deposit(&user, remaining, signer_seeds)?;
+            f_token_vault.reload()?;
+            let minted = f_token_vault.amount.saturating_sub(shares_before);
+            deposit_caps.add_shares(user_deposits, minted)?;
+        }
+    }
+
+    let order = &mut accounts.limit_order;
+    order.filled_amount_in = order.filled_amount_in.saturating_add(amount_in);
+    order.filled_amount_out = order.filled_amount_out.saturating_add(amount_out);
+
+    emit!(LimitOrderFilledEvent {
+        limit_order: order.key(),
+        keeper: accounts.keeper.key(),
+        amount_in,
+        amount_out,
+        remaining,
+    });
+    Ok(())
+}
@@ -0,0 +1,190 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked};
+use raydium_amm_v3::{libraries::tick_math, states::PoolState};
+use crate::constant::{CONFIG_SEED, DEPOSIT_CAPS_SEED, LIMIT_ORDER_SEED, USER_DEPOSITS_SEED};
+use crate::error::InteractDappError;
+use crate::instructions::{EarnAccounts, EarnUser};
+use crate::programs::JUPITER_LENDING_PROGRAM_ID;
+use crate::states::{Config, DepositCaps, Lending, LimitOrder, UserDeposits};
+use crate::utils::get_token_balance;
+
+#[derive(Accounts)]
+#[instruction(order_id: u64)]
+pub struct OpenLimitOrder<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Box<Account<'info, Config>>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + LimitOrder::INIT_SPACE,
+        seeds = [LIMIT_ORDER_SEED, owner.key().as_ref(), &order_id.to_le_bytes()],
+        bump,
+    )]
+    pub limit_order: Box<Account<'info, LimitOrder>>,
+
+    #[account(mint::token_program = input_token_program)]
+    pub input_mint: Box<InterfaceAccount<'info, Mint>>,
+    pub output_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(mut, token::mint = input_mint, token::authority = owner)]
+    pub owner_input_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Escrow of the input tokens, also the Earn depositor account
+    #[account(
+        init,
+        payer = owner,
+        associated_token::mint = input_mint,
+        associated_token::authority = limit_order,
+        associated_token::token_program = input_token_program,
+    )]
+    pub input_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The Raydium CLMM pool the order fills on
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// Jupiter Earn lending market of the input token, to keep the escrow in Earn
+    ///CHECK: deserialized and checked in the handler
+    pub lending: Option<UncheckedAccount<'info>>,
+    ///CHECK:
+    #[account(address = JUPITER_LENDING_PROGRAM_ID @ InteractDappError::InvalidLendingProgram)]
+    pub lending_program: Option<UncheckedAccount<'info>>,
+    #[account(mint::token_program = input_token_program)]
+    pub f_token_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+    /// fTokens received for the escrow
+    #[account(
+        init,
+        payer = owner,
+        associated_token::mint = f_token_mint,
+        associated_token::authority = limit_order,
+        associated_token::token_program = input_token_program,
+    )]
+    pub f_token_vault: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    /// Risk limits of the input mint, the escrow counts against them
+    #[account(
+        mut,
+        seeds = [DEPOSIT_CAPS_SEED, input_mint.key().as_ref()],
+        bump = deposit_caps.bump,
+    )]
+    pub deposit_caps: Option<Box<Account<'info, DepositCaps>>>,
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = 8 + UserDeposits::INIT_SPACE,
+        seeds = [USER_DEPOSITS_SEED, input_mint.key().as_ref(), owner.key().as_ref()],
+        bump,
+    )]
+    pub user_deposits: Option<Box<Account<'info, UserDeposits>>>,
+
+    pub input_token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+    // remaining accounts
+    // `EarnAccounts` of the lending market when the escrow is kept in Earn
+}
+
+pub fn open_limit_order<'a, 'b, 'c: 'info, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, OpenLimitOrder<'info>>,
+    order_id: u64,
+    amount: u64,
+    sqrt_price_limit_x64: u128,
+) -> Result<()> {
+    require!(!ctx.accounts.config.paused, InteractDappError::ProgramPaused);
+    require!(amount > 0, InteractDappError::ZeroAmount);
+    require!(
+        sqrt_price_limit_x64 > tick_math::MIN_SQRT_PRICE_X64
+            && sqrt_price_limit_x64 < tick_math::MAX_SQRT_PRICE_X64,
+        InteractDappError::InvalidOrderConfig
+    );
+    let accounts = &mut ctx.accounts;
+    let input_mint = accounts.input_mint.key();
+    let output_mint = accounts.output_mint.key();
+    let zero_for_one = {
+        let pool_state = accounts.pool_state.load()?;
+        let pool_mints = (pool_state.token_mint_0, pool_state.token_mint_1);
+        require!(
+            pool_mints == (input_mint, output_mint) || pool_mints == (output_mint, input_mint),
+            InteractDappError::InvalidOrderConfig
+        );
+        pool_state.token_mint_0 == input_mint
+    };
+
+    let (lending, token_exchange_price) = match (
+        &accounts.lending,
+        &accounts.lending_program,
+        &accounts.f_token_vault,
+    ) {
+        (Some(lending), Some(lending_program), Some(f_token_vault)) => {
+            let market = Lending::load(lending, lending_program.key)?;
+            require_keys_eq!(market.mint, input_mint, InteractDappError::InvalidOrderConfig);
+            require_keys_eq!(
+                market.f_token_mint,
+                f_token_vault.mint,
+                InteractDappError::InvalidOrderConfig
+            );
+            (lending.key(), market.token_exchange_price)
+        }
+        (None, None, None) => (Pubkey::default(), 0),
+        _ => return Err(InteractDappError::InvalidOrderConfig.into()),
+    };
+
+    accounts.limit_order.set_inner(LimitOrder {
+        owner: accounts.owner.key(),
+        order_id,
+        input_mint,
+        output_mint,
+        pool_state: accounts.pool_state.key(),
+        sqrt_price_limit_x64,
+        zero_for_one,
+        lending,
+        filled_amount_in: 0,
+        filled_amount_out: 0,
+        bump: ctx.bumps.limit_order,
+    });
+
+    token_interface::transfer_checked(
+        CpiContext::new(
+            accounts.input_token_program.to_account_info(),
+            TransferChecked {
+                from: accounts.owner_input_account.to_account_info(),
+                mint: accounts.input_mint.to_account_info(),
+                to: accounts.input_vault.to_account_info(),
+                authority: accounts.owner.to_account_info(),
+            },
+        ),
+        amount,
+        accounts.input_mint.decimals,
+    )?;
+
+    let order = &accounts.limit_order;
+    if let Some(f_token_vault) = &accounts.f_token_vault {
+        let earn = EarnAccounts::from_remaining_accounts(ctx.program_id, ctx.remaining_accounts)?;
+        require_keys_eq!(earn.lending.key(), order.lending, InteractDappError::InvalidOrderConfig);
+        let (Some(deposit_caps), Some(user_deposits), Some(bump)) =
+            (&mut accounts.deposit_caps, &mut accounts.user_deposits, ctx.bumps.user_deposits)
+        else {
+            return Err(InteractDappError::InvalidOrderConfig.into());
+        };
+        user_deposits.initialize(input_mint, order.owner, bump);
+        deposit_caps.check_deposit(user_deposits, amount, token_exchange_price)?;
+
+        let user = EarnUser {
+            signer: order.to_account_info(),
+            token_account: accounts.input_vault.to_account_info(),
+            f_token_account: f_token_vault.to_account_info(),
+            mint: accounts.input_mint.to_account_info(),
+            token_program: accounts.input_token_program.to_account_info(),
+            associated_token_program: accounts.associated_token_program.to_account_info(),
+            system_program: accounts.system_program.to_account_info(),
+        };
+        let order_id = order.order_id.to_le_bytes();
+        earn.deposit(&user, amount, &[&order.signer_seeds(&order_id)])?;
+        let shares = get_token_balance(&f_token_vault.to_account_info())?;
+        deposit_caps.add_shares(user_deposits, shares)?;
+    }
+    Ok(())
+}
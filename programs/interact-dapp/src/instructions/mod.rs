@@ -1,5 +1,21 @@
+pub mod admin;
+pub mod allocator;
+pub mod dca;
+pub mod jupiter_borrow;
 pub mod jupiter_earn;
+pub mod limit_order;
+pub mod operator;
 pub mod raydium;
+pub mod strategy;
+pub mod trigger_order;
 
+pub use admin::*;
+pub use allocator::*;
+pub use dca::*;
+pub use jupiter_borrow::*;
 pub use jupiter_earn::*;
+pub use limit_order::*;
+pub use operator::*;
 pub use raydium::*;
+pub use strategy::*;
+pub use trigger_order::*;
@@ -0,0 +1,105 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::constant::OPERATOR_DELEGATION_SEED;
+use crate::error::InteractDappError;
+use crate::programs::JUPITER_LENDING_PROGRAM_ID;
+use crate::states::{Lending, OperatorDelegation};
+
+#[derive(Accounts)]
+pub struct CreateOperatorDelegation<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    ///CHECK: key authorized to act for the owner
+    pub operator: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + OperatorDelegation::INIT_SPACE,
+        seeds = [
+            OPERATOR_DELEGATION_SEED,
+            owner.key().as_ref(),
+            operator.key().as_ref(),
+            mint.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub delegation: Box<Account<'info, OperatorDelegation>>,
+
+    #[account(mint::token_program = token_program)]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(mint::token_program = token_program)]
+    pub f_token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// Transit accounts of the delegation, Jupiter Earn requires ATAs of the signer
+    #[account(
+        init,
+        payer = owner,
+        associated_token::mint = mint,
+        associated_token::authority = delegation,
+        associated_token::token_program = token_program,
+    )]
+    pub token_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        init,
+        payer = owner,
+        associated_token::mint = f_token_mint,
+        associated_token::authority = delegation,
+        associated_token::token_program = token_program,
+    )]
+    pub f_token_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    ///CHECK: deserialized and checked in the handler
+    pub lending: AccountInfo<'info>,
+    ///CHECK:
+    #[account(address = JUPITER_LENDING_PROGRAM_ID @ InteractDappError::InvalidLendingProgram)]
+    pub lending_program: UncheckedAccount<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> CreateOperatorDelegation<'info> {
+    pub fn create_operator_delegation(
+        &mut self,
+        bumps: &CreateOperatorDelegationBumps,
+        permissions: u8,
+        max_amount_per_period: u64,
+        period: i64,
+        expires_at: i64,
+    ) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        require!(
+            permissions != 0
+                && permissions & !OperatorDelegation::ALL_PERMISSIONS == 0
+                && period > 0
+                && expires_at > now,
+            InteractDappError::InvalidOrderConfig
+        );
+        let lending = Lending::load(&self.lending, self.lending_program.key)?;
+        require_keys_eq!(lending.mint, self.mint.key(), InteractDappError::InvalidOrderConfig);
+        require_keys_eq!(
+            lending.f_token_mint,
+            self.f_token_mint.key(),
+            InteractDappError::InvalidOrderConfig
+        );
+
+        self.delegation.set_inner(OperatorDelegation {
+            owner: self.owner.key(),
+            operator: self.operator.key(),
+            mint: self.mint.key(),
+            f_token_mint: self.f_token_mint.key(),
+            lending: self.lending.key(),
+            permissions,
+            max_amount_per_period,
+            period,
+            period_start: now,
+            period_spent: 0,
+            expires_at,
+            bump: bumps.delegation,
+        });
+        Ok(())
+    }
+}
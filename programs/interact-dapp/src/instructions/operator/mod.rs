@@ -0,0 +1,61 @@
+pub mod create_operator_delegation;
+pub mod operator_deposit_earn;
+pub mod operator_withdraw_earn;
+pub mod revoke_operator_delegation;
+
+pub use create_operator_delegation::*;
+pub use operator_deposit_earn::*;
+pub use operator_withdraw_earn::*;
+pub use revoke_operator_delegation::*;
+
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{self, Mint, TransferChecked};
+use crate::error::InteractDappError;
+use crate::instructions::EarnAccounts;
+use crate::states::OperatorDelegation;
+
+/// Checks that the Earn accounts target the delegation lending market.
+pub(crate) fn check_delegation_earn_accounts(
+    delegation: &OperatorDelegation,
+    earn: &EarnAccounts,
+) -> Result<()> {
+    require_keys_eq!(earn.lending.key(), delegation.lending, InteractDappError::InvalidOrderConfig);
+    require_keys_eq!(
+        earn.f_token_mint.key(),
+        delegation.f_token_mint,
+        InteractDappError::InvalidOrderConfig
+    );
+    require_keys_eq!(
+        earn.lending_program.key(),
+        *earn.lending.owner,
+        InteractDappError::InvalidAccountOwner
+    );
+    Ok(())
+}
+
+/// Moves `amount` tokens with the delegation as signer, either as owner of its transit
+/// accounts or as SPL delegate of the user accounts.
+pub(crate) fn transfer_signed<'info>(
+    token_program: AccountInfo<'info>,
+    from: AccountInfo<'info>,
+    mint: &InterfaceAccount<'info, Mint>,
+    to: AccountInfo<'info>,
+    delegation: AccountInfo<'info>,
+    amount: u64,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    token_interface::transfer_checked(
+        CpiContext::new_with_signer(
+            token_program,
+            TransferChecked {
+                from,
+                mint: mint.to_account_info(),
+                to,
+                authority: delegation,
+            },
+            signer_seeds,
+        ),
+        amount,
+        mint.decimals,
+    )
+}
@@ -0,0 +1,117 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::constant::{CONFIG_SEED, DEPOSIT_CAPS_SEED};
+use crate::error::InteractDappError;
+use crate::events::OperatorEarnEvent;
+use crate::instructions::{EarnAccounts, EarnUser};
+use crate::math::convert_to_assets;
+use crate::states::{Config, DepositCaps, Lending, OperatorDelegation};
+use super::{check_delegation_earn_accounts, transfer_signed};
+
+/// `deposit_earn` signed by an operator: the owner underlying is deposited through the
+/// delegation and the minted fTokens are sent back to the owner.
+#[derive(Accounts)]
+pub struct OperatorDepositEarn<'info> {
+    pub operator: Signer<'info>,
+
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Box<Account<'info, Config>>,
+
+    #[account(mut, has_one = operator, has_one = mint, has_one = f_token_mint)]
+    pub delegation: Box<Account<'info, OperatorDelegation>>,
+
+    #[account(mint::token_program = token_program)]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(mint::token_program = token_program)]
+    pub f_token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// Underlying account of the owner, with the delegation approved as SPL delegate
+    #[account(mut, token::mint = mint, token::authority = delegation.owner)]
+    pub owner_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(mut, token::mint = f_token_mint, token::authority = delegation.owner)]
+    pub owner_f_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut, token::mint = mint, token::authority = delegation)]
+    pub token_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(mut, token::mint = f_token_mint, token::authority = delegation)]
+    pub f_token_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub earn: EarnAccounts<'info>,
+
+    /// Risk limits of the mint. The minted fTokens go back to the owner, so the deposit is
+    /// checked against the caps without being recorded in their ledger.
+    #[account(seeds = [DEPOSIT_CAPS_SEED, mint.key().as_ref()], bump = deposit_caps.bump)]
+    pub deposit_caps: Box<Account<'info, DepositCaps>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> OperatorDepositEarn<'info> {
+    pub fn operator_deposit_earn(&mut self, amount: u64) -> Result<()> {
+        require!(!self.config.paused, InteractDappError::ProgramPaused);
+        require!(amount > 0, InteractDappError::ZeroAmount);
+        check_delegation_earn_accounts(&self.delegation, &self.earn)?;
+        let lending = Lending::load(&self.earn.lending, self.earn.lending_program.key)?;
+        let tvl = self.deposit_caps.ledger.get_value(lending.token_exchange_price)?;
+        let owner_value =
+            convert_to_assets(self.owner_f_token_account.amount, lending.token_exchange_price)
+                .ok_or(InteractDappError::MathOverflow)?;
+        require!(
+            amount <= self.deposit_caps.get_deposit_headroom(tvl, owner_value),
+            InteractDappError::DepositCapExceeded
+        );
+        let now = Clock::get()?.unix_timestamp;
+        self.delegation
+            .consume(OperatorDelegation::DEPOSIT_EARN, amount, now)?;
+
+        let delegation = &self.delegation;
+        let seeds = delegation.signer_seeds();
+        let signer_seeds: &[&[&[u8]]] = &[&seeds];
+
+        transfer_signed(
+            self.token_program.to_account_info(),
+            self.owner_token_account.to_account_info(),
+            &self.mint,
+            self.token_vault.to_account_info(),
+            delegation.to_account_info(),
+            amount,
+            signer_seeds,
+        )?;
+        let user = EarnUser {
+            signer: delegation.to_account_info(),
+            token_account: self.token_vault.to_account_info(),
+            f_token_account: self.f_token_vault.to_account_info(),
+            mint: self.mint.to_account_info(),
+            token_program: self.token_program.to_account_info(),
+            associated_token_program: self.associated_token_program.to_account_info(),
+            system_program: self.system_program.to_account_info(),
+        };
+        self.earn.deposit(&user, amount, signer_seeds)?;
+
+        self.f_token_vault.reload()?;
+        let shares = self.f_token_vault.amount;
+        transfer_signed(
+            self.token_program.to_account_info(),
+            self.f_token_vault.to_account_info(),
+            &self.f_token_mint,
+            self.owner_f_token_account.to_account_info(),
+            delegation.to_account_info(),
+            shares,
+            signer_seeds,
+        )?;
+
+        emit!(OperatorEarnEvent {
+            delegation: delegation.key(),
+            owner: delegation.owner,
+            operator: self.operator.key(),
+            permission: OperatorDelegation::DEPOSIT_EARN,
+            assets: amount,
+            shares,
+            period_spent: delegation.period_spent,
+        });
+        Ok(())
+    }
+}
@@ -0,0 +1,125 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::constant::CONFIG_SEED;
+use crate::error::InteractDappError;
+use crate::events::OperatorEarnEvent;
+use crate::instructions::{EarnAccounts, EarnUser};
+use crate::math::preview_withdraw;
+use crate::states::{Config, Lending, OperatorDelegation};
+use super::{check_delegation_earn_accounts, transfer_signed};
+
+/// `withdraw_earn` signed by an operator: the owner fTokens needed for `assets` are
+/// withdrawn through the delegation and the underlying is sent back to the owner.
+#[derive(Accounts)]
+pub struct OperatorWithdrawEarn<'info> {
+    pub operator: Signer<'info>,
+
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Box<Account<'info, Config>>,
+
+    #[account(mut, has_one = operator, has_one = mint, has_one = f_token_mint)]
+    pub delegation: Box<Account<'info, OperatorDelegation>>,
+
+    #[account(mint::token_program = token_program)]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(mint::token_program = token_program)]
+    pub f_token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(mut, token::mint = mint, token::authority = delegation.owner)]
+    pub owner_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    /// fToken account of the owner, with the delegation approved as SPL delegate
+    #[account(mut, token::mint = f_token_mint, token::authority = delegation.owner)]
+    pub owner_f_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut, token::mint = mint, token::authority = delegation)]
+    pub token_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(mut, token::mint = f_token_mint, token::authority = delegation)]
+    pub f_token_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub earn: EarnAccounts<'info>,
+    ///CHECK: Earn claim account of the delegation
+    #[account(mut)]
+    pub claim_account: UncheckedAccount<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> OperatorWithdrawEarn<'info> {
+    pub fn operator_withdraw_earn(&mut self, assets: u64) -> Result<()> {
+        require!(!self.config.paused, InteractDappError::ProgramPaused);
+        require!(assets > 0, InteractDappError::ZeroAmount);
+        check_delegation_earn_accounts(&self.delegation, &self.earn)?;
+        let now = Clock::get()?.unix_timestamp;
+        self.delegation
+            .consume(OperatorDelegation::WITHDRAW_EARN, assets, now)?;
+
+        // The exchange price only grows when the lending market updates, so shares quoted
+        // at the stored price cover the withdrawal. The excess is returned below.
+        let lending = Lending::load(&self.earn.lending, self.earn.lending_program.key)?;
+        let shares = preview_withdraw(assets, lending.token_exchange_price)
+            .ok_or(InteractDappError::MathOverflow)?;
+
+        let delegation = &self.delegation;
+        let seeds = delegation.signer_seeds();
+        let signer_seeds: &[&[&[u8]]] = &[&seeds];
+
+        transfer_signed(
+            self.token_program.to_account_info(),
+            self.owner_f_token_account.to_account_info(),
+            &self.f_token_mint,
+            self.f_token_vault.to_account_info(),
+            delegation.to_account_info(),
+            shares,
+            signer_seeds,
+        )?;
+        let user = EarnUser {
+            signer: delegation.to_account_info(),
+            token_account: self.token_vault.to_account_info(),
+            f_token_account: self.f_token_vault.to_account_info(),
+            mint: self.mint.to_account_info(),
+            token_program: self.token_program.to_account_info(),
+            associated_token_program: self.associated_token_program.to_account_info(),
+            system_program: self.system_program.to_account_info(),
+        };
+        self.earn
+            .withdraw(&user, &self.claim_account, assets, signer_seeds)?;
+
+        self.token_vault.reload()?;
+        self.f_token_vault.reload()?;
+        let shares_burned = shares.saturating_sub(self.f_token_vault.amount);
+        transfer_signed(
+            self.token_program.to_account_info(),
+            self.token_vault.to_account_info(),
+            &self.mint,
+            self.owner_token_account.to_account_info(),
+            delegation.to_account_info(),
+            self.token_vault.amount,
+            signer_seeds,
+        )?;
+        if self.f_token_vault.amount > 0 {
+            transfer_signed(
+                self.token_program.to_account_info(),
+                self.f_token_vault.to_account_info(),
+                &self.f_token_mint,
+                self.owner_f_token_account.to_account_info(),
+                delegation.to_account_info(),
+                self.f_token_vault.amount,
+                signer_seeds,
+            )?;
+        }
+
+        emit!(OperatorEarnEvent {
+            delegation: delegation.key(),
+            owner: delegation.owner,
+            operator: self.operator.key(),
+            permission: OperatorDelegation::WITHDRAW_EARN,
+            assets,
+            shares: shares_burned,
+            period_spent: delegation.period_spent,
+        });
+        Ok(())
+    }
+}
@@ -0,0 +1,59 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::states::OperatorDelegation;
+use crate::utils::drain_token_account;
+
+/// Closes an operator delegation. SPL delegate approvals granted to it are left to the
+/// owner to revoke.
+#[derive(Accounts)]
+pub struct RevokeOperatorDelegation<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(mut, close = owner, has_one = owner, has_one = mint, has_one = f_token_mint)]
+    pub delegation: Box<Account<'info, OperatorDelegation>>,
+
+    #[account(mint::token_program = token_program)]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(mint::token_program = token_program)]
+    pub f_token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(mut, token::mint = mint, token::authority = delegation)]
+    pub token_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(mut, token::mint = f_token_mint, token::authority = delegation)]
+    pub f_token_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut, token::mint = mint)]
+    pub owner_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(mut, token::mint = f_token_mint)]
+    pub owner_f_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+impl<'info> RevokeOperatorDelegation<'info> {
+    pub fn revoke_operator_delegation(&mut self) -> Result<()> {
+        let delegation = &self.delegation;
+        let seeds = delegation.signer_seeds();
+        let signer_seeds: &[&[&[u8]]] = &[&seeds];
+
+        drain_token_account(
+            self.token_program.to_account_info(),
+            &self.token_vault,
+            &self.mint,
+            self.owner_token_account.to_account_info(),
+            delegation.to_account_info(),
+            self.owner.to_account_info(),
+            signer_seeds,
+        )?;
+        drain_token_account(
+            self.token_program.to_account_info(),
+            &self.f_token_vault,
+            &self.f_token_mint,
+            self.owner_f_token_account.to_account_info(),
+            delegation.to_account_info(),
+            self.owner.to_account_info(),
+            signer_seeds,
+        )
+    }
+}
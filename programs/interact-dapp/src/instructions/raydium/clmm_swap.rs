@@ -0,0 +1,160 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{instruction::Instruction, program::invoke_signed};
+use anchor_lang::Discriminator;
+use anchor_spl::memo::Memo;
+use anchor_spl::token::Token;
+use anchor_spl::token_interface::{Mint, Token2022, TokenAccount};
+use raydium_amm_v3::{
+    cpi,
+    program::AmmV3,
+    states::{AmmConfig, ObservationState, PoolState},
+};
+
+/// `swap_v2` data: discriminator, amount, other amount threshold, sqrt price limit and
+/// is base input
+const SWAP_V2_DATA_LEN: usize = 8 + 8 + 8 + 16 + 1;
+
+/// Invokes `swap_v2` without the copies of `cpi::swap_v2`. The data is built on the stack,
+/// the account infos of `accounts` are moved into the list given to the runtime and the
+/// remaining accounts, bitmap extension and tick arrays, are cloned once instead of twice.
+#[allow(clippy::too_many_arguments)]
+pub fn invoke_swap_v2<'info>(
+    clmm_program: &AccountInfo<'info>,
+    accounts: cpi::accounts::SwapSingleV2<'info>,
+    remaining_accounts: &[AccountInfo<'info>],
+    amount: u64,
+    other_amount_threshold: u64,
+    sqrt_price_limit_x64: u128,
+    is_base_input: bool,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let mut data = [0u8; SWAP_V2_DATA_LEN];
+    data[..8].copy_from_slice(raydium_amm_v3::instruction::SwapV2::DISCRIMINATOR);
+    data[8..16].copy_from_slice(&amount.to_le_bytes());
+    data[16..24].copy_from_slice(&other_amount_threshold.to_le_bytes());
+    data[24..40].copy_from_slice(&sqrt_price_limit_x64.to_le_bytes());
+    data[40] = is_base_input as u8;
+
+    let mut account_metas = accounts.to_account_metas(None);
+    account_metas.extend(remaining_accounts.iter().map(|account| AccountMeta {
+        pubkey: *account.key,
+        is_signer: account.is_signer,
+        is_writable: account.is_writable,
+    }));
+    let cpi::accounts::SwapSingleV2 {
+        payer,
+        amm_config,
+        pool_state,
+        input_token_account,
+        output_token_account,
+        input_vault,
+        output_vault,
+        observation_state,
+        token_program,
+        token_program_2022,
+        memo_program,
+        input_vault_mint,
+        output_vault_mint,
+    } = accounts;
+    let mut account_infos = Vec::with_capacity(account_metas.len());
+    account_infos.extend([
+        payer,
+        amm_config,
+        pool_state,
+        input_token_account,
+        output_token_account,
+        input_vault,
+        output_vault,
+        observation_state,
+        token_program,
+        token_program_2022,
+        memo_program,
+        input_vault_mint,
+        output_vault_mint,
+    ]);
+    account_infos.extend_from_slice(remaining_accounts);
+
+    let instruction = Instruction::new_with_bytes(*clmm_program.key, &data, account_metas);
+    invoke_signed(&instruction, &account_infos, signer_seeds).map_err(Into::into)
+}
+
+/// Pool accounts of a `swap_v2` made on behalf of a program-owned account.
+#[derive(Accounts)]
+pub struct ClmmSwapAccounts<'info> {
+    pub clmm_program: Program<'info, AmmV3>,
+
+    #[account(address = pool_state.load()?.amm_config)]
+    pub amm_config: Box<Account<'info, AmmConfig>>,
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+    #[account(mut, address = pool_state.load()?.observation_key)]
+    pub observation_state: AccountLoader<'info, ObservationState>,
+
+    /// The pool vault of the input token
+    #[account(mut)]
+    pub input_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+    /// The pool vault of the output token
+    #[account(mut)]
+    pub output_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(address = input_vault.mint)]
+    pub input_vault_mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(address = output_vault.mint)]
+    pub output_vault_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    pub token_program: Program<'info, Token>,
+    pub token_program_2022: Program<'info, Token2022>,
+    pub memo_program: Program<'info, Memo>,
+}
+
+impl<'info> ClmmSwapAccounts<'info> {
+    /// The token program owning `token_account`.
+    pub fn token_program_of(&self, token_account: &AccountInfo<'info>) -> AccountInfo<'info> {
+        if *token_account.owner == self.token_program_2022.key() {
+            self.token_program_2022.to_account_info()
+        } else {
+            self.token_program.to_account_info()
+        }
+    }
+
+    /// Swaps from `input_token_account` to `output_token_account`, both owned by `payer`.
+    /// `remaining_accounts` are the bitmap extension and tick arrays expected by `swap_v2`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn swap(
+        &self,
+        payer: AccountInfo<'info>,
+        input_token_account: AccountInfo<'info>,
+        output_token_account: AccountInfo<'info>,
+        remaining_accounts: &[AccountInfo<'info>],
+        amount: u64,
+        other_amount_threshold: u64,
+        sqrt_price_limit_x64: u128,
+        is_base_input: bool,
+        signer_seeds: &[&[&[u8]]],
+    ) -> Result<()> {
+        let cpi_accounts = cpi::accounts::SwapSingleV2 {
+            payer,
+            amm_config: self.amm_config.to_account_info(),
+            pool_state: self.pool_state.to_account_info(),
+            input_token_account,
+            output_token_account,
+            input_vault: self.input_vault.to_account_info(),
+            output_vault: self.output_vault.to_account_info(),
+            observation_state: self.observation_state.to_account_info(),
+            token_program: self.token_program.to_account_info(),
+            token_program_2022: self.token_program_2022.to_account_info(),
+            memo_program: self.memo_program.to_account_info(),
+            input_vault_mint: self.input_vault_mint.to_account_info(),
+            output_vault_mint: self.output_vault_mint.to_account_info(),
+        };
+        invoke_swap_v2(
+            &self.clmm_program,
+            cpi_accounts,
+            remaining_accounts,
+            amount,
+            other_amount_threshold,
+            sqrt_price_limit_x64,
+            is_base_input,
+            signer_seeds,
+        )
+    }
+}
@@ -1,3 +1,5 @@
+pub mod clmm_swap;
 pub mod swap;
 
+pub use clmm_swap::*;
 pub use swap::*;
\ No newline at end of file
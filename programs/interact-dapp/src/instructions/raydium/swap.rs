@@ -7,6 +7,11 @@ use raydium_amm_v3::{
     program::AmmV3,
     states::{AmmConfig, ObservationState, PoolState},
 };
+use crate::constant::CONFIG_SEED;
+use crate::error::InteractDappError;
+use crate::events::ProxySwapEvent;
+use crate::states::Config;
+use super::invoke_swap_v2;
 
 /// Memo msg for swap
 pub const SWAP_MEMO_MSG: &'static [u8] = b"raydium_swap";
@@ -16,6 +21,9 @@ pub struct ProxySwap<'info> {
     /// The user performing the swap
     pub payer: Signer<'info>,
 
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Box<Account<'info, Config>>,
+
     /// The factory state to read protocol fees
     #[account(address = pool_state.load()?.amm_config)]
     pub amm_config: Box<Account<'info, AmmConfig>>,
@@ -78,6 +86,7 @@ pub fn proxy_swap<'a, 'b, 'c: 'info, 'info>(
     sqrt_price_limit_x64: u128,
     is_base_input: bool,
 ) -> Result<()> {
+    require!(!ctx.accounts.config.paused, InteractDappError::ProgramPaused);
     let cpi_accounts = cpi::accounts::SwapSingleV2 {
         payer: ctx.accounts.payer.to_account_info(),
         amm_config: ctx.accounts.amm_config.to_account_info(),
@@ -93,13 +102,33 @@ pub fn proxy_swap<'a, 'b, 'c: 'info, 'info>(
         input_vault_mint: ctx.accounts.input_vault_mint.to_account_info(),
         output_vault_mint: ctx.accounts.output_vault_mint.to_account_info(),
     };
-    let cpi_context = CpiContext::new(ctx.accounts.clmm_program.to_account_info(), cpi_accounts)
-        .with_remaining_accounts(ctx.remaining_accounts.to_vec());
-    cpi::swap_v2(
-        cpi_context,
+    let input_balance_before = ctx.accounts.input_token_account.amount;
+    let output_balance_before = ctx.accounts.output_token_account.amount;
+    invoke_swap_v2(
+        &ctx.accounts.clmm_program,
+        cpi_accounts,
+        ctx.remaining_accounts,
         amount,
         other_amount_threshold,
         sqrt_price_limit_x64,
         is_base_input,
-    )
+        &[],
+    )?;
+
+    ctx.accounts.input_token_account.reload()?;
+    ctx.accounts.output_token_account.reload()?;
+    emit!(ProxySwapEvent {
+        payer: ctx.accounts.payer.key(),
+        pool_state: ctx.accounts.pool_state.key(),
+        input_mint: ctx.accounts.input_vault_mint.key(),
+        output_mint: ctx.accounts.output_vault_mint.key(),
+        amount_in: input_balance_before.saturating_sub(ctx.accounts.input_token_account.amount),
+        amount_out: ctx
+            .accounts
+            .output_token_account
+            .amount
+            .saturating_sub(output_balance_before),
+        is_base_input,
+    });
+    Ok(())
 }
\ No newline at end of file
@@ -0,0 +1,326 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::memo::Memo;
+use anchor_spl::token::Token;
+use anchor_spl::token_interface::{Mint, Token2022, TokenAccount};
+use raydium_amm_v3::{
+    cpi,
+    libraries::tick_math,
+    program::AmmV3,
+    states::{AmmConfig, ObservationState, PersonalPositionState, PoolState},
+};
+use crate::constant::{CONFIG_SEED, DEPOSIT_CAPS_SEED};
+use crate::error::InteractDappError;
+use crate::events::CompoundStepEvent;
+use crate::instructions::{invoke_swap_v2, EarnAccounts, EarnUser};
+use crate::math::{apply_slippage, pair_to_base};
+use crate::states::{CompoundStep, Config, DepositCaps, Strategy};
+use super::{check_earn_accounts, check_pool_vaults, get_checked_twap_tick, ordered};
+
+/// Permissionless crank running one step of the strategy compound cycle:
+/// harvest CLMM fees, swap the pair token to the base token, deposit idle base into Earn.
+#[derive(Accounts)]
+pub struct CrankCompound<'info> {
+    pub clmm_program: Program<'info, AmmV3>,
+
+    pub cranker: Signer<'info>,
+
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Box<Account<'info, Config>>,
+
+    #[account(
+        mut,
+        has_one = base_mint,
+        has_one = pair_mint,
+        has_one = base_vault,
+        has_one = pair_vault,
+        has_one = f_token_vault,
+        has_one = pool_state,
+    )]
+    pub strategy: Box<Account<'info, Strategy>>,
+
+    pub base_mint: Box<InterfaceAccount<'info, Mint>>,
+    pub pair_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(mut)]
+    pub base_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(mut)]
+    pub pair_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(mut)]
+    pub f_token_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(address = pool_state.load()?.amm_config)]
+    pub amm_config: Box<Account<'info, AmmConfig>>,
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+    #[account(mut, address = pool_state.load()?.observation_key)]
+    pub observation_state: AccountLoader<'info, ObservationState>,
+    /// Pool vault of the base token
+    #[account(mut)]
+    pub pool_base_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+    /// Pool vault of the pair token
+    #[account(mut)]
+    pub pool_pair_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    // Strategy position, required by the harvest step once a position is open
+    ///CHECK: deserialized and checked in the handler, the CLMM program writes to it
+    #[account(mut)]
+    pub personal_position: Option<UncheckedAccount<'info>>,
+    ///CHECK: checked by the CLMM program
+    pub position_nft_account: Option<UncheckedAccount<'info>>,
+    ///CHECK: checked by the CLMM program
+    #[account(mut)]
+    pub protocol_position: Option<UncheckedAccount<'info>>,
+    ///CHECK: checked by the CLMM program
+    #[account(mut)]
+    pub tick_array_lower: Option<UncheckedAccount<'info>>,
+    ///CHECK: checked by the CLMM program
+    #[account(mut)]
+    pub tick_array_upper: Option<UncheckedAccount<'info>>,
+
+    pub earn: EarnAccounts<'info>,
+    /// Risk limits of the base mint, the deposit step moves their ledger to Earn shares
+    #[account(
+        mut,
+        seeds = [DEPOSIT_CAPS_SEED, base_mint.key().as_ref()],
+        bump = deposit_caps.bump,
+    )]
+    pub deposit_caps: Box<Account<'info, DepositCaps>>,
+
+    pub token_program: Program<'info, Token>,
+    pub token_program_2022: Program<'info, Token2022>,
+    pub memo_program: Program<'info, Memo>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+    // remaining accounts
+    // harvest: reward token accounts expected by `decrease_liquidity_v2`
+    // swap: tickarray_bitmap_extension if needed, then the tick arrays crossed by the swap
+}
+
+pub fn crank_compound<'a, 'b, 'c: 'info, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, CrankCompound<'info>>,
+) -> Result<()> {
+    require!(!ctx.accounts.config.paused, InteractDappError::ProgramPaused);
+    let step = ctx.accounts.strategy.next_step;
+    let (base_amount, pair_amount) = match step {
+        CompoundStep::Harvest => ctx.accounts.harvest(ctx.remaining_accounts)?,
+        CompoundStep::Swap => ctx.accounts.swap(ctx.remaining_accounts)?,
+        CompoundStep::Deposit => ctx.accounts.deposit()?,
+    };
+    ctx.accounts.strategy.next_step = step.next();
+
+    emit!(CompoundStepEvent {
+        strategy: ctx.accounts.strategy.key(),
+        cranker: ctx.accounts.cranker.key(),
+        step,
+        base_amount,
+        pair_amount,
+    });
+    Ok(())
+}
+
+impl<'info> CrankCompound<'info> {
+    fn pool_vaults_ordered(&self) -> (AccountInfo<'info>, AccountInfo<'info>) {
+        ordered(
+            self.strategy.base_is_token_0(),
+            self.pool_base_vault.to_account_info(),
+            self.pool_pair_vault.to_account_info(),
+        )
+    }
+
+    /// Collects the position fees into the strategy vaults, returns the (base, pair) collected.
+    fn harvest(&mut self, remaining_accounts: &[AccountInfo<'info>]) -> Result<(u64, u64)> {
+        let now = Clock::get()?.unix_timestamp;
+        let strategy = &self.strategy;
+        require!(
+            now >= strategy.last_compound_timestamp.saturating_add(strategy.compound_interval),
+            InteractDappError::CompoundTooEarly
+        );
+        self.strategy.last_compound_timestamp = now;
+        if !self.strategy.has_position() {
+            return Ok((0, 0));
+        }
+
+        let (
+            Some(personal_position),
+            Some(position_nft_account),
+            Some(protocol_position),
+            Some(tick_array_lower),
+            Some(tick_array_upper),
+        ) = (
+            &self.personal_position,
+            &self.position_nft_account,
+            &self.protocol_position,
+            &self.tick_array_lower,
+            &self.tick_array_upper,
+        )
+        else {
+            return Err(InteractDappError::StrategyPositionMissing.into());
+        };
+        require_keys_eq!(
+            *personal_position.owner,
+            self.clmm_program.key(),
+            InteractDappError::InvalidAccountOwner
+        );
+        let position_nft_mint = {
+            let data = personal_position.try_borrow_data()?;
+            PersonalPositionState::try_deserialize(&mut &data[..])?.nft_mint
+        };
+        require_keys_eq!(
+            position_nft_mint,
+            self.strategy.position_nft_mint,
+            InteractDappError::StrategyPositionMissing
+        );
+        check_pool_vaults(
+            &self.strategy,
+            &*self.pool_state.load()?,
+            &self.pool_base_vault.key(),
+            &self.pool_pair_vault.key(),
+        )?;
+
+        let base_is_token_0 = self.strategy.base_is_token_0();
+        let (token_vault_0, token_vault_1) = self.pool_vaults_ordered();
+        let (recipient_token_account_0, recipient_token_account_1) = ordered(
+            base_is_token_0,
+            self.base_vault.to_account_info(),
+            self.pair_vault.to_account_info(),
+        );
+        let (vault_0_mint, vault_1_mint) = ordered(
+            base_is_token_0,
+            self.base_mint.to_account_info(),
+            self.pair_mint.to_account_info(),
+        );
+        let cpi_accounts = cpi::accounts::DecreaseLiquidityV2 {
+            nft_owner: self.strategy.to_account_info(),
+            nft_account: position_nft_account.to_account_info(),
+            personal_position: personal_position.to_account_info(),
+            pool_state: self.pool_state.to_account_info(),
+            protocol_position: protocol_position.to_account_info(),
+            token_vault_0,
+            token_vault_1,
+            tick_array_lower: tick_array_lower.to_account_info(),
+            tick_array_upper: tick_array_upper.to_account_info(),
+            recipient_token_account_0,
+            recipient_token_account_1,
+            token_program: self.token_program.to_account_info(),
+            token_program_2022: self.token_program_2022.to_account_info(),
+            memo_program: self.memo_program.to_account_info(),
+            vault_0_mint,
+            vault_1_mint,
+        };
+        let seeds = self.strategy.signer_seeds();
+        let signer_seeds: &[&[&[u8]]] = &[&seeds];
+        let cpi_context = CpiContext::new_with_signer(
+            self.clmm_program.to_account_info(),
+            cpi_accounts,
+            signer_seeds,
+        )
+        .with_remaining_accounts(remaining_accounts.to_vec());
+
+        let base_before = self.base_vault.amount;
+        let pair_before = self.pair_vault.amount;
+        // Removing zero liquidity only collects the fees owed to the position
+        cpi::decrease_liquidity_v2(cpi_context, 0, 0, 0)?;
+
+        self.base_vault.reload()?;
+        self.pair_vault.reload()?;
+        Ok((
+            self.base_vault.amount.saturating_sub(base_before),
+            self.pair_vault.amount.saturating_sub(pair_before),
+        ))
+    }
+
+    /// Swaps the whole pair balance into the base token, returns the (base out, pair in).
+    fn swap(&mut self, remaining_accounts: &[AccountInfo<'info>]) -> Result<(u64, u64)> {
+        let amount_in = self.pair_vault.amount;
+        if amount_in == 0 {
+            return Ok((0, 0));
+        }
+
+        let base_is_token_0 = self.strategy.base_is_token_0();
+        let expected_out = {
+            let pool_state = self.pool_state.load()?;
+            check_pool_vaults(
+                &self.strategy,
+                &pool_state,
+                &self.pool_base_vault.key(),
+                &self.pool_pair_vault.key(),
+            )?;
+            // Priced at the TWAP, the spot price is what a sandwich around the crank moves
+            let twap_tick = get_checked_twap_tick(
+                &self.strategy,
+                &pool_state,
+                &*self.observation_state.load()?,
+            )?;
+            let twap_sqrt_price_x64 = tick_math::get_sqrt_price_at_tick(twap_tick)?;
+            pair_to_base(amount_in, twap_sqrt_price_x64, base_is_token_0)
+                .ok_or(InteractDappError::MathOverflow)?
+        };
+        let minimum_amount_out = apply_slippage(expected_out, self.strategy.max_slippage_bps);
+
+        let cpi_accounts = cpi::accounts::SwapSingleV2 {
+            payer: self.strategy.to_account_info(),
+            amm_config: self.amm_config.to_account_info(),
+            pool_state: self.pool_state.to_account_info(),
+            input_token_account: self.pair_vault.to_account_info(),
+            output_token_account: self.base_vault.to_account_info(),
+            input_vault: self.pool_pair_vault.to_account_info(),
+            output_vault: self.pool_base_vault.to_account_info(),
+            observation_state: self.observation_state.to_account_info(),
+            token_program: self.token_program.to_account_info(),
+            token_program_2022: self.token_program_2022.to_account_info(),
+            memo_program: self.memo_program.to_account_info(),
+            input_vault_mint: self.pair_mint.to_account_info(),
+            output_vault_mint: self.base_mint.to_account_info(),
+        };
+        let seeds = self.strategy.signer_seeds();
+        let base_before = self.base_vault.amount;
+        invoke_swap_v2(
+            &self.clmm_program,
+            cpi_accounts,
+            remaining_accounts,
+            amount_in,
+            minimum_amount_out,
+            0,
+            true,
+            &[&seeds],
+        )?;
+
+        self.base_vault.reload()?;
+        self.pair_vault.reload()?;
+        Ok((
+            self.base_vault.amount.saturating_sub(base_before),
+            amount_in.saturating_sub(self.pair_vault.amount),
+        ))
+    }
+
+    /// Deposits the idle base balance into Jupiter Earn, returns the (base deposited, 0).
+    fn deposit(&mut self) -> Result<(u64, u64)> {
+        let amount = self.base_vault.amount;
+        if amount == 0 {
+            return Ok((0, 0));
+        }
+        check_earn_accounts(&self.strategy, &self.earn)?;
+
+        let user = EarnUser {
+            signer: self.strategy.to_account_info(),
+            token_account: self.base_vault.to_account_info(),
+            f_token_account: self.f_token_vault.to_account_info(),
+            mint: self.base_mint.to_account_info(),
+            token_program: self.token_program.to_account_info(),
+            associated_token_program: self.associated_token_program.to_account_info(),
+            system_program: self.system_program.to_account_info(),
+        };
+        let seeds = self.strategy.signer_seeds();
+        let shares_before = self.f_token_vault.amount;
+        self.earn.deposit(&user, amount, &[&seeds])?;
+
+        self.base_vault.reload()?;
+        self.f_token_vault.reload()?;
+        let deposited = amount.saturating_sub(self.base_vault.amount);
+        let ledger = &mut self.deposit_caps.ledger;
+        ledger.remove_assets(deposited);
+        ledger.add_shares(self.f_token_vault.amount.saturating_sub(shares_before))?;
+        Ok((deposited, 0))
+    }
+}
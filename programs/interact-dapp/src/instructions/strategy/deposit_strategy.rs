@@ -0,0 +1,173 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{self, Mint, MintTo, TokenAccount, TokenInterface, TransferChecked};
+use raydium_amm_v3::states::{ObservationState, PersonalPositionState, PoolState};
+use crate::constant::{CONFIG_SEED, DEPOSIT_CAPS_SEED, USER_DEPOSITS_SEED};
+use crate::error::InteractDappError;
+use crate::events::{DepositCapReachedEvent, StrategyDepositEvent};
+use crate::math::shares_for_deposit;
+use crate::programs::JUPITER_LENDING_PROGRAM_ID;
+use crate::states::{Config, DepositCaps, Lending, Strategy, UserDeposits};
+use super::{get_checked_twap_tick, StrategyValuation};
+
+#[derive(Accounts)]
+pub struct DepositStrategy<'info> {
+    #[account(mut)]
+    pub depositor: Signer<'info>,
+
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Box<Account<'info, Config>>,
+
+    #[account(
+        has_one = share_mint,
+        has_one = base_mint,
+        has_one = base_vault,
+        has_one = pair_vault,
+        has_one = f_token_vault,
+        has_one = pool_state,
+        has_one = lending,
+    )]
+    pub strategy: Box<Account<'info, Strategy>>,
+
+    #[account(mut)]
+    pub share_mint: Box<InterfaceAccount<'info, Mint>>,
+    pub base_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// Base token account the deposit is taken from
+    #[account(mut, token::mint = base_mint)]
+    pub depositor_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    /// Share token account the minted shares are sent to
+    #[account(mut, token::mint = share_mint)]
+    pub depositor_share_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub base_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+    pub pair_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+    pub f_token_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub pool_state: AccountLoader<'info, PoolState>,
+    #[account(address = pool_state.load()?.observation_key)]
+    pub observation_state: AccountLoader<'info, ObservationState>,
+    /// Required once the strategy has opened its CLMM position
+    pub personal_position: Option<Box<Account<'info, PersonalPositionState>>>,
+
+    ///CHECK: deserialized and checked in the handler
+    pub lending: AccountInfo<'info>,
+    ///CHECK:
+    #[account(address = JUPITER_LENDING_PROGRAM_ID @ InteractDappError::InvalidLendingProgram)]
+    pub lending_program: UncheckedAccount<'info>,
+
+    /// Risk limits of the base mint
+    #[account(
+        mut,
+        seeds = [DEPOSIT_CAPS_SEED, base_mint.key().as_ref()],
+        bump = deposit_caps.bump,
+        has_one = lending,
+    )]
+    pub deposit_caps: Box<Account<'info, DepositCaps>>,
+    #[account(
+        init_if_needed,
+        payer = depositor,
+        space = 8 + UserDeposits::INIT_SPACE,
+        seeds = [USER_DEPOSITS_SEED, base_mint.key().as_ref(), depositor.key().as_ref()],
+        bump,
+    )]
+    pub user_deposits: Box<Account<'info, UserDeposits>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> DepositStrategy<'info> {
+    pub fn deposit_strategy(
+        &mut self,
+        bumps: &DepositStrategyBumps,
+        requested: u64,
+    ) -> Result<()> {
+        require!(!self.config.paused, InteractDappError::ProgramPaused);
+        require!(requested > 0, InteractDappError::ZeroAmount);
+
+        let lending = Lending::load(&self.lending, self.lending_program.key)?;
+        let total_value = {
+            let twap_tick = get_checked_twap_tick(
+                &self.strategy,
+                &*self.pool_state.load()?,
+                &*self.observation_state.load()?,
+            )?;
+            StrategyValuation {
+                strategy: &self.strategy,
+                twap_tick,
+                base_vault: &self.base_vault,
+                pair_vault: &self.pair_vault,
+                f_token_vault: &self.f_token_vault,
+                lending: &lending,
+                position: self.personal_position.as_deref().map(|p| &**p),
+            }
+            .total_value()?
+        };
+
+        self.user_deposits
+            .initialize(self.base_mint.key(), self.depositor.key(), bumps.user_deposits);
+        let tvl = self.deposit_caps.ledger.get_value(lending.token_exchange_price)?;
+        let user_value = self
+            .user_deposits
+            .ledger
+            .get_value(lending.token_exchange_price)?;
+        let headroom = self.deposit_caps.get_deposit_headroom(tvl, user_value);
+        let amount = requested.min(headroom);
+        require!(amount > 0, InteractDappError::DepositCapExceeded);
+        if amount < requested {
+            emit!(DepositCapReachedEvent {
+                deposit_caps: self.deposit_caps.key(),
+                vault: self.strategy.key(),
+                depositor: self.depositor.key(),
+                requested,
+                accepted: amount,
+                tvl,
+                user_value,
+            });
+        }
+
+        let shares = shares_for_deposit(amount, self.share_mint.supply, total_value)
+            .ok_or(InteractDappError::MathOverflow)?;
+        require!(shares > 0, InteractDappError::ZeroAmount);
+
+        token_interface::transfer_checked(
+            CpiContext::new(
+                self.token_program.to_account_info(),
+                TransferChecked {
+                    from: self.depositor_token_account.to_account_info(),
+                    mint: self.base_mint.to_account_info(),
+                    to: self.base_vault.to_account_info(),
+                    authority: self.depositor.to_account_info(),
+                },
+            ),
+            amount,
+            self.base_mint.decimals,
+        )?;
+        self.deposit_caps.ledger.add_assets(amount)?;
+        self.user_deposits.ledger.add_assets(amount)?;
+
+        let seeds = self.strategy.signer_seeds();
+        token_interface::mint_to(
+            CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                MintTo {
+                    mint: self.share_mint.to_account_info(),
+                    to: self.depositor_share_account.to_account_info(),
+                    authority: self.strategy.to_account_info(),
+                },
+                &[&seeds],
+            ),
+            shares,
+        )?;
+
+        emit!(StrategyDepositEvent {
+            strategy: self.strategy.key(),
+            depositor: self.depositor.key(),
+            assets: amount,
+            shares,
+            total_value,
+        });
+        Ok(())
+    }
+}
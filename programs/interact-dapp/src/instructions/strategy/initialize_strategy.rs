@@ -0,0 +1,147 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use raydium_amm_v3::states::PoolState;
+use crate::constant::{
+    CONFIG_SEED, DEFAULT_STRATEGY_MAX_TICK_DEVIATION, DEFAULT_STRATEGY_TWAP_WINDOW,
+    MAX_STRATEGY_SLIPPAGE_BPS, STRATEGY_SEED, STRATEGY_SHARE_MINT_SEED,
+};
+use crate::error::InteractDappError;
+use crate::programs::JUPITER_LENDING_PROGRAM_ID;
+use crate::states::{CompoundStep, Config, Lending, Strategy};
+
+/// Creates the strategy of a pool and base token. The admin becomes its authority.
+#[derive(Accounts)]
+pub struct InitializeStrategy<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(seeds = [CONFIG_SEED], bump = config.bump, has_one = admin)]
+    pub config: Box<Account<'info, Config>>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + Strategy::INIT_SPACE,
+        seeds = [STRATEGY_SEED, pool_state.key().as_ref(), base_mint.key().as_ref()],
+        bump,
+    )]
+    pub strategy: Box<Account<'info, Strategy>>,
+
+    /// Strategy shares, minted to depositors
+    #[account(
+        init,
+        payer = admin,
+        seeds = [STRATEGY_SHARE_MINT_SEED, strategy.key().as_ref()],
+        bump,
+        mint::decimals = base_mint.decimals,
+        mint::authority = strategy,
+        mint::token_program = token_program,
+    )]
+    pub share_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The Raydium CLMM pool the strategy provides liquidity to
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    #[account(mint::token_program = token_program)]
+    pub base_mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(mint::token_program = pair_token_program)]
+    pub pair_mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(mint::token_program = token_program)]
+    pub f_token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        init,
+        payer = admin,
+        associated_token::mint = base_mint,
+        associated_token::authority = strategy,
+        associated_token::token_program = token_program,
+    )]
+    pub base_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        init,
+        payer = admin,
+        associated_token::mint = pair_mint,
+        associated_token::authority = strategy,
+        associated_token::token_program = pair_token_program,
+    )]
+    pub pair_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        init,
+        payer = admin,
+        associated_token::mint = f_token_mint,
+        associated_token::authority = strategy,
+        associated_token::token_program = token_program,
+    )]
+    pub f_token_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Jupiter Earn lending market of the base token
+    ///CHECK: deserialized and checked in the handler
+    pub lending: AccountInfo<'info>,
+    ///CHECK:
+    #[account(address = JUPITER_LENDING_PROGRAM_ID @ InteractDappError::InvalidLendingProgram)]
+    pub lending_program: UncheckedAccount<'info>,
+
+    /// Token program of the base, share and fToken mints
+    pub token_program: Interface<'info, TokenInterface>,
+    pub pair_token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> InitializeStrategy<'info> {
+    pub fn initialize_strategy(
+        &mut self,
+        bumps: &InitializeStrategyBumps,
+        compound_interval: i64,
+        max_slippage_bps: u16,
+    ) -> Result<()> {
+        require!(
+            compound_interval >= 0 && max_slippage_bps <= MAX_STRATEGY_SLIPPAGE_BPS,
+            InteractDappError::InvalidStrategyConfig
+        );
+
+        let pool_state = self.pool_state.load()?;
+        let base_mint = self.base_mint.key();
+        let pair_mint = self.pair_mint.key();
+        let pool_mints = (pool_state.token_mint_0, pool_state.token_mint_1);
+        require!(
+            pool_mints == (base_mint, pair_mint) || pool_mints == (pair_mint, base_mint),
+            InteractDappError::InvalidStrategyConfig
+        );
+
+        let lending = Lending::load(&self.lending, self.lending_program.key)?;
+        require_keys_eq!(lending.mint, base_mint, InteractDappError::InvalidStrategyConfig);
+        require_keys_eq!(
+            lending.f_token_mint,
+            self.f_token_mint.key(),
+            InteractDappError::InvalidStrategyConfig
+        );
+
+        self.strategy.set_inner(Strategy {
+            authority: self.admin.key(),
+            base_mint,
+            pair_mint,
+            share_mint: self.share_mint.key(),
+            base_vault: self.base_vault.key(),
+            pair_vault: self.pair_vault.key(),
+            f_token_vault: self.f_token_vault.key(),
+            pool_state: self.pool_state.key(),
+            lending: self.lending.key(),
+            f_token_mint: self.f_token_mint.key(),
+            position_nft_mint: Pubkey::default(),
+            tick_lower_index: 0,
+            tick_upper_index: 0,
+            range_width: 0,
+            twap_window: DEFAULT_STRATEGY_TWAP_WINDOW,
+            max_tick_deviation: DEFAULT_STRATEGY_MAX_TICK_DEVIATION,
+            max_slippage_bps,
+            compound_interval,
+            last_compound_timestamp: 0,
+            next_step: CompoundStep::Harvest,
+            bump: bumps.strategy,
+            share_mint_bump: bumps.share_mint,
+        });
+        Ok(())
+    }
+}
@@ -0,0 +1,127 @@
+pub mod crank_compound;
+pub mod deposit_strategy;
+pub mod initialize_strategy;
+pub mod open_strategy_position;
+pub mod rebalance_range;
+pub mod set_rebalance_config;
+pub mod withdraw_strategy;
+
+pub use crank_compound::*;
+pub use deposit_strategy::*;
+pub use initialize_strategy::*;
+pub use open_strategy_position::*;
+pub use rebalance_range::*;
+pub use set_rebalance_config::*;
+pub use withdraw_strategy::*;
+
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::TokenAccount;
+use raydium_amm_v3::libraries::tick_math;
+use raydium_amm_v3::states::{ObservationState, PersonalPositionState, PoolState};
+use crate::error::InteractDappError;
+use crate::instructions::EarnAccounts;
+use crate::math::{
+    get_position_token_amounts, get_strategy_total_value, get_twap_tick, StrategyHoldings,
+};
+use crate::states::{Lending, Strategy};
+
+/// Returns `(token_0, token_1)` for a `(base, pair)` couple of the strategy pool.
+pub(crate) fn ordered<T>(base_is_token_0: bool, base: T, pair: T) -> (T, T) {
+    if base_is_token_0 {
+        (base, pair)
+    } else {
+        (pair, base)
+    }
+}
+
+/// Checks that the given pool vaults hold the strategy base and pair tokens.
+pub(crate) fn check_pool_vaults(
+    strategy: &Strategy,
+    pool_state: &PoolState,
+    pool_base_vault: &Pubkey,
+    pool_pair_vault: &Pubkey,
+) -> Result<()> {
+    let (vault_0, vault_1) =
+        ordered(strategy.base_is_token_0(), pool_base_vault, pool_pair_vault);
+    require_keys_eq!(*vault_0, pool_state.token_vault_0, InteractDappError::InvalidPoolVault);
+    require_keys_eq!(*vault_1, pool_state.token_vault_1, InteractDappError::InvalidPoolVault);
+    Ok(())
+}
+
+/// Checks that the Earn accounts target the strategy lending market.
+pub(crate) fn check_earn_accounts(strategy: &Strategy, earn: &EarnAccounts) -> Result<()> {
+    require_keys_eq!(earn.lending.key(), strategy.lending, InteractDappError::InvalidStrategyConfig);
+    require_keys_eq!(
+        earn.f_token_mint.key(),
+        strategy.f_token_mint,
+        InteractDappError::InvalidStrategyConfig
+    );
+    require_keys_eq!(
+        earn.lending_program.key(),
+        *earn.lending.owner,
+        InteractDappError::InvalidAccountOwner
+    );
+    Ok(())
+}
+
+/// TWAP tick of the strategy pool over `strategy.twap_window`. Fails when the pool tick is
+/// more than `strategy.max_tick_deviation` away from it, as it is when the pool was just
+/// swapped to move the price the strategy is valued or rebalanced at.
+pub(crate) fn get_checked_twap_tick(
+    strategy: &Strategy,
+    pool_state: &PoolState,
+    observation_state: &ObservationState,
+) -> Result<i32> {
+    let now = Clock::get()?.unix_timestamp as u32;
+    let twap_tick = get_twap_tick(
+        observation_state,
+        pool_state.tick_current,
+        now,
+        strategy.twap_window,
+    )?;
+    require!(
+        pool_state.tick_current.abs_diff(twap_tick) <= strategy.max_tick_deviation,
+        InteractDappError::PriceDeviationTooHigh
+    );
+    Ok(twap_tick)
+}
+
+pub(crate) struct StrategyValuation<'a> {
+    pub strategy: &'a Strategy,
+    /// Tick the pair token and the CLMM position are priced at, from
+    /// [`get_checked_twap_tick`]
+    pub twap_tick: i32,
+    pub base_vault: &'a TokenAccount,
+    pub pair_vault: &'a TokenAccount,
+    pub f_token_vault: &'a TokenAccount,
+    pub lending: &'a Lending,
+    pub position: Option<&'a PersonalPositionState>,
+}
+
+impl StrategyValuation<'_> {
+    /// Value of everything the strategy holds, in base token units.
+    pub fn total_value(&self) -> Result<u64> {
+        let sqrt_price_x64 = tick_math::get_sqrt_price_at_tick(self.twap_tick)?;
+        let position_amounts = match (self.strategy.has_position(), self.position) {
+            (false, _) => (0, 0),
+            (true, Some(position)) => {
+                require_keys_eq!(
+                    position.nft_mint,
+                    self.strategy.position_nft_mint,
+                    InteractDappError::StrategyPositionMissing
+                );
+                get_position_token_amounts(position, self.twap_tick, sqrt_price_x64)?
+            }
+            (true, None) => return Err(InteractDappError::StrategyPositionMissing.into()),
+        };
+        let holdings = StrategyHoldings {
+            base_idle: self.base_vault.amount,
+            pair_idle: self.pair_vault.amount,
+            f_token_balance: self.f_token_vault.amount,
+            token_exchange_price: self.lending.token_exchange_price,
+            position_amounts,
+        };
+        get_strategy_total_value(&holdings, sqrt_price_x64, self.strategy.base_is_token_0())
+            .ok_or(InteractDappError::MathOverflow.into())
+    }
+}
@@ -0,0 +1,255 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token::Token;
+use anchor_spl::token_interface::{self, Mint, MintTo, Token2022, TokenAccount};
+use raydium_amm_v3::{
+    cpi,
+    libraries::tick_math,
+    program::AmmV3,
+    states::{ObservationState, PoolState},
+};
+use crate::constant::CONFIG_SEED;
+use crate::error::InteractDappError;
+use crate::events::StrategyDepositEvent;
+use crate::math::{pair_to_base, shares_for_deposit};
+use crate::programs::JUPITER_LENDING_PROGRAM_ID;
+use crate::states::{Config, Lending, Strategy};
+use super::{check_pool_vaults, get_checked_twap_tick, ordered, StrategyValuation};
+
+/// Opens the strategy CLMM position with tokens supplied by the authority, who receives
+/// shares for the value contributed. The position NFT is held by the strategy.
+#[derive(Accounts)]
+pub struct OpenStrategyPosition<'info> {
+    pub clmm_program: Program<'info, AmmV3>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Box<Account<'info, Config>>,
+
+    #[account(
+        mut,
+        has_one = authority,
+        has_one = share_mint,
+        has_one = base_mint,
+        has_one = pair_mint,
+        has_one = base_vault,
+        has_one = pair_vault,
+        has_one = f_token_vault,
+        has_one = pool_state,
+        has_one = lending,
+    )]
+    pub strategy: Box<Account<'info, Strategy>>,
+
+    #[account(mut)]
+    pub share_mint: Box<InterfaceAccount<'info, Mint>>,
+    pub base_mint: Box<InterfaceAccount<'info, Mint>>,
+    pub pair_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// Authority token accounts funding the position
+    #[account(mut, token::mint = base_mint, token::authority = authority)]
+    pub authority_base_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(mut, token::mint = pair_mint, token::authority = authority)]
+    pub authority_pair_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(mut, token::mint = share_mint)]
+    pub authority_share_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub base_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+    pub pair_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+    pub f_token_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+    #[account(address = pool_state.load()?.observation_key)]
+    pub observation_state: AccountLoader<'info, ObservationState>,
+    /// Pool vault of the base token
+    #[account(mut)]
+    pub pool_base_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+    /// Pool vault of the pair token
+    #[account(mut)]
+    pub pool_pair_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// New position NFT mint
+    #[account(mut)]
+    pub position_nft_mint: Signer<'info>,
+    ///CHECK: created by the CLMM program, ATA of the strategy
+    #[account(mut)]
+    pub position_nft_account: UncheckedAccount<'info>,
+    ///CHECK: created by the CLMM program
+    #[account(mut)]
+    pub metadata_account: UncheckedAccount<'info>,
+    ///CHECK: checked by the CLMM program
+    #[account(mut)]
+    pub protocol_position: UncheckedAccount<'info>,
+    ///CHECK: checked by the CLMM program
+    #[account(mut)]
+    pub tick_array_lower: UncheckedAccount<'info>,
+    ///CHECK: checked by the CLMM program
+    #[account(mut)]
+    pub tick_array_upper: UncheckedAccount<'info>,
+    ///CHECK: created by the CLMM program
+    #[account(mut)]
+    pub personal_position: UncheckedAccount<'info>,
+
+    ///CHECK: deserialized and checked in the handler
+    pub lending: AccountInfo<'info>,
+    ///CHECK:
+    #[account(address = JUPITER_LENDING_PROGRAM_ID @ InteractDappError::InvalidLendingProgram)]
+    pub lending_program: UncheckedAccount<'info>,
+
+    pub rent: Sysvar<'info, Rent>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+    pub token_program_2022: Program<'info, Token2022>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    ///CHECK: Metaplex token metadata program
+    pub metadata_program: UncheckedAccount<'info>,
+    // remaining accounts
+    // tickarray_bitmap_extension: must add account if the tick arrays are out of the default bitmap
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn open_strategy_position<'a, 'b, 'c: 'info, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, OpenStrategyPosition<'info>>,
+    tick_lower_index: i32,
+    tick_upper_index: i32,
+    tick_array_lower_start_index: i32,
+    tick_array_upper_start_index: i32,
+    liquidity: u128,
+    base_amount_max: u64,
+    pair_amount_max: u64,
+) -> Result<()> {
+    require!(!ctx.accounts.config.paused, InteractDappError::ProgramPaused);
+    let accounts = &ctx.accounts;
+    require!(!accounts.strategy.has_position(), InteractDappError::StrategyPositionExists);
+    require!(tick_lower_index < tick_upper_index, InteractDappError::InvalidStrategyConfig);
+
+    let base_is_token_0 = accounts.strategy.base_is_token_0();
+    let (total_value, twap_tick) = {
+        let pool_state = accounts.pool_state.load()?;
+        check_pool_vaults(
+            &accounts.strategy,
+            &pool_state,
+            &accounts.pool_base_vault.key(),
+            &accounts.pool_pair_vault.key(),
+        )?;
+        let twap_tick = get_checked_twap_tick(
+            &accounts.strategy,
+            &pool_state,
+            &*accounts.observation_state.load()?,
+        )?;
+        let lending = Lending::load(&accounts.lending, accounts.lending_program.key)?;
+        let total_value = StrategyValuation {
+            strategy: &accounts.strategy,
+            twap_tick,
+            base_vault: &accounts.base_vault,
+            pair_vault: &accounts.pair_vault,
+            f_token_vault: &accounts.f_token_vault,
+            lending: &lending,
+            position: None,
+        }
+        .total_value()?;
+        (total_value, twap_tick)
+    };
+
+    let (token_account_0, token_account_1) = ordered(
+        base_is_token_0,
+        accounts.authority_base_account.to_account_info(),
+        accounts.authority_pair_account.to_account_info(),
+    );
+    let (token_vault_0, token_vault_1) = ordered(
+        base_is_token_0,
+        accounts.pool_base_vault.to_account_info(),
+        accounts.pool_pair_vault.to_account_info(),
+    );
+    let (vault_0_mint, vault_1_mint) = ordered(
+        base_is_token_0,
+        accounts.base_mint.to_account_info(),
+        accounts.pair_mint.to_account_info(),
+    );
+    let (amount_0_max, amount_1_max) = ordered(base_is_token_0, base_amount_max, pair_amount_max);
+
+    let cpi_accounts = cpi::accounts::OpenPositionV2 {
+        payer: accounts.authority.to_account_info(),
+        position_nft_owner: accounts.strategy.to_account_info(),
+        position_nft_mint: accounts.position_nft_mint.to_account_info(),
+        position_nft_account: accounts.position_nft_account.to_account_info(),
+        metadata_account: accounts.metadata_account.to_account_info(),
+        pool_state: accounts.pool_state.to_account_info(),
+        protocol_position: accounts.protocol_position.to_account_info(),
+        tick_array_lower: accounts.tick_array_lower.to_account_info(),
+        tick_array_upper: accounts.tick_array_upper.to_account_info(),
+        personal_position: accounts.personal_position.to_account_info(),
+        token_account_0,
+        token_account_1,
+        token_vault_0,
+        token_vault_1,
+        rent: accounts.rent.to_account_info(),
+        system_program: accounts.system_program.to_account_info(),
+        token_program: accounts.token_program.to_account_info(),
+        associated_token_program: accounts.associated_token_program.to_account_info(),
+        metadata_program: accounts.metadata_program.to_account_info(),
+        token_program_2022: accounts.token_program_2022.to_account_info(),
+        vault_0_mint,
+        vault_1_mint,
+    };
+    let cpi_context = CpiContext::new(accounts.clmm_program.to_account_info(), cpi_accounts)
+        .with_remaining_accounts(ctx.remaining_accounts.to_vec());
+
+    let base_before = accounts.authority_base_account.amount;
+    let pair_before = accounts.authority_pair_account.amount;
+    cpi::open_position_v2(
+        cpi_context,
+        tick_lower_index,
+        tick_upper_index,
+        tick_array_lower_start_index,
+        tick_array_upper_start_index,
+        liquidity,
+        amount_0_max,
+        amount_1_max,
+        true,
+        None,
+    )?;
+
+    let accounts = &mut ctx.accounts;
+    accounts.authority_base_account.reload()?;
+    accounts.authority_pair_account.reload()?;
+    let base_spent = base_before.saturating_sub(accounts.authority_base_account.amount);
+    let pair_spent = pair_before.saturating_sub(accounts.authority_pair_account.amount);
+    let twap_sqrt_price_x64 = tick_math::get_sqrt_price_at_tick(twap_tick)?;
+    let assets = pair_to_base(pair_spent, twap_sqrt_price_x64, base_is_token_0)
+        .and_then(|pair_value| base_spent.checked_add(pair_value))
+        .ok_or(InteractDappError::MathOverflow)?;
+    let shares = shares_for_deposit(assets, accounts.share_mint.supply, total_value)
+        .ok_or(InteractDappError::MathOverflow)?;
+    require!(shares > 0, InteractDappError::ZeroAmount);
+
+    let strategy = &mut accounts.strategy;
+    strategy.position_nft_mint = accounts.position_nft_mint.key();
+    strategy.tick_lower_index = tick_lower_index;
+    strategy.tick_upper_index = tick_upper_index;
+
+    let seeds = strategy.signer_seeds();
+    token_interface::mint_to(
+        CpiContext::new_with_signer(
+            accounts.token_program.to_account_info(),
+            MintTo {
+                mint: accounts.share_mint.to_account_info(),
+                to: accounts.authority_share_account.to_account_info(),
+                authority: strategy.to_account_info(),
+            },
+            &[&seeds],
+        ),
+        shares,
+    )?;
+
+    emit!(StrategyDepositEvent {
+        strategy: strategy.key(),
+        depositor: accounts.authority.key(),
+        assets,
+        shares,
+        total_value,
+    });
+    Ok(())
+}
@@ -0,0 +1,509 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::memo::Memo;
+use anchor_spl::token::Token;
+use anchor_spl::token_interface::{self, Approve, Mint, Revoke, Token2022, TokenAccount};
+use raydium_amm_v3::{
+    cpi,
+    libraries::{big_num::U256, liquidity_math, tick_math},
+    program::AmmV3,
+    states::{AmmConfig, ObservationState, PersonalPositionState, PoolState, TickArrayState},
+};
+use crate::constant::CONFIG_SEED;
+use crate::error::InteractDappError;
+use crate::events::RebalanceRangeEvent;
+use crate::instructions::invoke_swap_v2;
+use crate::math::{
+    apply_slippage, base_to_pair, get_base_value_share, get_centered_range, pair_to_base,
+};
+use crate::states::{Config, Strategy};
+use super::{check_pool_vaults, get_checked_twap_tick, ordered};
+
+/// Moves the strategy CLMM position back around the pool price once the price has left
+/// its range: removes the liquidity, swaps to the ratio of the new range and reopens it.
+/// Permissionless: the pool price has to agree with its TWAP, and every leg is bounded by
+/// the strategy slippage from the TWAP price. The keeper pays the rent of the new position
+/// accounts.
+#[derive(Accounts)]
+pub struct RebalanceRange<'info> {
+    pub clmm_program: Program<'info, AmmV3>,
+
+    #[account(mut)]
+    pub keeper: Signer<'info>,
+
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Box<Account<'info, Config>>,
+
+    #[account(
+        mut,
+        has_one = base_mint,
+        has_one = pair_mint,
+        has_one = base_vault,
+        has_one = pair_vault,
+        has_one = pool_state,
+    )]
+    pub strategy: Box<Account<'info, Strategy>>,
+
+    pub base_mint: Box<InterfaceAccount<'info, Mint>>,
+    pub pair_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(mut)]
+    pub base_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(mut)]
+    pub pair_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(address = pool_state.load()?.amm_config)]
+    pub amm_config: Box<Account<'info, AmmConfig>>,
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+    #[account(mut, address = pool_state.load()?.observation_key)]
+    pub observation_state: AccountLoader<'info, ObservationState>,
+    /// Pool vault of the base token
+    #[account(mut)]
+    pub pool_base_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+    /// Pool vault of the pair token
+    #[account(mut)]
+    pub pool_pair_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    // Current position, closed by the rebalance
+    ///CHECK: the strategy position NFT mint
+    #[account(mut, address = strategy.position_nft_mint)]
+    pub position_nft_mint: UncheckedAccount<'info>,
+    ///CHECK: checked by the CLMM program
+    #[account(mut)]
+    pub position_nft_account: UncheckedAccount<'info>,
+    ///CHECK: deserialized and checked in the handler, the CLMM program writes to it
+    #[account(mut)]
+    pub personal_position: UncheckedAccount<'info>,
+    ///CHECK: checked by the CLMM program
+    #[account(mut)]
+    pub protocol_position: UncheckedAccount<'info>,
+    ///CHECK: checked by the CLMM program
+    #[account(mut)]
+    pub tick_array_lower: UncheckedAccount<'info>,
+    ///CHECK: checked by the CLMM program
+    #[account(mut)]
+    pub tick_array_upper: UncheckedAccount<'info>,
+
+    // New position, opened around the current tick
+    #[account(mut)]
+    pub new_position_nft_mint: Signer<'info>,
+    ///CHECK: created by the CLMM program, ATA of the strategy
+    #[account(mut)]
+    pub new_position_nft_account: UncheckedAccount<'info>,
+    ///CHECK: created by the CLMM program
+    #[account(mut)]
+    pub new_metadata_account: UncheckedAccount<'info>,
+    ///CHECK: checked by the CLMM program
+    #[account(mut)]
+    pub new_protocol_position: UncheckedAccount<'info>,
+    ///CHECK: checked by the CLMM program
+    #[account(mut)]
+    pub new_tick_array_lower: UncheckedAccount<'info>,
+    ///CHECK: checked by the CLMM program
+    #[account(mut)]
+    pub new_tick_array_upper: UncheckedAccount<'info>,
+    ///CHECK: created by the CLMM program
+    #[account(mut)]
+    pub new_personal_position: UncheckedAccount<'info>,
+
+    pub rent: Sysvar<'info, Rent>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+    pub token_program_2022: Program<'info, Token2022>,
+    pub memo_program: Program<'info, Memo>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    ///CHECK: Metaplex token metadata program
+    pub metadata_program: UncheckedAccount<'info>,
+    // remaining accounts, split by remaining_accounts_indices:
+    // reward token accounts for `decrease_liquidity_v2`,
+    // tickarray_bitmap_extension and tick arrays for `swap_v2`,
+    // tickarray_bitmap_extension for `open_position_v2`
+}
+
+pub fn rebalance_range<'a, 'b, 'c: 'info, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, RebalanceRange<'info>>,
+    remaining_accounts_indices: Vec<u8>,
+) -> Result<()> {
+    require!(!ctx.accounts.config.paused, InteractDappError::ProgramPaused);
+    if remaining_accounts_indices.len() != 3 {
+        return Err(InteractDappError::InvalidRemainingAccountsIndices.into());
+    }
+    let decrease_count = remaining_accounts_indices[0] as usize;
+    let swap_count = remaining_accounts_indices[1] as usize;
+    let open_count = remaining_accounts_indices[2] as usize;
+    if ctx.remaining_accounts.len() < decrease_count + swap_count + open_count {
+        return Err(InteractDappError::InvalidRemainingAccountsIndices.into());
+    }
+    let (decrease_accounts, rest) = ctx.remaining_accounts.split_at(decrease_count);
+    let (swap_accounts, rest) = rest.split_at(swap_count);
+    let open_accounts = &rest[..open_count];
+
+    let accounts = &mut ctx.accounts;
+    require!(accounts.strategy.has_position(), InteractDappError::StrategyPositionMissing);
+    let old_tick_lower = accounts.strategy.tick_lower_index;
+    let old_tick_upper = accounts.strategy.tick_upper_index;
+
+    let (tick_current, twap_tick, tick_lower, tick_upper) = {
+        let pool_state = accounts.pool_state.load()?;
+        check_pool_vaults(
+            &accounts.strategy,
+            &pool_state,
+            &accounts.pool_base_vault.key(),
+            &accounts.pool_pair_vault.key(),
+        )?;
+        let tick_current = pool_state.tick_current;
+        require!(
+            tick_current < old_tick_lower || tick_current >= old_tick_upper,
+            InteractDappError::PositionInRange
+        );
+
+        let twap_tick = get_checked_twap_tick(
+            &accounts.strategy,
+            &pool_state,
+            &*accounts.observation_state.load()?,
+        )?;
+
+        let (tick_lower, tick_upper) = get_centered_range(
+            tick_current,
+            pool_state.tick_spacing,
+            accounts.strategy.range_width,
+        )
+        .ok_or(InteractDappError::InvalidStrategyConfig)?;
+        (tick_current, twap_tick, tick_lower, tick_upper)
+    };
+
+    let liquidity = accounts.load_position_liquidity()?;
+    accounts.close_position(liquidity, twap_tick, decrease_accounts)?;
+    accounts.swap_to_range(tick_lower, tick_upper, twap_tick, swap_accounts)?;
+    let new_liquidity = accounts.open_position(tick_lower, tick_upper, open_accounts)?;
+
+    let strategy = &mut accounts.strategy;
+    strategy.position_nft_mint = accounts.new_position_nft_mint.key();
+    strategy.tick_lower_index = tick_lower;
+    strategy.tick_upper_index = tick_upper;
+
+    emit!(RebalanceRangeEvent {
+        strategy: strategy.key(),
+        keeper: accounts.keeper.key(),
+        old_tick_lower,
+        old_tick_upper,
+        tick_lower,
+        tick_upper,
+        tick_current,
+        twap_tick,
+        liquidity: new_liquidity,
+    });
+    Ok(())
+}
+
+impl<'info> RebalanceRange<'info> {
+    fn token_program_of(&self, token_account: &AccountInfo<'info>) -> AccountInfo<'info> {
+        if *token_account.owner == self.token_program_2022.key() {
+            self.token_program_2022.to_account_info()
+        } else {
+            self.token_program.to_account_info()
+        }
+    }
+
+    fn load_position_liquidity(&self) -> Result<u128> {
+        let personal_position = &self.personal_position;
+        require_keys_eq!(
+            *personal_position.owner,
+            self.clmm_program.key(),
+            InteractDappError::InvalidAccountOwner
+        );
+        let data = personal_position.try_borrow_data()?;
+        let position = PersonalPositionState::try_deserialize(&mut &data[..])?;
+        require_keys_eq!(
+            position.nft_mint,
+            self.strategy.position_nft_mint,
+            InteractDappError::StrategyPositionMissing
+        );
+        Ok(position.liquidity)
+    }
+
+    /// Removes all the liquidity and fees of the current position into the strategy vaults
+    /// and burns its NFT. The minimum amounts are the ones the liquidity is worth at the
+    /// TWAP price less the strategy slippage.
+    fn close_position(
+        &self,
+        liquidity: u128,
+        twap_tick: i32,
+        remaining_accounts: &[AccountInfo<'info>],
+    ) -> Result<()> {
+        let (amount_0, amount_1) = liquidity_math::get_delta_amounts_signed(
+            twap_tick,
+            tick_math::get_sqrt_price_at_tick(twap_tick)?,
+            self.strategy.tick_lower_index,
+            self.strategy.tick_upper_index,
+            -(liquidity as i128),
+        )?;
+        let max_slippage_bps = self.strategy.max_slippage_bps;
+        let amount_0_min = apply_slippage(amount_0, max_slippage_bps);
+        let amount_1_min = apply_slippage(amount_1, max_slippage_bps);
+
+        let base_is_token_0 = self.strategy.base_is_token_0();
+        let (token_vault_0, token_vault_1) = ordered(
+            base_is_token_0,
+            self.pool_base_vault.to_account_info(),
+            self.pool_pair_vault.to_account_info(),
+        );
+        let (recipient_token_account_0, recipient_token_account_1) = ordered(
+            base_is_token_0,
+            self.base_vault.to_account_info(),
+            self.pair_vault.to_account_info(),
+        );
+        let (vault_0_mint, vault_1_mint) = ordered(
+            base_is_token_0,
+            self.base_mint.to_account_info(),
+            self.pair_mint.to_account_info(),
+        );
+        let seeds = self.strategy.signer_seeds();
+        let signer_seeds: &[&[&[u8]]] = &[&seeds];
+
+        let cpi_accounts = cpi::accounts::DecreaseLiquidityV2 {
+            nft_owner: self.strategy.to_account_info(),
+            nft_account: self.position_nft_account.to_account_info(),
+            personal_position: self.personal_position.to_account_info(),
+            pool_state: self.pool_state.to_account_info(),
+            protocol_position: self.protocol_position.to_account_info(),
+            token_vault_0,
+            token_vault_1,
+            tick_array_lower: self.tick_array_lower.to_account_info(),
+            tick_array_upper: self.tick_array_upper.to_account_info(),
+            recipient_token_account_0,
+            recipient_token_account_1,
+            token_program: self.token_program.to_account_info(),
+            token_program_2022: self.token_program_2022.to_account_info(),
+            memo_program: self.memo_program.to_account_info(),
+            vault_0_mint,
+            vault_1_mint,
+        };
+        cpi::decrease_liquidity_v2(
+            CpiContext::new_with_signer(
+                self.clmm_program.to_account_info(),
+                cpi_accounts,
+                signer_seeds,
+            )
+            .with_remaining_accounts(remaining_accounts.to_vec()),
+            liquidity,
+            amount_0_min,
+            amount_1_min,
+        )?;
+
+        let cpi_accounts = cpi::accounts::ClosePosition {
+            nft_owner: self.strategy.to_account_info(),
+            position_nft_mint: self.position_nft_mint.to_account_info(),
+            position_nft_account: self.position_nft_account.to_account_info(),
+            personal_position: self.personal_position.to_account_info(),
+            system_program: self.system_program.to_account_info(),
+            token_program: self.token_program.to_account_info(),
+        };
+        cpi::close_position(CpiContext::new_with_signer(
+            self.clmm_program.to_account_info(),
+            cpi_accounts,
+            signer_seeds,
+        ))
+    }
+
+    /// Swaps the strategy balances to the token ratio of the new range. The minimum amount
+    /// out is quoted at the TWAP price less the strategy slippage.
+    fn swap_to_range(
+        &mut self,
+        tick_lower: i32,
+        tick_upper: i32,
+        twap_tick: i32,
+        remaining_accounts: &[AccountInfo<'info>],
+    ) -> Result<()> {
+        self.base_vault.reload()?;
+        self.pair_vault.reload()?;
+        let base_is_token_0 = self.strategy.base_is_token_0();
+        let sqrt_price_x64 = self.pool_state.load()?.sqrt_price_x64;
+        let twap_sqrt_price_x64 = tick_math::get_sqrt_price_at_tick(twap_tick)?;
+        let base_held = self.base_vault.amount;
+        let pair_held = self.pair_vault.amount;
+
+        let (share_numerator, share_denominator) = get_base_value_share(
+            tick_math::get_sqrt_price_at_tick(tick_lower)?,
+            sqrt_price_x64,
+            tick_math::get_sqrt_price_at_tick(tick_upper)?,
+            base_is_token_0,
+        )
+        .ok_or(InteractDappError::MathOverflow)?;
+        let total_value = pair_to_base(pair_held, sqrt_price_x64, base_is_token_0)
+            .and_then(|pair_value| base_held.checked_add(pair_value))
+            .ok_or(InteractDappError::MathOverflow)?;
+        let target_base = (U256::from(total_value) * share_numerator / share_denominator).as_u64();
+
+        let (base_input, amount_in, expected_out) = if base_held > target_base {
+            let amount_in = base_held - target_base;
+            let expected_out = base_to_pair(amount_in, twap_sqrt_price_x64, base_is_token_0);
+            (true, amount_in, expected_out)
+        } else {
+            let amount_in = base_to_pair(target_base - base_held, sqrt_price_x64, base_is_token_0)
+                .ok_or(InteractDappError::MathOverflow)?
+                .min(pair_held);
+            let expected_out = pair_to_base(amount_in, twap_sqrt_price_x64, base_is_token_0);
+            (false, amount_in, expected_out)
+        };
+        if amount_in == 0 {
+            return Ok(());
+        }
+        let minimum_amount_out = apply_slippage(
+            expected_out.ok_or(InteractDappError::MathOverflow)?,
+            self.strategy.max_slippage_bps,
+        );
+
+        let (input_token_account, output_token_account) = ordered(
+            base_input,
+            self.base_vault.to_account_info(),
+            self.pair_vault.to_account_info(),
+        );
+        let (input_vault, output_vault) = ordered(
+            base_input,
+            self.pool_base_vault.to_account_info(),
+            self.pool_pair_vault.to_account_info(),
+        );
+        let (input_vault_mint, output_vault_mint) = ordered(
+            base_input,
+            self.base_mint.to_account_info(),
+            self.pair_mint.to_account_info(),
+        );
+        let cpi_accounts = cpi::accounts::SwapSingleV2 {
+            payer: self.strategy.to_account_info(),
+            amm_config: self.amm_config.to_account_info(),
+            pool_state: self.pool_state.to_account_info(),
+            input_token_account,
+            output_token_account,
+            input_vault,
+            output_vault,
+            observation_state: self.observation_state.to_account_info(),
+            token_program: self.token_program.to_account_info(),
+            token_program_2022: self.token_program_2022.to_account_info(),
+            memo_program: self.memo_program.to_account_info(),
+            input_vault_mint,
+            output_vault_mint,
+        };
+        let seeds = self.strategy.signer_seeds();
+        invoke_swap_v2(
+            &self.clmm_program,
+            cpi_accounts,
+            remaining_accounts,
+            amount_in,
+            minimum_amount_out,
+            0,
+            true,
+            &[&seeds],
+        )
+    }
+
+    /// Opens the new position with the strategy balances, returns its liquidity.
+    ///
+    /// The CLMM program pulls the deposit from accounts the payer can move, so the strategy
+    /// delegates its vaults to the keeper for the duration of the CPI.
+    fn open_position(
+        &mut self,
+        tick_lower: i32,
+        tick_upper: i32,
+        remaining_accounts: &[AccountInfo<'info>],
+    ) -> Result<u128> {
+        self.base_vault.reload()?;
+        self.pair_vault.reload()?;
+        let base_is_token_0 = self.strategy.base_is_token_0();
+        let (amount_0, amount_1) =
+            ordered(base_is_token_0, self.base_vault.amount, self.pair_vault.amount);
+        // one unit kept back on each side for the rounding up of the CLMM program
+        let liquidity = liquidity_math::get_liquidity_from_amounts(
+            self.pool_state.load()?.sqrt_price_x64,
+            tick_math::get_sqrt_price_at_tick(tick_lower)?,
+            tick_math::get_sqrt_price_at_tick(tick_upper)?,
+            amount_0.saturating_sub(1),
+            amount_1.saturating_sub(1),
+        );
+        require!(liquidity > 0, InteractDappError::InsufficientStrategyLiquidity);
+
+        let seeds = self.strategy.signer_seeds();
+        let signer_seeds: &[&[&[u8]]] = &[&seeds];
+        let (token_account_0, token_account_1) = ordered(
+            base_is_token_0,
+            self.base_vault.to_account_info(),
+            self.pair_vault.to_account_info(),
+        );
+        for (token_account, amount) in [(&token_account_0, amount_0), (&token_account_1, amount_1)] {
+            token_interface::approve(
+                CpiContext::new_with_signer(
+                    self.token_program_of(token_account),
+                    Approve {
+                        to: token_account.clone(),
+                        delegate: self.keeper.to_account_info(),
+                        authority: self.strategy.to_account_info(),
+                    },
+                    signer_seeds,
+                ),
+                amount,
+            )?;
+        }
+
+        let (token_vault_0, token_vault_1) = ordered(
+            base_is_token_0,
+            self.pool_base_vault.to_account_info(),
+            self.pool_pair_vault.to_account_info(),
+        );
+        let (vault_0_mint, vault_1_mint) = ordered(
+            base_is_token_0,
+            self.base_mint.to_account_info(),
+            self.pair_mint.to_account_info(),
+        );
+        let tick_spacing = self.pool_state.load()?.tick_spacing;
+        let cpi_accounts = cpi::accounts::OpenPositionV2 {
+            payer: self.keeper.to_account_info(),
+            position_nft_owner: self.strategy.to_account_info(),
+            position_nft_mint: self.new_position_nft_mint.to_account_info(),
+            position_nft_account: self.new_position_nft_account.to_account_info(),
+            metadata_account: self.new_metadata_account.to_account_info(),
+            pool_state: self.pool_state.to_account_info(),
+            protocol_position: self.new_protocol_position.to_account_info(),
+            tick_array_lower: self.new_tick_array_lower.to_account_info(),
+            tick_array_upper: self.new_tick_array_upper.to_account_info(),
+            personal_position: self.new_personal_position.to_account_info(),
+            token_account_0: token_account_0.clone(),
+            token_account_1: token_account_1.clone(),
+            token_vault_0,
+            token_vault_1,
+            rent: self.rent.to_account_info(),
+            system_program: self.system_program.to_account_info(),
+            token_program: self.token_program.to_account_info(),
+            associated_token_program: self.associated_token_program.to_account_info(),
+            metadata_program: self.metadata_program.to_account_info(),
+            token_program_2022: self.token_program_2022.to_account_info(),
+            vault_0_mint,
+            vault_1_mint,
+        };
+        cpi::open_position_v2(
+            CpiContext::new(self.clmm_program.to_account_info(), cpi_accounts)
+                .with_remaining_accounts(remaining_accounts.to_vec()),
+            tick_lower,
+            tick_upper,
+            TickArrayState::get_array_start_index(tick_lower, tick_spacing),
+            TickArrayState::get_array_start_index(tick_upper, tick_spacing),
+            liquidity,
+            amount_0,
+            amount_1,
+            false,
+            None,
+        )?;
+
+        for token_account in [&token_account_0, &token_account_1] {
+            token_interface::revoke(CpiContext::new_with_signer(
+                self.token_program_of(token_account),
+                Revoke {
+                    source: token_account.clone(),
+                    authority: self.strategy.to_account_info(),
+                },
+                signer_seeds,
+            ))?;
+        }
+        Ok(liquidity)
+    }
+}
@@ -0,0 +1,30 @@
+use anchor_lang::prelude::*;
+use crate::error::InteractDappError;
+use crate::states::Strategy;
+
+#[derive(Accounts)]
+pub struct SetRebalanceConfig<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(mut, has_one = authority)]
+    pub strategy: Box<Account<'info, Strategy>>,
+}
+
+impl<'info> SetRebalanceConfig<'info> {
+    pub fn set_rebalance_config(
+        &mut self,
+        range_width: i32,
+        twap_window: u32,
+        max_tick_deviation: u32,
+    ) -> Result<()> {
+        require!(
+            range_width > 0 && twap_window > 0,
+            InteractDappError::InvalidStrategyConfig
+        );
+        let strategy = &mut self.strategy;
+        strategy.range_width = range_width;
+        strategy.twap_window = twap_window;
+        strategy.max_tick_deviation = max_tick_deviation;
+        Ok(())
+    }
+}
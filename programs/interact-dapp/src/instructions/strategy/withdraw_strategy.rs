@@ -0,0 +1,375 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::memo::Memo;
+use anchor_spl::token_interface::{
+    self, Burn, Mint, Token2022, TokenAccount, TokenInterface, TransferChecked,
+};
+use raydium_amm_v3::{
+    cpi,
+    libraries::{liquidity_math, tick_math},
+    program::AmmV3,
+    states::{ObservationState, PersonalPositionState, PoolState},
+};
+use crate::constant::{CONFIG_SEED, DEPOSIT_CAPS_SEED, USER_DEPOSITS_SEED};
+use crate::error::InteractDappError;
+use crate::events::StrategyWithdrawEvent;
+use crate::instructions::{EarnAccounts, EarnUser};
+use crate::math::{
+    apply_slippage, assets_for_shares, base_to_pair, convert_to_assets, liquidity_for_shares,
+};
+use crate::states::{Config, DepositCaps, Lending, Strategy, UserDeposits};
+use super::{
+    check_earn_accounts, check_pool_vaults, get_checked_twap_tick, ordered, StrategyValuation,
+};
+
+/// Redeems strategy shares for the base token. Withdrawals are paid from the idle base
+/// balance, then from Earn, then by removing the shares' pro rata part of the CLMM
+/// liquidity. Value still owed once the base runs out is paid in the pair token at the TWAP
+/// price. Earn withdrawals the liquidity layer would queue fail with `WithdrawalQueued`.
+#[derive(Accounts)]
+pub struct WithdrawStrategy<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Box<Account<'info, Config>>,
+
+    #[account(
+        has_one = share_mint,
+        has_one = base_mint,
+        has_one = pair_mint,
+        has_one = base_vault,
+        has_one = pair_vault,
+        has_one = f_token_vault,
+        has_one = pool_state,
+    )]
+    pub strategy: Box<Account<'info, Strategy>>,
+
+    #[account(mut)]
+    pub share_mint: Box<InterfaceAccount<'info, Mint>>,
+    pub base_mint: Box<InterfaceAccount<'info, Mint>>,
+    pub pair_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// Share token account the shares are burned from
+    #[account(mut, token::mint = share_mint, token::authority = owner)]
+    pub owner_share_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    /// Base token account the assets are sent to
+    #[account(mut, token::mint = base_mint)]
+    pub recipient_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    /// Pair token account paid the value the base balance cannot cover
+    #[account(mut, token::mint = pair_mint)]
+    pub recipient_pair_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    #[account(mut)]
+    pub base_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(mut)]
+    pub pair_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(mut)]
+    pub f_token_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub clmm_program: Program<'info, AmmV3>,
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+    #[account(address = pool_state.load()?.observation_key)]
+    pub observation_state: AccountLoader<'info, ObservationState>,
+
+    // Strategy position, required once the strategy has opened it
+    #[account(mut)]
+    pub personal_position: Option<Box<Account<'info, PersonalPositionState>>>,
+    ///CHECK: checked by the CLMM program
+    pub position_nft_account: Option<UncheckedAccount<'info>>,
+    ///CHECK: checked by the CLMM program
+    #[account(mut)]
+    pub protocol_position: Option<UncheckedAccount<'info>>,
+    ///CHECK: checked by the CLMM program
+    #[account(mut)]
+    pub tick_array_lower: Option<UncheckedAccount<'info>>,
+    ///CHECK: checked by the CLMM program
+    #[account(mut)]
+    pub tick_array_upper: Option<UncheckedAccount<'info>>,
+    /// Pool vault of the base token
+    #[account(mut)]
+    pub pool_base_vault: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    /// Pool vault of the pair token
+    #[account(mut)]
+    pub pool_pair_vault: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    pub earn: EarnAccounts<'info>,
+    /// Earn claim account of the strategy
+    ///CHECK:
+    #[account(mut)]
+    pub claim_account: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [DEPOSIT_CAPS_SEED, base_mint.key().as_ref()],
+        bump = deposit_caps.bump,
+    )]
+    pub deposit_caps: Box<Account<'info, DepositCaps>>,
+    /// Deposit record of the owner, absent when the shares were received by transfer
+    #[account(
+        mut,
+        seeds = [USER_DEPOSITS_SEED, base_mint.key().as_ref(), owner.key().as_ref()],
+        bump = user_deposits.bump,
+    )]
+    pub user_deposits: Option<Box<Account<'info, UserDeposits>>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub pair_token_program: Interface<'info, TokenInterface>,
+    pub token_program_2022: Program<'info, Token2022>,
+    pub memo_program: Program<'info, Memo>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+    // remaining accounts: reward token accounts expected by `decrease_liquidity_v2`
+}
+
+pub fn withdraw_strategy<'a, 'b, 'c: 'info, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, WithdrawStrategy<'info>>,
+    shares: u64,
+) -> Result<()> {
+    let accounts = ctx.accounts;
+    require!(!accounts.config.paused, InteractDappError::ProgramPaused);
+    require!(shares > 0, InteractDappError::ZeroAmount);
+    check_earn_accounts(&accounts.strategy, &accounts.earn)?;
+
+    let twap_tick = get_checked_twap_tick(
+        &accounts.strategy,
+        &*accounts.pool_state.load()?,
+        &*accounts.observation_state.load()?,
+    )?;
+    let lending = Lending::load(&accounts.earn.lending, accounts.earn.lending_program.key)?;
+    let total_value = StrategyValuation {
+        strategy: &accounts.strategy,
+        twap_tick,
+        base_vault: &accounts.base_vault,
+        pair_vault: &accounts.pair_vault,
+        f_token_vault: &accounts.f_token_vault,
+        lending: &lending,
+        position: accounts.personal_position.as_deref().map(|p| &**p),
+    }
+    .total_value()?;
+    // Supply before the burn, the position is split by the fraction of it redeemed
+    let share_supply = accounts.share_mint.supply;
+    let assets = assets_for_shares(shares, share_supply, total_value)
+        .ok_or(InteractDappError::MathOverflow)?;
+    require!(assets > 0, InteractDappError::ZeroAmount);
+
+    token_interface::burn(
+        CpiContext::new(
+            accounts.token_program.to_account_info(),
+            Burn {
+                mint: accounts.share_mint.to_account_info(),
+                from: accounts.owner_share_account.to_account_info(),
+                authority: accounts.owner.to_account_info(),
+            },
+        ),
+        shares,
+    )?;
+
+    let shortfall = assets.saturating_sub(accounts.base_vault.amount);
+    if shortfall > 0 {
+        let earn_assets =
+            convert_to_assets(accounts.f_token_vault.amount, lending.token_exchange_price)
+                .ok_or(InteractDappError::MathOverflow)?;
+        accounts.withdraw_from_earn(shortfall.min(earn_assets))?;
+    }
+    if accounts.base_vault.amount < assets && accounts.strategy.has_position() {
+        accounts.decrease_liquidity(shares, share_supply, twap_tick, ctx.remaining_accounts)?;
+    }
+
+    let base_amount = assets.min(accounts.base_vault.amount);
+    let pair_amount = base_to_pair(
+        assets - base_amount,
+        tick_math::get_sqrt_price_at_tick(twap_tick)?,
+        accounts.strategy.base_is_token_0(),
+    )
+    .ok_or(InteractDappError::MathOverflow)?;
+    require!(
+        accounts.pair_vault.amount >= pair_amount,
+        InteractDappError::InsufficientStrategyLiquidity
+    );
+
+    let seeds = accounts.strategy.signer_seeds();
+    if base_amount > 0 {
+        token_interface::transfer_checked(
+            CpiContext::new_with_signer(
+                accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: accounts.base_vault.to_account_info(),
+                    mint: accounts.base_mint.to_account_info(),
+                    to: accounts.recipient_token_account.to_account_info(),
+                    authority: accounts.strategy.to_account_info(),
+                },
+                &[&seeds],
+            ),
+            base_amount,
+            accounts.base_mint.decimals,
+        )?;
+    }
+    if pair_amount > 0 {
+        let Some(recipient_pair_account) = &accounts.recipient_pair_account else {
+            return Err(InteractDappError::InsufficientStrategyLiquidity.into());
+        };
+        token_interface::transfer_checked(
+            CpiContext::new_with_signer(
+                accounts.pair_token_program.to_account_info(),
+                TransferChecked {
+                    from: accounts.pair_vault.to_account_info(),
+                    mint: accounts.pair_mint.to_account_info(),
+                    to: recipient_pair_account.to_account_info(),
+                    authority: accounts.strategy.to_account_info(),
+                },
+                &[&seeds],
+            ),
+            pair_amount,
+            accounts.pair_mint.decimals,
+        )?;
+    }
+    accounts.deposit_caps.ledger.remove_assets(assets);
+    if let Some(user_deposits) = &mut accounts.user_deposits {
+        user_deposits.ledger.remove_assets(assets);
+    }
+
+    emit!(StrategyWithdrawEvent {
+        strategy: accounts.strategy.key(),
+        owner: accounts.owner.key(),
+        assets,
+        shares,
+        total_value,
+        base_amount,
+        pair_amount,
+    });
+    Ok(())
+}
+
+impl<'info> WithdrawStrategy<'info> {
+    /// Moves `amount` of base from Earn to the base vault and the ledger from shares to assets.
+    fn withdraw_from_earn(&mut self, amount: u64) -> Result<()> {
+        if amount == 0 {
+            return Ok(());
+        }
+        let user = EarnUser {
+            signer: self.strategy.to_account_info(),
+            token_account: self.base_vault.to_account_info(),
+            f_token_account: self.f_token_vault.to_account_info(),
+            mint: self.base_mint.to_account_info(),
+            token_program: self.token_program.to_account_info(),
+            associated_token_program: self.associated_token_program.to_account_info(),
+            system_program: self.system_program.to_account_info(),
+        };
+        let seeds = self.strategy.signer_seeds();
+        let base_before = self.base_vault.amount;
+        let shares_before = self.f_token_vault.amount;
+        self.earn.withdraw(&user, &self.claim_account, amount, &[&seeds])?;
+        self.base_vault.reload()?;
+        self.f_token_vault.reload()?;
+        let ledger = &mut self.deposit_caps.ledger;
+        ledger.remove_shares(shares_before.saturating_sub(self.f_token_vault.amount));
+        ledger.add_assets(self.base_vault.amount.saturating_sub(base_before))
+    }
+
+    /// Removes the liquidity backing `shares` out of `share_supply` into the strategy vaults.
+    /// The amounts out are bounded by the strategy slippage from the TWAP price.
+    fn decrease_liquidity(
+        &mut self,
+        shares: u64,
+        share_supply: u64,
+        twap_tick: i32,
+        remaining_accounts: &[AccountInfo<'info>],
+    ) -> Result<()> {
+        let (
+            Some(personal_position),
+            Some(position_nft_account),
+            Some(protocol_position),
+            Some(tick_array_lower),
+            Some(tick_array_upper),
+            Some(pool_base_vault),
+            Some(pool_pair_vault),
+        ) = (
+            &self.personal_position,
+            &self.position_nft_account,
+            &self.protocol_position,
+            &self.tick_array_lower,
+            &self.tick_array_upper,
+            &self.pool_base_vault,
+            &self.pool_pair_vault,
+        )
+        else {
+            return Err(InteractDappError::StrategyPositionMissing.into());
+        };
+        check_pool_vaults(
+            &self.strategy,
+            &*self.pool_state.load()?,
+            &pool_base_vault.key(),
+            &pool_pair_vault.key(),
+        )?;
+        let liquidity = liquidity_for_shares(personal_position.liquidity, shares, share_supply)
+            .ok_or(InteractDappError::MathOverflow)?;
+        if liquidity == 0 {
+            return Ok(());
+        }
+
+        let (amount_0, amount_1) = liquidity_math::get_delta_amounts_signed(
+            twap_tick,
+            tick_math::get_sqrt_price_at_tick(twap_tick)?,
+            self.strategy.tick_lower_index,
+            self.strategy.tick_upper_index,
+            -(liquidity as i128),
+        )?;
+        let max_slippage_bps = self.strategy.max_slippage_bps;
+        let amount_0_min = apply_slippage(amount_0, max_slippage_bps);
+        let amount_1_min = apply_slippage(amount_1, max_slippage_bps);
+
+        let base_is_token_0 = self.strategy.base_is_token_0();
+        let (token_vault_0, token_vault_1) = ordered(
+            base_is_token_0,
+            pool_base_vault.to_account_info(),
+            pool_pair_vault.to_account_info(),
+        );
+        let (recipient_token_account_0, recipient_token_account_1) = ordered(
+            base_is_token_0,
+            self.base_vault.to_account_info(),
+            self.pair_vault.to_account_info(),
+        );
+        let (vault_0_mint, vault_1_mint) = ordered(
+            base_is_token_0,
+            self.base_mint.to_account_info(),
+            self.pair_mint.to_account_info(),
+        );
+        let cpi_accounts = cpi::accounts::DecreaseLiquidityV2 {
+            nft_owner: self.strategy.to_account_info(),
+            nft_account: position_nft_account.to_account_info(),
+            personal_position: personal_position.to_account_info(),
+            pool_state: self.pool_state.to_account_info(),
+            protocol_position: protocol_position.to_account_info(),
+            token_vault_0,
+            token_vault_1,
+            tick_array_lower: tick_array_lower.to_account_info(),
+            tick_array_upper: tick_array_upper.to_account_info(),
+            recipient_token_account_0,
+            recipient_token_account_1,
+            token_program: self.token_program.to_account_info(),
+            token_program_2022: self.token_program_2022.to_account_info(),
+            memo_program: self.memo_program.to_account_info(),
+            vault_0_mint,
+            vault_1_mint,
+        };
+        let seeds = self.strategy.signer_seeds();
+        let signer_seeds: &[&[&[u8]]] = &[&seeds];
+        cpi::decrease_liquidity_v2(
+            CpiContext::new_with_signer(
+                self.clmm_program.to_account_info(),
+                cpi_accounts,
+                signer_seeds,
+            )
+            .with_remaining_accounts(remaining_accounts.to_vec()),
+            liquidity,
+            amount_0_min,
+            amount_1_min,
+        )?;
+
+        // The value only moves from the position to the vaults, the ledger is unchanged
+        self.base_vault.reload()?;
+        self.pair_vault.reload()?;
+        Ok(())
+    }
+}
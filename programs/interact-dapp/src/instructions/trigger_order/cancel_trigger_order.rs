@@ -0,0 +1,71 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::constant::{DEPOSIT_CAPS_SEED, USER_DEPOSITS_SEED};
+use crate::states::{DepositCaps, TriggerOrder, UserDeposits};
+use crate::utils::drain_token_account;
+
+/// Closes a trigger order, returning whatever is left in escrow to the owner.
+#[derive(Accounts)]
+pub struct CancelTriggerOrder<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(mut, close = owner, has_one = owner, has_one = mint, has_one = f_token_mint)]
+    pub trigger_order: Box<Account<'info, TriggerOrder>>,
+
+    #[account(mint::token_program = token_program)]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(mint::token_program = token_program)]
+    pub f_token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(mut, token::mint = f_token_mint, token::authority = trigger_order)]
+    pub f_token_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(mut, token::mint = mint, token::authority = trigger_order)]
+    pub token_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut, token::mint = f_token_mint)]
+    pub owner_f_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(mut, token::mint = mint)]
+    pub owner_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut, seeds = [DEPOSIT_CAPS_SEED, mint.key().as_ref()], bump = deposit_caps.bump)]
+    pub deposit_caps: Box<Account<'info, DepositCaps>>,
+    #[account(
+        mut,
+        seeds = [USER_DEPOSITS_SEED, mint.key().as_ref(), owner.key().as_ref()],
+        bump = user_deposits.bump,
+    )]
+    pub user_deposits: Box<Account<'info, UserDeposits>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+impl<'info> CancelTriggerOrder<'info> {
+    pub fn cancel_trigger_order(&mut self) -> Result<()> {
+        let order = &self.trigger_order;
+        let order_id = order.order_id.to_le_bytes();
+        let seeds = order.signer_seeds(&order_id);
+        let signer_seeds: &[&[&[u8]]] = &[&seeds];
+
+        self.deposit_caps
+            .remove_shares(&mut self.user_deposits, self.f_token_vault.amount);
+        drain_token_account(
+            self.token_program.to_account_info(),
+            &self.f_token_vault,
+            &self.f_token_mint,
+            self.owner_f_token_account.to_account_info(),
+            order.to_account_info(),
+            self.owner.to_account_info(),
+            signer_seeds,
+        )?;
+        drain_token_account(
+            self.token_program.to_account_info(),
+            &self.token_vault,
+            &self.mint,
+            self.owner_token_account.to_account_info(),
+            order.to_account_info(),
+            self.owner.to_account_info(),
+            signer_seeds,
+        )
+    }
+}
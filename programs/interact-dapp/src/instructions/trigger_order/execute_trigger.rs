@@ -0,0 +1,173 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_interface::TokenAccount;
+use raydium_amm_v3::libraries::tick_math;
+use crate::constant::{CONFIG_SEED, DEPOSIT_CAPS_SEED, USER_DEPOSITS_SEED};
+use crate::error::InteractDappError;
+use crate::events::TriggerExecutedEvent;
+use crate::instructions::{ClmmSwapAccounts, EarnAccounts, EarnUser};
+use crate::math::{apply_slippage, get_twap_tick, token_0_to_token_1, token_1_to_token_0};
+use crate::states::{Config, DepositCaps, TriggerOrder, UserDeposits};
+
+/// Permissionless exit of a trigger order once the pool TWAP has crossed its trigger:
+/// redeems the escrowed fTokens and swaps the underlying to the owner.
+#[derive(Accounts)]
+pub struct ExecuteTrigger<'info> {
+    pub keeper: Signer<'info>,
+
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Box<Account<'info, Config>>,
+
+    #[account(mut)]
+    pub trigger_order: Box<Account<'info, TriggerOrder>>,
+
+    #[account(mut, token::mint = trigger_order.f_token_mint, token::authority = trigger_order)]
+    pub f_token_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(mut, token::mint = trigger_order.mint, token::authority = trigger_order)]
+    pub token_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+    /// Output token account of the order owner, receives the proceeds
+    #[account(
+        mut,
+        token::mint = trigger_order.output_mint,
+        token::authority = trigger_order.owner,
+    )]
+    pub owner_output_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub earn: EarnAccounts<'info>,
+    ///CHECK: Earn claim account of the order
+    #[account(mut)]
+    pub claim_account: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [DEPOSIT_CAPS_SEED, trigger_order.mint.as_ref()],
+        bump = deposit_caps.bump,
+    )]
+    pub deposit_caps: Box<Account<'info, DepositCaps>>,
+    #[account(
+        mut,
+        seeds = [USER_DEPOSITS_SEED, trigger_order.mint.as_ref(), trigger_order.owner.as_ref()],
+        bump = user_deposits.bump,
+    )]
+    pub user_deposits: Box<Account<'info, UserDeposits>>,
+
+    pub swap: ClmmSwapAccounts<'info>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+    // remaining accounts
+    // tickarray_bitmap_extension and tick arrays for `swap_v2`
+}
+
+pub fn execute_trigger<'a, 'b, 'c: 'info, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, ExecuteTrigger<'info>>,
+) -> Result<()> {
+    require!(!ctx.accounts.config.paused, InteractDappError::ProgramPaused);
+    let accounts = &mut ctx.accounts;
+    let order = &accounts.trigger_order;
+    require!(!order.executed, InteractDappError::OrderAlreadyExecuted);
+    require_keys_eq!(
+        accounts.swap.pool_state.key(),
+        order.pool_state,
+        InteractDappError::InvalidOrderConfig
+    );
+    require_keys_eq!(
+        accounts.swap.input_vault_mint.key(),
+        order.mint,
+        InteractDappError::InvalidOrderConfig
+    );
+    require_keys_eq!(
+        accounts.swap.output_vault_mint.key(),
+        order.output_mint,
+        InteractDappError::InvalidOrderConfig
+    );
+    require_keys_eq!(accounts.earn.lending.key(), order.lending, InteractDappError::InvalidOrderConfig);
+    require_keys_eq!(
+        accounts.earn.lending_program.key(),
+        *accounts.earn.lending.owner,
+        InteractDappError::InvalidAccountOwner
+    );
+
+    let (twap_tick, underlying_is_token_0) = {
+        let pool_state = accounts.swap.pool_state.load()?;
+        let twap_tick = get_twap_tick(
+            &*accounts.swap.observation_state.load()?,
+            pool_state.tick_current,
+            Clock::get()?.unix_timestamp as u32,
+            order.twap_window,
+        )?;
+        (twap_tick, pool_state.token_mint_0 == order.mint)
+    };
+    require!(
+        order.is_triggered(twap_tick, underlying_is_token_0),
+        InteractDappError::TriggerNotReached
+    );
+
+    let order_id = order.order_id.to_le_bytes();
+    let seeds = order.signer_seeds(&order_id);
+    let signer_seeds: &[&[&[u8]]] = &[&seeds];
+
+    let shares = accounts.f_token_vault.amount;
+    if shares > 0 {
+        let user = EarnUser {
+            signer: order.to_account_info(),
+            token_account: accounts.token_vault.to_account_info(),
+            f_token_account: accounts.f_token_vault.to_account_info(),
+            mint: accounts.swap.input_vault_mint.to_account_info(),
+            token_program: accounts
+                .swap
+                .token_program_of(&accounts.token_vault.to_account_info()),
+            associated_token_program: accounts.associated_token_program.to_account_info(),
+            system_program: accounts.system_program.to_account_info(),
+        };
+        accounts
+            .earn
+            .redeem(&user, &accounts.claim_account, shares, signer_seeds)?;
+        accounts
+            .deposit_caps
+            .remove_shares(&mut accounts.user_deposits, shares);
+        accounts.token_vault.reload()?;
+    }
+
+    let amount_in = accounts.token_vault.amount;
+    require!(amount_in > 0, InteractDappError::ZeroAmount);
+    let twap_sqrt_price_x64 = tick_math::get_sqrt_price_at_tick(twap_tick)?;
+    let expected_out = if underlying_is_token_0 {
+        token_0_to_token_1(amount_in, twap_sqrt_price_x64)
+    } else {
+        token_1_to_token_0(amount_in, twap_sqrt_price_x64)
+    }
+    .ok_or(InteractDappError::MathOverflow)?;
+    let minimum_amount_out = apply_slippage(expected_out, order.max_slippage_bps);
+
+    let output_before = accounts.owner_output_account.amount;
+    accounts.swap.swap(
+        order.to_account_info(),
+        accounts.token_vault.to_account_info(),
+        accounts.owner_output_account.to_account_info(),
+        ctx.remaining_accounts,
+        amount_in,
+        minimum_amount_out,
+        0,
+        true,
+        signer_seeds,
+    )?;
+    accounts.owner_output_account.reload()?;
+    let amount_out = accounts
+        .owner_output_account
+        .amount
+        .saturating_sub(output_before);
+
+    let order = &mut accounts.trigger_order;
+    order.executed = true;
+
+    emit!(TriggerExecutedEvent {
+        trigger_order: order.key(),
+        keeper: accounts.keeper.key(),
+        twap_tick,
+        shares_redeemed: shares,
+        amount_in,
+        amount_out,
+    });
+    Ok(())
+}
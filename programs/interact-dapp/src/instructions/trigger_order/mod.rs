@@ -0,0 +1,7 @@
+pub mod cancel_trigger_order;
+pub mod execute_trigger;
+pub mod open_trigger_order;
+
+pub use cancel_trigger_order::*;
+pub use execute_trigger::*;
+pub use open_trigger_order::*;
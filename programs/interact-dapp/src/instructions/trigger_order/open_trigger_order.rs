@@ -0,0 +1,160 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked};
+use raydium_amm_v3::{libraries::tick_math, states::PoolState};
+use crate::constant::{CONFIG_SEED, DEPOSIT_CAPS_SEED, TRIGGER_ORDER_SEED, USER_DEPOSITS_SEED};
+use crate::error::InteractDappError;
+use crate::math::{convert_to_assets, BPS_PRECISION};
+use crate::programs::JUPITER_LENDING_PROGRAM_ID;
+use crate::states::{Config, DepositCaps, Lending, TriggerKind, TriggerOrder, UserDeposits};
+
+#[derive(Accounts)]
+#[instruction(order_id: u64)]
+pub struct OpenTriggerOrder<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Box<Account<'info, Config>>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + TriggerOrder::INIT_SPACE,
+        seeds = [TRIGGER_ORDER_SEED, owner.key().as_ref(), &order_id.to_le_bytes()],
+        bump,
+    )]
+    pub trigger_order: Box<Account<'info, TriggerOrder>>,
+
+    #[account(mint::token_program = token_program)]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(mint::token_program = token_program)]
+    pub f_token_mint: Box<InterfaceAccount<'info, Mint>>,
+    pub output_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(mut, token::mint = f_token_mint, token::authority = owner)]
+    pub owner_f_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Escrow of the fTokens
+    #[account(
+        init,
+        payer = owner,
+        associated_token::mint = f_token_mint,
+        associated_token::authority = trigger_order,
+        associated_token::token_program = token_program,
+    )]
+    pub f_token_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+    /// Receives the underlying on execution, the Earn redeem recipient
+    #[account(
+        init,
+        payer = owner,
+        associated_token::mint = mint,
+        associated_token::authority = trigger_order,
+        associated_token::token_program = token_program,
+    )]
+    pub token_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The Raydium CLMM pool the exit is swapped on and the TWAP is read from
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    ///CHECK: deserialized and checked in the handler
+    pub lending: AccountInfo<'info>,
+    ///CHECK:
+    #[account(address = JUPITER_LENDING_PROGRAM_ID @ InteractDappError::InvalidLendingProgram)]
+    pub lending_program: UncheckedAccount<'info>,
+
+    /// Risk limits of the underlying, the escrow counts against them
+    #[account(mut, seeds = [DEPOSIT_CAPS_SEED, mint.key().as_ref()], bump = deposit_caps.bump)]
+    pub deposit_caps: Box<Account<'info, DepositCaps>>,
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = 8 + UserDeposits::INIT_SPACE,
+        seeds = [USER_DEPOSITS_SEED, mint.key().as_ref(), owner.key().as_ref()],
+        bump,
+    )]
+    pub user_deposits: Box<Account<'info, UserDeposits>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> OpenTriggerOrder<'info> {
+    #[allow(clippy::too_many_arguments)]
+    pub fn open_trigger_order(
+        &mut self,
+        bumps: &OpenTriggerOrderBumps,
+        order_id: u64,
+        shares: u64,
+        kind: TriggerKind,
+        trigger_tick: i32,
+        twap_window: u32,
+        max_slippage_bps: u16,
+    ) -> Result<()> {
+        require!(!self.config.paused, InteractDappError::ProgramPaused);
+        require!(shares > 0, InteractDappError::ZeroAmount);
+        require!(
+            (tick_math::MIN_TICK..=tick_math::MAX_TICK).contains(&trigger_tick)
+                && twap_window > 0
+                && (max_slippage_bps as u128) < BPS_PRECISION,
+            InteractDappError::InvalidOrderConfig
+        );
+        let mint = self.mint.key();
+        let output_mint = self.output_mint.key();
+        {
+            let pool_state = self.pool_state.load()?;
+            let pool_mints = (pool_state.token_mint_0, pool_state.token_mint_1);
+            require!(
+                pool_mints == (mint, output_mint) || pool_mints == (output_mint, mint),
+                InteractDappError::InvalidOrderConfig
+            );
+        }
+        let lending = Lending::load(&self.lending, self.lending_program.key)?;
+        require_keys_eq!(lending.mint, mint, InteractDappError::InvalidOrderConfig);
+        require_keys_eq!(
+            lending.f_token_mint,
+            self.f_token_mint.key(),
+            InteractDappError::InvalidOrderConfig
+        );
+        let assets = convert_to_assets(shares, lending.token_exchange_price)
+            .ok_or(InteractDappError::MathOverflow)?;
+        self.user_deposits
+            .initialize(mint, self.owner.key(), bumps.user_deposits);
+        self.deposit_caps
+            .check_deposit(&self.user_deposits, assets, lending.token_exchange_price)?;
+
+        token_interface::transfer_checked(
+            CpiContext::new(
+                self.token_program.to_account_info(),
+                TransferChecked {
+                    from: self.owner_f_token_account.to_account_info(),
+                    mint: self.f_token_mint.to_account_info(),
+                    to: self.f_token_vault.to_account_info(),
+                    authority: self.owner.to_account_info(),
+                },
+            ),
+            shares,
+            self.f_token_mint.decimals,
+        )?;
+        self.deposit_caps
+            .add_shares(&mut self.user_deposits, shares)?;
+
+        self.trigger_order.set_inner(TriggerOrder {
+            owner: self.owner.key(),
+            order_id,
+            mint,
+            f_token_mint: self.f_token_mint.key(),
+            lending: self.lending.key(),
+            output_mint,
+            pool_state: self.pool_state.key(),
+            kind,
+            trigger_tick,
+            twap_window,
+            max_slippage_bps,
+            executed: false,
+            bump: bumps.trigger_order,
+        });
+        Ok(())
+    }
+}
@@ -1,11 +1,21 @@
 use anchor_lang::prelude::*;
 pub mod error;
 pub mod constant;
+pub mod events;
 pub mod instructions;
+pub mod math;
+pub mod programs;
+pub mod states;
+pub mod utils;
 use crate::instructions::*;
+use crate::math::{EarnApy, EarnPositionPreview, PositionHealth};
+use crate::states::TriggerKind;
 
 declare_id!("DC2y62K2opFJ21AMZwcYG7HDaNfUTU4YZszpnpG18r61");
 
+// Typed bindings for the Jupiter lending program, generated from idls/jupiter_lend.json
+declare_program!(jupiter_lend);
+
 #[program]
 pub mod interact_dapp {
     use super::*;
@@ -15,6 +25,12 @@ pub mod interact_dapp {
     pub fn withdraw_earn(ctx: Context<WithdrawParams>, assets: u64) -> Result<()> {
         ctx.accounts.withdraw_earn(assets)
     }
+    pub fn withdraw_earn_max_available(ctx: Context<WithdrawParams>, assets: u64) -> Result<u64> {
+        ctx.accounts.withdraw_earn_max_available(assets)
+    }
+    pub fn claim_withdrawal(ctx: Context<ClaimWithdrawalParams>) -> Result<()> {
+        ctx.accounts.claim_withdrawal()
+    }
     pub fn proxy_swap<'a, 'b, 'c: 'info, 'info>(
         ctx: Context<'a, 'b, 'c, 'info, ProxySwap<'info>>,
         amount: u64,
@@ -30,4 +46,226 @@ pub mod interact_dapp {
             is_base_input,
         )
     }
+    pub fn position_health<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, PositionHealthParams<'info>>,
+        remaining_accounts_indices: Vec<u8>,
+    ) -> Result<PositionHealth> {
+        instructions::position_health(ctx, remaining_accounts_indices)
+    }
+    pub fn preview_earn_position(
+        ctx: Context<PreviewEarnPositionParams>,
+        principal: u64,
+        deposit_assets: u64,
+        withdraw_assets: u64,
+    ) -> Result<EarnPositionPreview> {
+        ctx.accounts
+            .preview_earn_position(principal, deposit_assets, withdraw_assets)
+    }
+    pub fn earn_apy(ctx: Context<EarnApyParams>) -> Result<EarnApy> {
+        ctx.accounts.earn_apy()
+    }
+    pub fn initialize_strategy(
+        ctx: Context<InitializeStrategy>,
+        compound_interval: i64,
+        max_slippage_bps: u16,
+    ) -> Result<()> {
+        ctx.accounts
+            .initialize_strategy(&ctx.bumps, compound_interval, max_slippage_bps)
+    }
+    #[allow(clippy::too_many_arguments)]
+    pub fn open_strategy_position<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, OpenStrategyPosition<'info>>,
+        tick_lower_index: i32,
+        tick_upper_index: i32,
+        tick_array_lower_start_index: i32,
+        tick_array_upper_start_index: i32,
+        liquidity: u128,
+        base_amount_max: u64,
+        pair_amount_max: u64,
+    ) -> Result<()> {
+        instructions::open_strategy_position(
+            ctx,
+            tick_lower_index,
+            tick_upper_index,
+            tick_array_lower_start_index,
+            tick_array_upper_start_index,
+            liquidity,
+            base_amount_max,
+            pair_amount_max,
+        )
+    }
+    pub fn deposit_strategy(ctx: Context<DepositStrategy>, amount: u64) -> Result<()> {
+        ctx.accounts.deposit_strategy(&ctx.bumps, amount)
+    }
+    pub fn withdraw_strategy<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, WithdrawStrategy<'info>>,
+        shares: u64,
+    ) -> Result<()> {
+        instructions::withdraw_strategy(ctx, shares)
+    }
+    pub fn crank_compound<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, CrankCompound<'info>>,
+    ) -> Result<()> {
+        instructions::crank_compound(ctx)
+    }
+    pub fn set_rebalance_config(
+        ctx: Context<SetRebalanceConfig>,
+        range_width: i32,
+        twap_window: u32,
+        max_tick_deviation: u32,
+    ) -> Result<()> {
+        ctx.accounts
+            .set_rebalance_config(range_width, twap_window, max_tick_deviation)
+    }
+    pub fn rebalance_range<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, RebalanceRange<'info>>,
+        remaining_accounts_indices: Vec<u8>,
+    ) -> Result<()> {
+        instructions::rebalance_range(ctx, remaining_accounts_indices)
+    }
+    pub fn open_dca_order(
+        ctx: Context<OpenDcaOrder>,
+        order_id: u64,
+        amount: u64,
+        amount_per_interval: u64,
+        min_output_per_interval: u64,
+        interval: i64,
+        tip_lamports: u64,
+    ) -> Result<()> {
+        ctx.accounts.open_dca_order(
+            &ctx.bumps,
+            order_id,
+            amount,
+            amount_per_interval,
+            min_output_per_interval,
+            interval,
+            tip_lamports,
+        )
+    }
+    pub fn execute_dca<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, ExecuteDca<'info>>,
+        remaining_accounts_indices: Vec<u8>,
+    ) -> Result<()> {
+        instructions::execute_dca(ctx, remaining_accounts_indices)
+    }
+    pub fn close_dca_order(ctx: Context<CloseDcaOrder>) -> Result<()> {
+        ctx.accounts.close_dca_order()
+    }
+    pub fn open_limit_order<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, OpenLimitOrder<'info>>,
+        order_id: u64,
+        amount: u64,
+        sqrt_price_limit_x64: u128,
+    ) -> Result<()> {
+        instructions::open_limit_order(ctx, order_id, amount, sqrt_price_limit_x64)
+    }
+    pub fn fill_limit_order<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, FillLimitOrder<'info>>,
+        remaining_accounts_indices: Vec<u8>,
+    ) -> Result<()> {
+        instructions::fill_limit_order(ctx, remaining_accounts_indices)
+    }
+    pub fn cancel_limit_order(ctx: Context<CancelLimitOrder>) -> Result<()> {
+        ctx.accounts.cancel_limit_order()
+    }
+    pub fn open_trigger_order(
+        ctx: Context<OpenTriggerOrder>,
+        order_id: u64,
+        shares: u64,
+        kind: TriggerKind,
+        trigger_tick: i32,
+        twap_window: u32,
+        max_slippage_bps: u16,
+    ) -> Result<()> {
+        ctx.accounts.open_trigger_order(
+            &ctx.bumps,
+            order_id,
+            shares,
+            kind,
+            trigger_tick,
+            twap_window,
+            max_slippage_bps,
+        )
+    }
+    pub fn execute_trigger<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, ExecuteTrigger<'info>>,
+    ) -> Result<()> {
+        instructions::execute_trigger(ctx)
+    }
+    pub fn cancel_trigger_order(ctx: Context<CancelTriggerOrder>) -> Result<()> {
+        ctx.accounts.cancel_trigger_order()
+    }
+    pub fn create_operator_delegation(
+        ctx: Context<CreateOperatorDelegation>,
+        permissions: u8,
+        max_amount_per_period: u64,
+        period: i64,
+        expires_at: i64,
+    ) -> Result<()> {
+        ctx.accounts.create_operator_delegation(
+            &ctx.bumps,
+            permissions,
+            max_amount_per_period,
+            period,
+            expires_at,
+        )
+    }
+    pub fn revoke_operator_delegation(ctx: Context<RevokeOperatorDelegation>) -> Result<()> {
+        ctx.accounts.revoke_operator_delegation()
+    }
+    pub fn operator_deposit_earn(ctx: Context<OperatorDepositEarn>, amount: u64) -> Result<()> {
+        ctx.accounts.operator_deposit_earn(amount)
+    }
+    pub fn operator_withdraw_earn(ctx: Context<OperatorWithdrawEarn>, assets: u64) -> Result<()> {
+        ctx.accounts.operator_withdraw_earn(assets)
+    }
+    pub fn initialize_config(ctx: Context<InitializeConfig>, admin: Pubkey) -> Result<()> {
+        ctx.accounts.initialize_config(&ctx.bumps, admin)
+    }
+    pub fn initialize_deposit_caps(
+        ctx: Context<InitializeDepositCaps>,
+        global_cap: u64,
+        per_user_cap: u64,
+    ) -> Result<()> {
+        ctx.accounts
+            .initialize_deposit_caps(&ctx.bumps, global_cap, per_user_cap)
+    }
+    pub fn update_deposit_caps(
+        ctx: Context<UpdateDepositCaps>,
+        global_cap: u64,
+        per_user_cap: u64,
+    ) -> Result<()> {
+        ctx.accounts.update_deposit_caps(global_cap, per_user_cap)
+    }
+    pub fn update_config(
+        ctx: Context<UpdateConfig>,
+        paused: Option<bool>,
+        fee_bps: Option<u16>,
+    ) -> Result<()> {
+        ctx.accounts.update_config(paused, fee_bps)
+    }
+    pub fn initialize_allocator(
+        ctx: Context<InitializeAllocator>,
+        keeper: Pubkey,
+        min_apy_gap: u64,
+        max_slippage_bps: u16,
+    ) -> Result<()> {
+        ctx.accounts
+            .initialize_allocator(&ctx.bumps, keeper, min_apy_gap, max_slippage_bps)
+    }
+    pub fn add_allocator_market(ctx: Context<AddAllocatorMarket>) -> Result<()> {
+        ctx.accounts.add_allocator_market()
+    }
+    pub fn allocator_deposit(ctx: Context<AllocatorDeposit>, amount: u64) -> Result<()> {
+        ctx.accounts.allocator_deposit(&ctx.bumps, amount)
+    }
+    pub fn allocator_withdraw(ctx: Context<AllocatorWithdraw>, assets: u64) -> Result<()> {
+        ctx.accounts.allocator_withdraw(assets)
+    }
+    pub fn reallocate<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, Reallocate<'info>>,
+        amount: u64,
+    ) -> Result<()> {
+        instructions::reallocate(ctx, amount)
+    }
 }
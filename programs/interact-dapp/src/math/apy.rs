@@ -0,0 +1,102 @@
+use anchor_lang::prelude::*;
+use crate::states::{Lending, LendingRewardsRateModel, TokenReserve};
+use super::{convert_to_assets, BPS_PRECISION, REWARDS_RATE_PRECISION, SECONDS_PER_YEAR};
+
+/// 100% in the precision of the rates below, the same as the lending rewards rate
+pub const RATE_PRECISION: u128 = REWARDS_RATE_PRECISION;
+
+/// Terms of the exp series used to compound a rate per second
+const COMPOUNDING_TERMS: u128 = 8;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default, PartialEq)]
+pub struct EarnApy {
+    /// Rate paid by the liquidity layer borrowers to suppliers
+    pub supply_apr: u64,
+    /// Rate paid by the rewards rate model on the lending TVL
+    pub rewards_apr: u64,
+    /// `supply_apr` compounded per second
+    pub supply_apy: u64,
+    /// `supply_apr + rewards_apr` compounded per second
+    pub total_apy: u64,
+    /// Underlying value of all fTokens of the lending market
+    pub total_assets: u64,
+}
+
+/// Supply APR of a liquidity layer reserve: the borrow rate on the utilized part of the
+/// supply, minus the protocol fee on interest. `borrow_rate`, `last_utilization` and
+/// `fee_on_interest` all use 1e4 precision.
+pub fn get_supply_apr(reserve: &TokenReserve) -> Option<u64> {
+    let fee_factor = BPS_PRECISION.checked_sub(reserve.fee_on_interest as u128)?;
+    let apr = (reserve.borrow_rate as u128)
+        .checked_mul(reserve.last_utilization as u128)?
+        .checked_mul(fee_factor)?
+        .checked_mul(RATE_PRECISION)?
+        / (BPS_PRECISION * BPS_PRECISION * BPS_PRECISION);
+    u64::try_from(apr).ok()
+}
+
+/// Yearly reward of the rewards rate model at `now`. Once the current program ends, the
+/// queued `next_reward_amount` is paid over `next_duration`.
+fn get_yearly_reward(model: &LendingRewardsRateModel, now: u64) -> Option<u128> {
+    if model.start_time == 0 || now < model.start_time {
+        return Some(0);
+    }
+    let end_time = model.start_time.checked_add(model.duration)?;
+    if now <= end_time {
+        return Some(model.yearly_reward as u128);
+    }
+    if model.next_duration > 0 && now <= end_time.checked_add(model.next_duration)? {
+        return Some(
+            (model.next_reward_amount as u128).checked_mul(SECONDS_PER_YEAR)?
+                / model.next_duration as u128,
+        );
+    }
+    Some(0)
+}
+
+/// Rewards APR paid on `total_assets`, in `REWARDS_RATE_PRECISION`. No rewards are paid
+/// below `start_tvl`.
+pub fn get_rewards_rate(
+    model: &LendingRewardsRateModel,
+    total_assets: u64,
+    now: i64,
+) -> Option<u64> {
+    if total_assets == 0 || total_assets < model.start_tvl {
+        return Some(0);
+    }
+    let yearly_reward = get_yearly_reward(model, now.max(0) as u64)?;
+    let rate = yearly_reward.checked_mul(RATE_PRECISION)? / total_assets as u128;
+    u64::try_from(rate).ok()
+}
+
+/// `e^apr - 1`, the yield of `apr` compounded per second over a year.
+pub fn compound_apr(apr: u64) -> Option<u64> {
+    let apr = apr as u128;
+    let mut term = RATE_PRECISION;
+    let mut apy = 0u128;
+    for k in 1..=COMPOUNDING_TERMS {
+        term = term.checked_mul(apr)? / RATE_PRECISION / k;
+        apy = apy.checked_add(term)?;
+    }
+    u64::try_from(apy).ok()
+}
+
+/// Current APRs and APYs of a Jupiter Earn lending market with `f_token_supply` fTokens.
+pub fn get_earn_apy(
+    lending: &Lending,
+    reserve: &TokenReserve,
+    model: &LendingRewardsRateModel,
+    f_token_supply: u64,
+    now: i64,
+) -> Option<EarnApy> {
+    let total_assets = convert_to_assets(f_token_supply, lending.token_exchange_price)?;
+    let supply_apr = get_supply_apr(reserve)?;
+    let rewards_apr = get_rewards_rate(model, total_assets, now)?;
+    Some(EarnApy {
+        supply_apr,
+        rewards_apr,
+        supply_apy: compound_apr(supply_apr)?,
+        total_apy: compound_apr(supply_apr.checked_add(rewards_apr)?)?,
+        total_assets,
+    })
+}
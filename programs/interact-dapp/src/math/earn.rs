@@ -0,0 +1,182 @@
+use anchor_lang::prelude::*;
+use crate::states::{Lending, TokenReserve, UserSupplyPosition};
+use super::{BPS_PRECISION, EXCHANGE_PRICES_PRECISION, SECONDS_PER_YEAR};
+
+/// 100% in the precision used to grow the fToken exchange price
+pub const RETURN_PERCENT_PRECISION: u128 = 100_000_000_000_000;
+/// 100% in the precision of the lending rewards rate
+pub const REWARDS_RATE_PRECISION: u128 = 1_000_000_000_000;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default, PartialEq)]
+pub struct EarnPositionPreview {
+    /// fToken balance of the position
+    pub shares: u64,
+    /// Underlying value of `shares` at the up-to-date exchange price
+    pub asset_value: u64,
+    /// `asset_value` above the provided principal
+    pub accrued_yield: u64,
+    pub token_exchange_price: u64,
+    pub liquidity_exchange_price: u64,
+    /// fTokens minted for depositing `deposit_assets` (previewDeposit)
+    pub deposit_shares: u64,
+    /// fTokens burned for withdrawing `withdraw_assets` (previewWithdraw)
+    pub withdraw_shares: u64,
+}
+
+fn elapsed(now: i64, last_update_timestamp: u64) -> u128 {
+    (now.max(0) as u64).saturating_sub(last_update_timestamp) as u128
+}
+
+/// Supply exchange price of the liquidity layer, accrued up to `now`.
+pub fn get_liquidity_supply_exchange_price(reserve: &TokenReserve, now: i64) -> Option<u64> {
+    let supply_exchange_price = reserve.supply_exchange_price as u128;
+    let seconds = elapsed(now, reserve.last_update_timestamp);
+    if seconds == 0 || reserve.borrow_rate == 0 {
+        return Some(reserve.supply_exchange_price);
+    }
+
+    let supply_with_interest = (reserve.total_supply_with_interest as u128)
+        .checked_mul(supply_exchange_price)?
+        / EXCHANGE_PRICES_PRECISION;
+    if supply_with_interest == 0 {
+        return Some(reserve.supply_exchange_price);
+    }
+    let borrow_with_interest = (reserve.total_borrow_with_interest as u128)
+        .checked_mul(reserve.borrow_exchange_price as u128)?
+        / EXCHANGE_PRICES_PRECISION;
+
+    // interest paid by borrowers, minus the protocol fee, goes to suppliers with interest
+    let interest = borrow_with_interest
+        .checked_mul(reserve.borrow_rate as u128)?
+        .checked_mul(seconds)?
+        / (SECONDS_PER_YEAR * BPS_PRECISION);
+    let supplier_interest = interest
+        .checked_mul(BPS_PRECISION.checked_sub(reserve.fee_on_interest as u128)?)?
+        / BPS_PRECISION;
+    let increase = supply_exchange_price.checked_mul(supplier_interest)? / supply_with_interest;
+    u64::try_from(supply_exchange_price.checked_add(increase)?).ok()
+}
+
+/// fToken exchange price, grown by the liquidity yield and the rewards rate since the
+/// last update of the `Lending` account.
+pub fn get_token_exchange_price(
+    lending: &Lending,
+    liquidity_exchange_price: u64,
+    rewards_rate: u64,
+    now: i64,
+) -> Option<u64> {
+    let old_token_exchange_price = lending.token_exchange_price as u128;
+    let old_liquidity_exchange_price = lending.liquidity_exchange_price as u128;
+    let seconds = elapsed(now, lending.last_update_timestamp);
+
+    let mut total_return = (rewards_rate as u128)
+        .checked_mul(seconds)?
+        .checked_mul(RETURN_PERCENT_PRECISION / REWARDS_RATE_PRECISION)?
+        / SECONDS_PER_YEAR;
+    let liquidity_exchange_price = liquidity_exchange_price as u128;
+    if liquidity_exchange_price > old_liquidity_exchange_price && old_liquidity_exchange_price > 0 {
+        total_return = total_return.checked_add(
+            (liquidity_exchange_price - old_liquidity_exchange_price)
+                .checked_mul(RETURN_PERCENT_PRECISION)?
+                / old_liquidity_exchange_price,
+        )?;
+    }
+    let increase =
+        old_token_exchange_price.checked_mul(total_return)? / RETURN_PERCENT_PRECISION;
+    u64::try_from(old_token_exchange_price.checked_add(increase)?).ok()
+}
+
+pub fn convert_to_assets(shares: u64, token_exchange_price: u64) -> Option<u64> {
+    let assets =
+        (shares as u128).checked_mul(token_exchange_price as u128)? / EXCHANGE_PRICES_PRECISION;
+    u64::try_from(assets).ok()
+}
+
+pub fn convert_to_shares(assets: u64, token_exchange_price: u64) -> Option<u64> {
+    let shares = (assets as u128).checked_mul(EXCHANGE_PRICES_PRECISION)?
+        / token_exchange_price as u128;
+    u64::try_from(shares).ok()
+}
+
+/// Shares minted for a deposit of `assets`, rounded down like the fToken does.
+pub fn preview_deposit(assets: u64, token_exchange_price: u64) -> Option<u64> {
+    convert_to_shares(assets, token_exchange_price)
+}
+
+/// Shares burned for a withdrawal of `assets`, rounded up like the fToken does.
+pub fn preview_withdraw(assets: u64, token_exchange_price: u64) -> Option<u64> {
+    let numerator = (assets as u128).checked_mul(EXCHANGE_PRICES_PRECISION)?;
+    let shares = numerator.div_ceil(token_exchange_price as u128);
+    u64::try_from(shares).ok()
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn preview_earn_position(
+    lending: &Lending,
+    reserve: &TokenReserve,
+    shares: u64,
+    principal: u64,
+    deposit_assets: u64,
+    withdraw_assets: u64,
+    rewards_rate: u64,
+    now: i64,
+) -> Option<EarnPositionPreview> {
+    let liquidity_exchange_price = get_liquidity_supply_exchange_price(reserve, now)?;
+    let token_exchange_price =
+        get_token_exchange_price(lending, liquidity_exchange_price, rewards_rate, now)?;
+    if token_exchange_price == 0 {
+        return None;
+    }
+    let asset_value = convert_to_assets(shares, token_exchange_price)?;
+
+    Some(EarnPositionPreview {
+        shares,
+        asset_value,
+        accrued_yield: asset_value.saturating_sub(principal),
+        token_exchange_price,
+        liquidity_exchange_price,
+        deposit_shares: preview_deposit(deposit_assets, token_exchange_price)?,
+        withdraw_shares: preview_withdraw(withdraw_assets, token_exchange_price)?,
+    })
+}
+
+/// Withdrawal limit of a liquidity layer supply position at `now`, in raw position units.
+/// The limit shrinks linearly from the last stored limit by `expand_pct` of the supply
+/// per `expand_duration`, down to `supply * (1 - expand_pct)`. Positions below
+/// `base_withdrawal_limit` can be fully withdrawn.
+pub fn get_withdrawal_limit(position: &UserSupplyPosition, now: i64) -> Option<u128> {
+    let user_supply = position.amount as u128;
+    let last_limit = position.withdrawal_limit;
+    if last_limit == 0 || user_supply < position.base_withdrawal_limit as u128 {
+        return Some(0);
+    }
+    // expand_pct uses the same 1e4 precision as basis points
+    let max_expansion = user_supply.checked_mul(position.expand_pct as u128)? / BPS_PRECISION;
+    let seconds = elapsed(now, position.last_update);
+    let expanded = if position.expand_duration == 0 {
+        max_expansion
+    } else {
+        max_expansion.checked_mul(seconds)? / position.expand_duration as u128
+    };
+    let min_limit = user_supply.saturating_sub(max_expansion);
+    Some(last_limit.saturating_sub(expanded).max(min_limit))
+}
+
+/// Underlying amount withdrawable right now from a liquidity layer supply position, bound by
+/// its withdrawal limit and by the tokens held in the liquidity `vault`.
+pub fn get_withdrawable_amount(
+    position: &UserSupplyPosition,
+    reserve: &TokenReserve,
+    vault_balance: u64,
+    now: i64,
+) -> Option<u64> {
+    let limit = get_withdrawal_limit(position, now)?;
+    let raw = (position.amount as u128).saturating_sub(limit);
+    let withdrawable = if position.with_interest != 0 {
+        let supply_exchange_price = get_liquidity_supply_exchange_price(reserve, now)?;
+        raw.checked_mul(supply_exchange_price as u128)? / EXCHANGE_PRICES_PRECISION
+    } else {
+        raw
+    };
+    Some(u64::try_from(withdrawable).unwrap_or(u64::MAX).min(vault_balance))
+}
@@ -0,0 +1,14 @@
+pub mod apy;
+pub mod earn;
+pub mod strategy;
+pub mod vault_health;
+
+pub use apy::*;
+pub use earn::*;
+pub use strategy::*;
+pub use vault_health::*;
+
+/// Precision of the Jupiter liquidity, lending and vault exchange prices
+pub const EXCHANGE_PRICES_PRECISION: u128 = 1_000_000_000_000;
+pub const BPS_PRECISION: u128 = 10_000;
+pub const SECONDS_PER_YEAR: u128 = 31_536_000;
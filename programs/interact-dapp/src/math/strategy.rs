@@ -0,0 +1,235 @@
+use anchor_lang::prelude::*;
+use raydium_amm_v3::libraries::{big_num::U256, liquidity_math, tick_math};
+use raydium_amm_v3::states::{ObservationState, PersonalPositionState};
+use crate::error::InteractDappError;
+use super::{BPS_PRECISION, EXCHANGE_PRICES_PRECISION};
+
+/// Values `amount` of token_0 in token_1 at `sqrt_price_x64`.
+pub fn token_0_to_token_1(amount: u64, sqrt_price_x64: u128) -> Option<u64> {
+    let sqrt_price = U256::from(sqrt_price_x64);
+    let value = ((U256::from(amount) * sqrt_price) >> 64) * sqrt_price >> 64;
+    (value <= U256::from(u64::MAX)).then(|| value.as_u64())
+}
+
+/// Values `amount` of token_1 in token_0 at `sqrt_price_x64`.
+pub fn token_1_to_token_0(amount: u64, sqrt_price_x64: u128) -> Option<u64> {
+    let sqrt_price = U256::from(sqrt_price_x64);
+    let price_x128 = sqrt_price * sqrt_price;
+    if price_x128.is_zero() {
+        return None;
+    }
+    let value = (U256::from(amount) << 128) / price_x128;
+    (value <= U256::from(u64::MAX)).then(|| value.as_u64())
+}
+
+/// Values `amount` of the pool's other token in the base token at `sqrt_price_x64`.
+pub fn pair_to_base(amount: u64, sqrt_price_x64: u128, base_is_token_0: bool) -> Option<u64> {
+    if base_is_token_0 {
+        token_1_to_token_0(amount, sqrt_price_x64)
+    } else {
+        token_0_to_token_1(amount, sqrt_price_x64)
+    }
+}
+
+/// Values `amount` of the base token in the pool's other token at `sqrt_price_x64`.
+pub fn base_to_pair(amount: u64, sqrt_price_x64: u128, base_is_token_0: bool) -> Option<u64> {
+    if base_is_token_0 {
+        token_0_to_token_1(amount, sqrt_price_x64)
+    } else {
+        token_1_to_token_0(amount, sqrt_price_x64)
+    }
+}
+
+/// Minimum amount out once `slippage_bps` is taken off `amount`.
+pub fn apply_slippage(amount: u64, slippage_bps: u16) -> u64 {
+    let kept = BPS_PRECISION.saturating_sub(slippage_bps as u128);
+    (amount as u128 * kept / BPS_PRECISION) as u64
+}
+
+/// Shares and assets added to the strategy totals when pricing a share. A first depositor
+/// cannot inflate the share price by donating to the vault: all but a
+/// `1 / STRATEGY_VIRTUAL_AMOUNT` fraction of the donation accrues to the virtual shares.
+pub const STRATEGY_VIRTUAL_AMOUNT: u64 = 1_000;
+
+/// Shares minted for `assets`, rounded down so existing holders are never diluted.
+pub fn shares_for_deposit(assets: u64, total_shares: u64, total_value: u64) -> Option<u64> {
+    let shares = (assets as u128)
+        .checked_mul(total_shares as u128 + STRATEGY_VIRTUAL_AMOUNT as u128)?
+        / (total_value as u128 + STRATEGY_VIRTUAL_AMOUNT as u128);
+    u64::try_from(shares).ok()
+}
+
+/// Assets owed for burning `shares`, rounded down in favour of the remaining holders and
+/// never above `total_value`.
+pub fn assets_for_shares(shares: u64, total_shares: u64, total_value: u64) -> Option<u64> {
+    if total_shares == 0 {
+        return None;
+    }
+    let assets = (shares as u128)
+        .checked_mul(total_value as u128 + STRATEGY_VIRTUAL_AMOUNT as u128)?
+        / (total_shares as u128 + STRATEGY_VIRTUAL_AMOUNT as u128);
+    u64::try_from(assets.min(total_value as u128)).ok()
+}
+
+/// Share of the position `liquidity` backing `shares`, rounded down.
+pub fn liquidity_for_shares(liquidity: u128, shares: u64, total_shares: u64) -> Option<u128> {
+    if total_shares == 0 {
+        return None;
+    }
+    let amount = U256::from(liquidity) * U256::from(shares) / U256::from(total_shares);
+    (amount <= U256::from(liquidity)).then(|| amount.as_u128())
+}
+
+/// Token amounts held by a CLMM position at `sqrt_price_x64`, including fees already owed
+/// to it. `tick` is the tick of `sqrt_price_x64`.
+pub fn get_position_token_amounts(
+    position: &PersonalPositionState,
+    tick: i32,
+    sqrt_price_x64: u128,
+) -> Result<(u64, u64)> {
+    let (amount_0, amount_1) = if position.liquidity == 0 {
+        (0, 0)
+    } else {
+        liquidity_math::get_delta_amounts_signed(
+            tick,
+            sqrt_price_x64,
+            position.tick_lower_index,
+            position.tick_upper_index,
+            -(position.liquidity as i128),
+        )?
+    };
+    Ok((
+        amount_0.saturating_add(position.token_fees_owed_0),
+        amount_1.saturating_add(position.token_fees_owed_1),
+    ))
+}
+
+pub struct StrategyHoldings {
+    pub base_idle: u64,
+    pub pair_idle: u64,
+    pub f_token_balance: u64,
+    pub token_exchange_price: u64,
+    /// (token_0, token_1) held by the CLMM position
+    pub position_amounts: (u64, u64),
+}
+
+/// Total value of the strategy in the base token, pricing the pair token at `sqrt_price_x64`.
+pub fn get_strategy_total_value(
+    holdings: &StrategyHoldings,
+    sqrt_price_x64: u128,
+    base_is_token_0: bool,
+) -> Option<u64> {
+    let earn_value = (holdings.f_token_balance as u128)
+        .checked_mul(holdings.token_exchange_price as u128)?
+        / EXCHANGE_PRICES_PRECISION;
+    let (position_base, position_pair) = if base_is_token_0 {
+        holdings.position_amounts
+    } else {
+        (holdings.position_amounts.1, holdings.position_amounts.0)
+    };
+    let pair_value = pair_to_base(
+        holdings.pair_idle.checked_add(position_pair)?,
+        sqrt_price_x64,
+        base_is_token_0,
+    )?;
+
+    let total = (holdings.base_idle as u128)
+        .checked_add(position_base as u128)?
+        .checked_add(pair_value as u128)?
+        .checked_add(earn_value)?;
+    u64::try_from(total).ok()
+}
+
+/// Tick range of `width` ticks aligned to `tick_spacing` and centered on `tick_current`.
+pub fn get_centered_range(tick_current: i32, tick_spacing: u16, width: i32) -> Option<(i32, i32)> {
+    let tick_spacing = tick_spacing as i32;
+    if tick_spacing == 0 || width < 2 * tick_spacing || width % tick_spacing != 0 {
+        return None;
+    }
+    let tick_lower = (tick_current - width / 2).div_euclid(tick_spacing) * tick_spacing;
+    let tick_upper = tick_lower.checked_add(width)?;
+    (tick_lower >= tick_math::MIN_TICK && tick_upper <= tick_math::MAX_TICK)
+        .then_some((tick_lower, tick_upper))
+}
+
+/// Share of a position's value held in the base token, as `(numerator, denominator)`.
+///
+/// Per unit of liquidity the position holds `(sqrt_upper - sqrt) / (sqrt * sqrt_upper)` of
+/// token_0 and `sqrt - sqrt_lower` of token_1, so valued in token_1 the two legs weigh
+/// `(sqrt_upper - sqrt) * sqrt / sqrt_upper` and `sqrt - sqrt_lower`.
+pub fn get_base_value_share(
+    sqrt_price_lower_x64: u128,
+    sqrt_price_x64: u128,
+    sqrt_price_upper_x64: u128,
+    base_is_token_0: bool,
+) -> Option<(U256, U256)> {
+    let sqrt_price = sqrt_price_x64.clamp(sqrt_price_lower_x64, sqrt_price_upper_x64);
+    let value_0 = U256::from(sqrt_price_upper_x64 - sqrt_price)
+        .checked_mul(U256::from(sqrt_price))?
+        .checked_div(U256::from(sqrt_price_upper_x64))?;
+    let value_1 = U256::from(sqrt_price - sqrt_price_lower_x64);
+    let total = value_0.checked_add(value_1)?;
+    if total.is_zero() {
+        return None;
+    }
+    Some((if base_is_token_0 { value_0 } else { value_1 }, total))
+}
+
+/// Average tick of the pool over the last `window` seconds. The tick cumulative at the
+/// start of the window is interpolated between the last observation at or before it and the
+/// following one, the most recent observation being extended to `now` with the current
+/// tick. Fails with `StaleOracle` when the observations do not reach back to the start of
+/// the window, as the pool could then have been moved just before the first one.
+pub fn get_twap_tick(
+    observation_state: &ObservationState,
+    tick_current: i32,
+    now: u32,
+    window: u32,
+) -> Result<i32> {
+    require!(window > 0, InteractDappError::StaleOracle);
+    // copied out, the observation account is packed
+    let observations = observation_state.observations;
+    let newest_index = observation_state.observation_index as usize;
+    let newest = *observations
+        .get(newest_index)
+        .ok_or(InteractDappError::InvalidAccountData)?;
+    let (newest_timestamp, newest_cumulative) = (newest.block_timestamp, newest.tick_cumulative);
+    let cumulative_now = now
+        .checked_sub(newest_timestamp)
+        .and_then(|elapsed| (tick_current as i64).checked_mul(elapsed as i64))
+        .and_then(|extension| newest_cumulative.checked_add(extension))
+        .ok_or(InteractDappError::MathOverflow)?;
+
+    let window_start = now.checked_sub(window).ok_or(InteractDappError::StaleOracle)?;
+    // (timestamp, tick cumulative) of the point following the visited observation
+    let mut next = (now, cumulative_now);
+    let mut start_cumulative = None;
+    for offset in 0..observations.len() {
+        let index = (newest_index + observations.len() - offset) % observations.len();
+        let observation = observations[index];
+        let (timestamp, cumulative) = (observation.block_timestamp, observation.tick_cumulative);
+        // older slots of the ring buffer are either unwritten or overwritten
+        let wrapped = offset > 0 && timestamp >= newest_timestamp;
+        if timestamp == 0 || wrapped {
+            break;
+        }
+        if timestamp <= window_start {
+            // the tick is constant between two observations, so the cumulative is linear
+            let (next_timestamp, next_cumulative) = next;
+            let delta = next_cumulative
+                .checked_sub(cumulative)
+                .and_then(|delta| delta.checked_mul((window_start - timestamp) as i64))
+                .map(|delta| delta / (next_timestamp - timestamp) as i64)
+                .ok_or(InteractDappError::MathOverflow)?;
+            start_cumulative = Some(cumulative + delta);
+            break;
+        }
+        next = (timestamp, cumulative);
+    }
+    let start_cumulative = start_cumulative.ok_or(InteractDappError::StaleOracle)?;
+    let twap = cumulative_now
+        .checked_sub(start_cumulative)
+        .ok_or(InteractDappError::MathOverflow)?
+        .div_euclid(window as i64);
+    i32::try_from(twap).map_err(|_| InteractDappError::MathOverflow.into())
+}
@@ -0,0 +1,145 @@
+use anchor_lang::prelude::*;
+use raydium_amm_v3::libraries::big_num::U256;
+use crate::states::{Position, VaultState};
+use super::{BPS_PRECISION, EXCHANGE_PRICES_PRECISION};
+
+/// Precision of the collateral/debt rate returned by the Jupiter oracle
+pub const ORACLE_PRECISION: u128 = 1_000_000_000_000_000;
+/// Precision of `collateral_factor` / `liquidation_threshold` in the vault config
+pub const FACTOR_PRECISION: u128 = 1_000;
+
+pub const MIN_TICK: i32 = -16383;
+pub const MAX_TICK: i32 = 16383;
+
+/// 1.0015 in Q48, the price step between two vault ticks
+const TICK_BASE_X48: u128 = 281_897_189_175_721;
+const ZERO_TICK_SCALED_RATIO: u128 = 1 << 48;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default, PartialEq)]
+pub struct PositionHealth {
+    pub nft_id: u32,
+    /// Collateral in vault units (1e9 decimals)
+    pub collateral: u128,
+    /// Debt in vault units (1e9 decimals)
+    pub debt: u128,
+    /// Collateral valued in the debt token
+    pub collateral_value: u128,
+    /// Collateral price in the debt token, `ORACLE_PRECISION` scaled
+    pub oracle_price: u128,
+    pub ltv_bps: u64,
+    pub collateral_factor_bps: u64,
+    pub liquidation_threshold_bps: u64,
+    /// `collateral_value * liquidation_threshold / debt`, bps scaled; `u64::MAX` without debt
+    pub health_factor_bps: u64,
+    /// `liquidation_threshold - ltv`, negative once the position is liquidatable
+    pub liquidation_distance_bps: i64,
+    /// Oracle price at which the position reaches the liquidation threshold
+    pub liquidation_price: u128,
+    /// Debt that can still be taken before hitting the collateral factor
+    pub available_borrow: u128,
+    /// The position tick was liquidated since the position was last settled by `operate`,
+    /// the amounts above are the ones from before the liquidation
+    pub is_liquidated: bool,
+}
+
+fn mul_shift_48(a: U256, b: U256) -> U256 {
+    (a * b) >> 48
+}
+
+/// Returns 1.0015^tick in Q48.
+pub fn get_ratio_at_tick(tick: i32) -> Option<u128> {
+    if !(MIN_TICK..=MAX_TICK).contains(&tick) {
+        return None;
+    }
+    let mut exponent = tick.unsigned_abs();
+    let mut base = U256::from(TICK_BASE_X48);
+    let mut ratio = U256::from(ZERO_TICK_SCALED_RATIO);
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            ratio = mul_shift_48(ratio, base);
+        }
+        base = mul_shift_48(base, base);
+        exponent >>= 1;
+    }
+    if tick < 0 {
+        ratio = (U256::from(ZERO_TICK_SCALED_RATIO) << 48) / ratio;
+    }
+    if ratio > U256::from(u128::MAX) {
+        return None;
+    }
+    Some(ratio.as_u128())
+}
+
+/// Returns the (collateral, debt) of a position in vault units.
+pub fn get_position_amounts(position: &Position, vault_state: &VaultState) -> Option<(u128, u128)> {
+    let col_raw = position.supply_amount as u128;
+    let collateral = col_raw
+        .checked_mul(vault_state.vault_supply_exchange_price as u128)?
+        / EXCHANGE_PRICES_PRECISION;
+    if position.is_supply_only_position == 1 {
+        return Some((collateral, 0));
+    }
+
+    let ratio = get_ratio_at_tick(position.tick)?;
+    let debt_raw = (U256::from(col_raw) * U256::from(ratio)) >> 48;
+    let debt_raw = debt_raw
+        .as_u128()
+        .saturating_sub(position.dust_debt_amount as u128);
+    let debt = debt_raw.checked_mul(vault_state.vault_borrow_exchange_price as u128)?
+        / EXCHANGE_PRICES_PRECISION;
+    Some((collateral, debt))
+}
+
+pub fn compute_position_health(
+    nft_id: u32,
+    collateral: u128,
+    debt: u128,
+    oracle_price: u128,
+    collateral_factor: u16,
+    liquidation_threshold: u16,
+) -> Option<PositionHealth> {
+    let collateral_value = collateral.checked_mul(oracle_price)? / ORACLE_PRECISION;
+    let collateral_factor_bps = collateral_factor as u128 * BPS_PRECISION / FACTOR_PRECISION;
+    let liquidation_threshold_bps =
+        liquidation_threshold as u128 * BPS_PRECISION / FACTOR_PRECISION;
+
+    let ltv_bps = if debt == 0 {
+        0
+    } else if collateral_value == 0 {
+        u64::MAX as u128
+    } else {
+        (debt.checked_mul(BPS_PRECISION)? / collateral_value).min(u64::MAX as u128)
+    };
+    let health_factor_bps = if debt == 0 {
+        u64::MAX as u128
+    } else {
+        (collateral_value.checked_mul(liquidation_threshold_bps)? / debt).min(u64::MAX as u128)
+    };
+    let liquidation_price = if debt == 0 || collateral == 0 || liquidation_threshold_bps == 0 {
+        0
+    } else {
+        debt.checked_mul(ORACLE_PRECISION)?
+            .checked_mul(BPS_PRECISION)?
+            / collateral.checked_mul(liquidation_threshold_bps)?
+    };
+    let available_borrow = (collateral_value.checked_mul(collateral_factor_bps)?
+        / BPS_PRECISION)
+        .saturating_sub(debt);
+
+    Some(PositionHealth {
+        nft_id,
+        collateral,
+        debt,
+        collateral_value,
+        oracle_price,
+        ltv_bps: ltv_bps as u64,
+        collateral_factor_bps: collateral_factor_bps as u64,
+        liquidation_threshold_bps: liquidation_threshold_bps as u64,
+        health_factor_bps: health_factor_bps as u64,
+        liquidation_distance_bps: (liquidation_threshold_bps as i128 - ltv_bps as i128)
+            .clamp(i64::MIN as i128, i64::MAX as i128) as i64,
+        liquidation_price,
+        available_borrow,
+        is_liquidated: false,
+    })
+}
@@ -0,0 +1,77 @@
+//! Addresses of the programs interact_dapp calls into, for the cluster selected by the
+//! `devnet` (default), `mainnet` or `localnet` feature. Every instruction checks the
+//! program accounts it is passed against them.
+use anchor_lang::prelude::*;
+
+#[cfg(all(feature = "devnet", feature = "mainnet"))]
+compile_error!("the `devnet` and `mainnet` features are mutually exclusive");
+
+#[cfg(not(any(feature = "devnet", feature = "mainnet")))]
+compile_error!("one of the `devnet` or `mainnet` features must be enabled");
+
+#[cfg(feature = "mainnet")]
+mod cluster {
+    use super::*;
+
+    pub const CLUSTER: &str = "mainnet";
+    pub const JUPITER_LENDING_PROGRAM_ID: Pubkey =
+        pubkey!("jup3YeL8QhtSx1e253b2FDvsMNC87fDrgQZivbrndc9");
+    pub const JUPITER_LIQUIDITY_PROGRAM_ID: Pubkey =
+        pubkey!("jupeiUmn818Jg1ekPURTpr4mFo29p46vygyykFJ3wZC");
+    pub const JUPITER_VAULTS_PROGRAM_ID: Pubkey =
+        pubkey!("jupr81YtYssSyPt8jbnGuiWon5f6x9TcDEFxYe3Bdzi");
+    pub const RAYDIUM_CLMM_PROGRAM_ID: Pubkey =
+        pubkey!("CAMMCzo5YL8w4VFF8KVHrK22GGUsp5VTaW7grrKgrWqK");
+}
+
+#[cfg(all(feature = "devnet", not(feature = "localnet")))]
+mod cluster {
+    use super::*;
+
+    pub const CLUSTER: &str = "devnet";
+    pub const JUPITER_LENDING_PROGRAM_ID: Pubkey =
+        pubkey!("7tjE28izRUjzmxC1QNXnNwcc4N82CNYCexf3k8mw67s3");
+    pub const JUPITER_LIQUIDITY_PROGRAM_ID: Pubkey =
+        pubkey!("5uDkCoM96pwGYhAUucvCzLfm5UcjVRuxz6gH81RnRBmL");
+    pub const JUPITER_VAULTS_PROGRAM_ID: Pubkey =
+        pubkey!("Ho32sUQ4NzuAQgkPkHuNDG3G18rgHmYtXFA8EBmqQrAu");
+    pub const RAYDIUM_CLMM_PROGRAM_ID: Pubkey =
+        pubkey!("devi51mZmdwUJGU9hjN27vEz64Gps7uUefqxg27EAtH");
+}
+
+// programs/mock-lending stands in for both the lending and the liquidity program; the
+// local tests run the devnet Raydium build
+#[cfg(feature = "localnet")]
+mod cluster {
+    use super::*;
+
+    pub const CLUSTER: &str = "localnet";
+    pub const JUPITER_LENDING_PROGRAM_ID: Pubkey =
+        pubkey!("8dyGaYwQkXrN2gRm5asDTdpxfUuUoFw5BFcozYZ3o4H");
+    pub const JUPITER_LIQUIDITY_PROGRAM_ID: Pubkey = JUPITER_LENDING_PROGRAM_ID;
+    pub const JUPITER_VAULTS_PROGRAM_ID: Pubkey =
+        pubkey!("Ho32sUQ4NzuAQgkPkHuNDG3G18rgHmYtXFA8EBmqQrAu");
+    pub const RAYDIUM_CLMM_PROGRAM_ID: Pubkey =
+        pubkey!("devi51mZmdwUJGU9hjN27vEz64Gps7uUefqxg27EAtH");
+}
+
+pub use cluster::*;
+
+const fn same_address(a: &Pubkey, b: &Pubkey) -> bool {
+    let (a, b) = (a.to_bytes(), b.to_bytes());
+    let mut i = 0;
+    while i < 32 {
+        if a[i] != b[i] {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+// `Program<'info, AmmV3>` checks against the id raydium-amm-v3 was built with, which the
+// cluster feature has to select
+const _: () = assert!(
+    same_address(&RAYDIUM_CLMM_PROGRAM_ID, &raydium_amm_v3::ID),
+    "raydium-amm-v3 is built for another cluster"
+);
@@ -0,0 +1,38 @@
+use anchor_lang::prelude::*;
+use crate::constant::ALLOCATOR_SEED;
+
+pub const MAX_ALLOCATOR_MARKETS: usize = 8;
+
+/// Treasury funds spread over allowlisted Jupiter Earn lending markets. `keeper` moves
+/// funds towards the market with the higher APY, within the bounds set by `authority`.
+#[account]
+#[derive(InitSpace)]
+pub struct Allocator {
+    pub authority: Pubkey,
+    pub keeper: Pubkey,
+
+    /// Allowlisted `Lending` accounts, the allocator holds ATAs of their mint and fToken mint
+    #[max_len(MAX_ALLOCATOR_MARKETS)]
+    pub markets: Vec<Pubkey>,
+
+    /// Minimum APY gap between two markets to move funds, in `RATE_PRECISION`
+    pub min_apy_gap: u64,
+    /// Maximum loss of a move swap against a 1:1 stablecoin price
+    pub max_slippage_bps: u16,
+
+    pub bump: u8,
+}
+
+impl Allocator {
+    pub fn is_allowlisted(&self, lending: &Pubkey) -> bool {
+        self.markets.contains(lending)
+    }
+
+    pub fn signer_seeds(&self) -> [&[u8]; 3] {
+        [
+            ALLOCATOR_SEED,
+            self.authority.as_ref(),
+            std::slice::from_ref(&self.bump),
+        ]
+    }
+}
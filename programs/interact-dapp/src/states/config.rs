@@ -0,0 +1,15 @@
+use anchor_lang::prelude::*;
+
+/// Program-wide settings. Created once by the program upgrade authority.
+#[account]
+#[derive(InitSpace)]
+pub struct Config {
+    pub admin: Pubkey,
+    /// Emergency stop, set and cleared by the admin. Stops the Earn, swap, strategy,
+    /// order, operator and allocator instructions; claims, cancellations and revocations
+    /// stay available.
+    pub paused: bool,
+    /// Protocol fee, in bps
+    pub fee_bps: u16,
+    pub bump: u8,
+}
@@ -0,0 +1,47 @@
+use anchor_lang::prelude::*;
+use crate::constant::DCA_ORDER_SEED;
+
+/// Input tokens escrowed by `owner` and swapped into `output_mint` one leg per interval
+/// by permissionless crankers.
+#[account]
+#[derive(InitSpace)]
+pub struct DcaOrder {
+    pub owner: Pubkey,
+    pub order_id: u64,
+
+    pub input_mint: Pubkey,
+    pub output_mint: Pubkey,
+    /// Raydium CLMM pool the legs are swapped on
+    pub pool_state: Pubkey,
+
+    pub amount_per_interval: u64,
+    /// Minimum output of a full leg, scaled down for a partial last leg
+    pub min_output_per_interval: u64,
+    pub interval: i64,
+    pub next_execution_timestamp: i64,
+
+    /// Lamports paid from the order to the cranker for each executed leg
+    pub tip_lamports: u64,
+    /// Jupiter Earn lending market the output is deposited into, default to keep it idle
+    pub lending: Pubkey,
+
+    pub total_input_spent: u64,
+    pub total_output_received: u64,
+
+    pub bump: u8,
+}
+
+impl DcaOrder {
+    pub fn deposits_to_earn(&self) -> bool {
+        self.lending != Pubkey::default()
+    }
+
+    pub fn signer_seeds<'a>(&'a self, order_id: &'a [u8; 8]) -> [&'a [u8]; 4] {
+        [
+            DCA_ORDER_SEED,
+            self.owner.as_ref(),
+            order_id,
+            std::slice::from_ref(&self.bump),
+        ]
+    }
+}
@@ -0,0 +1,125 @@
+use anchor_lang::prelude::*;
+use crate::error::InteractDappError;
+use crate::math::convert_to_assets;
+
+/// Value held in the program vaults of a mint, kept as Earn shares and underlying so it is
+/// priced at the current fToken exchange price.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq, InitSpace)]
+pub struct DepositLedger {
+    /// fTokens held in program vaults: strategies, allocators and Earn order escrows
+    pub f_token_shares: u64,
+    /// Underlying deposited into strategies and not yet moved to Earn, at its deposit value
+    pub strategy_assets: u64,
+}
+
+impl DepositLedger {
+    pub fn get_value(&self, token_exchange_price: u64) -> Result<u64> {
+        convert_to_assets(self.f_token_shares, token_exchange_price)
+            .and_then(|assets| assets.checked_add(self.strategy_assets))
+            .ok_or(InteractDappError::MathOverflow.into())
+    }
+
+    pub fn add_shares(&mut self, shares: u64) -> Result<()> {
+        self.f_token_shares = self
+            .f_token_shares
+            .checked_add(shares)
+            .ok_or(InteractDappError::MathOverflow)?;
+        Ok(())
+    }
+
+    /// Saturates, vaults funded before the ledger existed hold more than it counts.
+    pub fn remove_shares(&mut self, shares: u64) {
+        self.f_token_shares = self.f_token_shares.saturating_sub(shares);
+    }
+
+    pub fn add_assets(&mut self, assets: u64) -> Result<()> {
+        self.strategy_assets = self
+            .strategy_assets
+            .checked_add(assets)
+            .ok_or(InteractDappError::MathOverflow)?;
+        Ok(())
+    }
+
+    /// Saturates, strategies pay out fees and yield on top of the deposits.
+    pub fn remove_assets(&mut self, assets: u64) {
+        self.strategy_assets = self.strategy_assets.saturating_sub(assets);
+    }
+}
+
+/// Risk limits on the program vaults holding `mint` or its fTokens, and the value they hold.
+#[account]
+#[derive(InitSpace)]
+pub struct DepositCaps {
+    pub mint: Pubkey,
+    pub lending: Pubkey,
+
+    /// Maximum value held for all depositors
+    pub global_cap: u64,
+    /// Maximum value held for a single depositor, see `UserDeposits`
+    pub per_user_cap: u64,
+
+    /// Value held for all depositors
+    pub ledger: DepositLedger,
+
+    pub bump: u8,
+}
+
+impl DepositCaps {
+    /// Largest deposit accepted with the given TVL and depositor position value.
+    pub fn get_deposit_headroom(&self, tvl: u64, user_value: u64) -> u64 {
+        self.global_cap
+            .saturating_sub(tvl)
+            .min(self.per_user_cap.saturating_sub(user_value))
+    }
+
+    /// Fails unless `assets` fit under both caps.
+    pub fn check_deposit(
+        &self,
+        user_deposits: &UserDeposits,
+        assets: u64,
+        token_exchange_price: u64,
+    ) -> Result<()> {
+        let tvl = self.ledger.get_value(token_exchange_price)?;
+        let user_value = user_deposits.ledger.get_value(token_exchange_price)?;
+        require!(
+            assets <= self.get_deposit_headroom(tvl, user_value),
+            InteractDappError::DepositCapExceeded
+        );
+        Ok(())
+    }
+
+    /// Records shares minted into a program vault on behalf of `user_deposits`.
+    pub fn add_shares(&mut self, user_deposits: &mut UserDeposits, shares: u64) -> Result<()> {
+        self.ledger.add_shares(shares)?;
+        user_deposits.ledger.add_shares(shares)
+    }
+
+    /// Records shares burned or released from a program vault of `user_deposits`.
+    pub fn remove_shares(&mut self, user_deposits: &mut UserDeposits, shares: u64) {
+        self.ledger.remove_shares(shares);
+        user_deposits.ledger.remove_shares(shares);
+    }
+}
+
+/// Value the program vaults of `mint` hold for `user`, checked against
+/// `DepositCaps::per_user_cap`. Kept by the program instead of read from the user share
+/// accounts, which a depositor could spread over fresh accounts or transfer away.
+#[account]
+#[derive(InitSpace)]
+pub struct UserDeposits {
+    pub mint: Pubkey,
+    pub user: Pubkey,
+
+    pub ledger: DepositLedger,
+
+    pub bump: u8,
+}
+
+impl UserDeposits {
+    /// Sets the identity of a record created with `init_if_needed`.
+    pub fn initialize(&mut self, mint: Pubkey, user: Pubkey, bump: u8) {
+        self.mint = mint;
+        self.user = user;
+        self.bump = bump;
+    }
+}
@@ -0,0 +1,44 @@
+use anchor_lang::prelude::*;
+use crate::error::InteractDappError;
+
+// Account types generated from idls/jupiter_lend.json. The generated `Owner` is the
+// mainnet lending program, so ownership is checked against the program passed in.
+pub use crate::jupiter_lend::accounts::{
+    Lending, LendingAdmin, LendingRewardsRateModel, TokenReserve, UserSupplyPosition,
+};
+
+fn load<T: AccountDeserialize>(info: &AccountInfo, owner: &Pubkey) -> Result<T> {
+    require_keys_eq!(*info.owner, *owner, InteractDappError::InvalidAccountOwner);
+    let data = info.try_borrow_data()?;
+    T::try_deserialize(&mut &data[..]).map_err(|_| InteractDappError::InvalidAccountData.into())
+}
+
+impl Lending {
+    pub fn load(info: &AccountInfo, lending_program: &Pubkey) -> Result<Self> {
+        load(info, lending_program)
+    }
+}
+
+impl LendingAdmin {
+    pub fn load(info: &AccountInfo, lending_program: &Pubkey) -> Result<Self> {
+        load(info, lending_program)
+    }
+}
+
+impl LendingRewardsRateModel {
+    pub fn load(info: &AccountInfo, lending_program: &Pubkey) -> Result<Self> {
+        load(info, lending_program)
+    }
+}
+
+impl TokenReserve {
+    pub fn load(info: &AccountInfo, liquidity_program: &Pubkey) -> Result<Self> {
+        load(info, liquidity_program)
+    }
+}
+
+impl UserSupplyPosition {
+    pub fn load(info: &AccountInfo, liquidity_program: &Pubkey) -> Result<Self> {
+        load(info, liquidity_program)
+    }
+}
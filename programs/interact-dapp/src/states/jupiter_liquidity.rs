@@ -0,0 +1,32 @@
+use anchor_lang::prelude::*;
+use super::load_external as load;
+
+// Read-only mirror of the liquidity layer claim account. Withdrawals the liquidity layer
+// does not transfer directly are credited to it and paid out by the liquidity `claim`.
+
+fn get_user_claim_discriminator() -> [u8; 8] {
+    // discriminator = sha256("account:UserClaim")[0..8]
+    [228, 142, 195, 181, 228, 147, 32, 209]
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct UserClaim {
+    pub user: Pubkey,
+    /// Underlying amount waiting to be claimed
+    pub amount: u64,
+    pub mint: Pubkey,
+}
+
+impl UserClaim {
+    pub fn load(info: &AccountInfo, liquidity_program: &Pubkey) -> Result<Self> {
+        load(info, liquidity_program, get_user_claim_discriminator())
+    }
+
+    /// Amount waiting in a claim account, 0 if it is not created yet.
+    pub fn get_amount(info: &AccountInfo, liquidity_program: &Pubkey) -> Result<u64> {
+        if info.data_is_empty() {
+            return Ok(0);
+        }
+        Ok(Self::load(info, liquidity_program)?.amount)
+    }
+}
@@ -0,0 +1,134 @@
+use anchor_lang::prelude::*;
+use super::load_external as load;
+
+// Read-only mirrors of the Jupiter Vaults program accounts. Only the leading
+// fields we need are declared; trailing data is ignored on deserialization.
+
+fn get_vault_config_discriminator() -> [u8; 8] {
+    // discriminator = sha256("account:VaultConfig")[0..8]
+    [99, 86, 43, 216, 184, 102, 119, 77]
+}
+
+fn get_vault_state_discriminator() -> [u8; 8] {
+    // discriminator = sha256("account:VaultState")[0..8]
+    [228, 196, 82, 165, 98, 210, 235, 152]
+}
+
+fn get_position_discriminator() -> [u8; 8] {
+    // discriminator = sha256("account:Position")[0..8]
+    [170, 188, 143, 228, 122, 64, 247, 208]
+}
+
+fn get_tick_discriminator() -> [u8; 8] {
+    // discriminator = sha256("account:Tick")[0..8]
+    [176, 94, 67, 247, 133, 173, 7, 115]
+}
+
+fn get_oracle_discriminator() -> [u8; 8] {
+    // discriminator = sha256("account:Oracle")[0..8]
+    [139, 194, 131, 179, 140, 179, 229, 244]
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct VaultConfig {
+    pub vault_id: u16,
+    pub supply_rate_magnifier: i16,
+    pub borrow_rate_magnifier: i16,
+    /// 800 = 80%
+    pub collateral_factor: u16,
+    /// 800 = 80%
+    pub liquidation_threshold: u16,
+    pub liquidation_max_limit: u16,
+    pub withdraw_gap: u16,
+    pub liquidation_penalty: u16,
+    pub borrow_fee: u16,
+    pub oracle: Pubkey,
+    pub rebalancer: Pubkey,
+    pub liquidity_program: Pubkey,
+    pub oracle_program: Pubkey,
+    pub supply_token: Pubkey,
+    pub borrow_token: Pubkey,
+    pub bump: u8,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct VaultState {
+    pub vault_id: u16,
+    pub branch_liquidated: u8,
+    pub topmost_tick: i32,
+    pub current_branch_id: u32,
+    pub total_branch_id: u32,
+    pub total_supply: u64,
+    pub total_borrow: u64,
+    pub total_positions: u32,
+    pub absorbed_debt_amount: u128,
+    pub absorbed_col_amount: u128,
+    pub absorbed_dust_debt: u64,
+    pub liquidity_supply_exchange_price: u64,
+    pub liquidity_borrow_exchange_price: u64,
+    pub vault_supply_exchange_price: u64,
+    pub vault_borrow_exchange_price: u64,
+    pub next_position_id: u32,
+    pub last_update_timestamp: u64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct Position {
+    pub vault_id: u16,
+    pub nft_id: u32,
+    pub position_mint: Pubkey,
+    pub is_supply_only_position: u8,
+    pub tick: i32,
+    pub tick_id: u32,
+    /// Raw collateral, multiply by the vault supply exchange price
+    pub supply_amount: u64,
+    /// Raw dust debt, subtracted from the debt derived from the tick
+    pub dust_debt_amount: u64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct Tick {
+    pub vault_id: u16,
+    pub tick: i32,
+    pub is_liquidated: u8,
+    pub total_ids: u32,
+    pub raw_debt: u64,
+    pub is_fully_liquidated: u8,
+    pub liquidation_branch_id: u32,
+    pub debt_factor: u64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct Oracle {
+    pub nonce: u16,
+}
+
+impl VaultConfig {
+    pub fn load(info: &AccountInfo, vaults_program: &Pubkey) -> Result<Self> {
+        load(info, vaults_program, get_vault_config_discriminator())
+    }
+}
+
+impl VaultState {
+    pub fn load(info: &AccountInfo, vaults_program: &Pubkey) -> Result<Self> {
+        load(info, vaults_program, get_vault_state_discriminator())
+    }
+}
+
+impl Position {
+    pub fn load(info: &AccountInfo, vaults_program: &Pubkey) -> Result<Self> {
+        load(info, vaults_program, get_position_discriminator())
+    }
+}
+
+impl Tick {
+    pub fn load(info: &AccountInfo, vaults_program: &Pubkey) -> Result<Self> {
+        load(info, vaults_program, get_tick_discriminator())
+    }
+}
+
+impl Oracle {
+    pub fn load(info: &AccountInfo, oracle_program: &Pubkey) -> Result<Self> {
+        load(info, oracle_program, get_oracle_discriminator())
+    }
+}
@@ -0,0 +1,53 @@
+use anchor_lang::prelude::*;
+use crate::constant::LIMIT_ORDER_SEED;
+
+/// Input tokens escrowed by `owner` until a Raydium CLMM pool trades through
+/// `sqrt_price_limit_x64`, then swapped by a keeper up to that price.
+#[account]
+#[derive(InitSpace)]
+pub struct LimitOrder {
+    pub owner: Pubkey,
+    pub order_id: u64,
+
+    pub input_mint: Pubkey,
+    pub output_mint: Pubkey,
+    pub pool_state: Pubkey,
+
+    /// Pool price the order fills at, token_1 per token_0 in Q64.64 square root
+    pub sqrt_price_limit_x64: u128,
+    /// Whether the input is the pool's token_0
+    pub zero_for_one: bool,
+
+    /// Jupiter Earn lending market the escrow sits in until fill, default to keep it idle
+    pub lending: Pubkey,
+
+    pub filled_amount_in: u64,
+    pub filled_amount_out: u64,
+
+    pub bump: u8,
+}
+
+impl LimitOrder {
+    pub fn escrows_in_earn(&self) -> bool {
+        self.lending != Pubkey::default()
+    }
+
+    /// Whether the pool has traded through the order price. Selling token_0 pushes the
+    /// price down, so the order fills while the pool is above its limit, and vice versa.
+    pub fn is_crossed(&self, sqrt_price_x64: u128) -> bool {
+        if self.zero_for_one {
+            sqrt_price_x64 > self.sqrt_price_limit_x64
+        } else {
+            sqrt_price_x64 < self.sqrt_price_limit_x64
+        }
+    }
+
+    pub fn signer_seeds<'a>(&'a self, order_id: &'a [u8; 8]) -> [&'a [u8]; 4] {
+        [
+            LIMIT_ORDER_SEED,
+            self.owner.as_ref(),
+            order_id,
+            std::slice::from_ref(&self.bump),
+        ]
+    }
+}
@@ -0,0 +1,40 @@
+use anchor_lang::prelude::*;
+use crate::error::InteractDappError;
+
+pub mod allocator;
+pub mod config;
+pub mod dca;
+pub mod deposit_caps;
+pub mod jupiter_lend;
+pub mod jupiter_liquidity;
+pub mod jupiter_vaults;
+pub mod limit_order;
+pub mod operator_delegation;
+pub mod strategy;
+pub mod trigger_order;
+
+pub use allocator::*;
+pub use config::*;
+pub use dca::*;
+pub use deposit_caps::*;
+pub use jupiter_lend::*;
+pub use jupiter_liquidity::*;
+pub use jupiter_vaults::*;
+pub use limit_order::*;
+pub use operator_delegation::*;
+pub use strategy::*;
+pub use trigger_order::*;
+
+/// Deserializes an account owned by another program, checking owner and discriminator.
+pub(crate) fn load_external<T: AnchorDeserialize>(
+    info: &AccountInfo,
+    owner: &Pubkey,
+    discriminator: [u8; 8],
+) -> Result<T> {
+    require_keys_eq!(*info.owner, *owner, InteractDappError::InvalidAccountOwner);
+    let data = info.try_borrow_data()?;
+    if data.len() < 8 || data[..8] != discriminator {
+        return Err(InteractDappError::InvalidAccountData.into());
+    }
+    T::deserialize(&mut &data[8..]).map_err(|_| InteractDappError::InvalidAccountData.into())
+}
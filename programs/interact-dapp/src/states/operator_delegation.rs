@@ -0,0 +1,72 @@
+use anchor_lang::prelude::*;
+use crate::constant::OPERATOR_DELEGATION_SEED;
+use crate::error::InteractDappError;
+
+/// Authorizes `operator` to call a subset of instructions on behalf of `owner` for one
+/// Earn lending market. Tokens are pulled from the owner through SPL delegate approvals
+/// granted to this account, the caps below are enforced on top of them.
+#[account]
+#[derive(InitSpace)]
+pub struct OperatorDelegation {
+    pub owner: Pubkey,
+    pub operator: Pubkey,
+
+    /// Underlying token of the lending market
+    pub mint: Pubkey,
+    pub f_token_mint: Pubkey,
+    pub lending: Pubkey,
+
+    /// Bitmask of `OperatorDelegation::DEPOSIT_EARN`, `OperatorDelegation::WITHDRAW_EARN`
+    pub permissions: u8,
+    /// Underlying amount the operator may move per period, across all permissions
+    pub max_amount_per_period: u64,
+    /// Length of a cap period, in seconds
+    pub period: i64,
+    pub period_start: i64,
+    pub period_spent: u64,
+    /// Timestamp after which the delegation can no longer be used
+    pub expires_at: i64,
+
+    pub bump: u8,
+}
+
+impl OperatorDelegation {
+    pub const DEPOSIT_EARN: u8 = 1 << 0;
+    pub const WITHDRAW_EARN: u8 = 1 << 1;
+    pub const ALL_PERMISSIONS: u8 = Self::DEPOSIT_EARN | Self::WITHDRAW_EARN;
+
+    /// Checks `permission` and expiry, and counts `amount` against the cap of the
+    /// current period, starting a new period when the last one has elapsed.
+    pub fn consume(&mut self, permission: u8, amount: u64, now: i64) -> Result<()> {
+        require!(
+            self.permissions & permission == permission,
+            InteractDappError::OperatorNotPermitted
+        );
+        require!(now < self.expires_at, InteractDappError::DelegationExpired);
+
+        if now >= self.period_start.saturating_add(self.period) {
+            self.period_start = now;
+            self.period_spent = 0;
+        }
+        let spent = self
+            .period_spent
+            .checked_add(amount)
+            .ok_or(InteractDappError::MathOverflow)?;
+        require!(
+            spent <= self.max_amount_per_period,
+            InteractDappError::DelegationCapExceeded
+        );
+        self.period_spent = spent;
+        Ok(())
+    }
+
+    pub fn signer_seeds(&self) -> [&[u8]; 5] {
+        [
+            OPERATOR_DELEGATION_SEED,
+            self.owner.as_ref(),
+            self.operator.as_ref(),
+            self.mint.as_ref(),
+            std::slice::from_ref(&self.bump),
+        ]
+    }
+}
@@ -0,0 +1,82 @@
+use anchor_lang::prelude::*;
+use crate::constant::STRATEGY_SEED;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub enum CompoundStep {
+    /// Collect the fees of the CLMM position into the strategy vaults
+    Harvest,
+    /// Swap the pair token balance into the base token
+    Swap,
+    /// Deposit the idle base token balance into Jupiter Earn
+    Deposit,
+}
+
+impl CompoundStep {
+    pub fn next(self) -> Self {
+        match self {
+            CompoundStep::Harvest => CompoundStep::Swap,
+            CompoundStep::Swap => CompoundStep::Deposit,
+            CompoundStep::Deposit => CompoundStep::Harvest,
+        }
+    }
+}
+
+/// A single-token vault that LPs into a Raydium CLMM range and parks the rest of its
+/// balance in Jupiter Earn. Depositors own it pro-rata through `share_mint`.
+#[account]
+#[derive(InitSpace)]
+pub struct Strategy {
+    pub authority: Pubkey,
+    pub base_mint: Pubkey,
+    pub pair_mint: Pubkey,
+    pub share_mint: Pubkey,
+
+    /// Strategy ATAs for the base, pair and fToken mints
+    pub base_vault: Pubkey,
+    pub pair_vault: Pubkey,
+    pub f_token_vault: Pubkey,
+
+    pub pool_state: Pubkey,
+    pub lending: Pubkey,
+    pub f_token_mint: Pubkey,
+
+    /// NFT mint of the Raydium personal position, default while no position is open
+    pub position_nft_mint: Pubkey,
+    pub tick_lower_index: i32,
+    pub tick_upper_index: i32,
+
+    /// Width in ticks of the range reopened by `rebalance_range`, 0 until configured
+    pub range_width: i32,
+    /// Window of the pool TWAP the strategy is valued and rebalanced at, in seconds
+    pub twap_window: u32,
+    /// Maximum distance in ticks between the pool tick and its TWAP for a deposit,
+    /// withdrawal or rebalance
+    pub max_tick_deviation: u32,
+
+    pub max_slippage_bps: u16,
+    pub compound_interval: i64,
+    pub last_compound_timestamp: i64,
+    pub next_step: CompoundStep,
+
+    pub bump: u8,
+    pub share_mint_bump: u8,
+}
+
+impl Strategy {
+    pub fn has_position(&self) -> bool {
+        self.position_nft_mint != Pubkey::default()
+    }
+
+    pub fn base_is_token_0(&self) -> bool {
+        self.base_mint < self.pair_mint
+    }
+
+    pub fn signer_seeds(&self) -> [&[u8]; 4] {
+        [
+            STRATEGY_SEED,
+            self.pool_state.as_ref(),
+            self.base_mint.as_ref(),
+            std::slice::from_ref(&self.bump),
+        ]
+    }
+}
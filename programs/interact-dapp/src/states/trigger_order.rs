@@ -0,0 +1,67 @@
+use anchor_lang::prelude::*;
+use crate::constant::TRIGGER_ORDER_SEED;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub enum TriggerKind {
+    /// Exit once the underlying price falls to the trigger price
+    StopLoss,
+    /// Exit once the underlying price rises to the trigger price
+    TakeProfit,
+}
+
+/// fTokens escrowed by `owner`, redeemed and swapped into `output_mint` once the pool TWAP
+/// of the underlying crosses `trigger_tick`.
+#[account]
+#[derive(InitSpace)]
+pub struct TriggerOrder {
+    pub owner: Pubkey,
+    pub order_id: u64,
+
+    /// Underlying token of the fTokens
+    pub mint: Pubkey,
+    pub f_token_mint: Pubkey,
+    pub lending: Pubkey,
+    pub output_mint: Pubkey,
+    pub pool_state: Pubkey,
+
+    pub kind: TriggerKind,
+    /// Pool tick the TWAP is compared against
+    pub trigger_tick: i32,
+    /// Window of the pool TWAP, in seconds
+    pub twap_window: u32,
+    /// Maximum slippage of the exit swap against the TWAP price
+    pub max_slippage_bps: u16,
+
+    pub executed: bool,
+    pub bump: u8,
+}
+
+impl TriggerOrder {
+    /// Whether the underlying price at `twap_tick` has crossed the trigger. Pool ticks
+    /// price token_0 in token_1, so they move against the price of a token_1 underlying.
+    pub fn is_triggered(&self, twap_tick: i32, underlying_is_token_0: bool) -> bool {
+        let price_rose = if underlying_is_token_0 {
+            twap_tick >= self.trigger_tick
+        } else {
+            twap_tick <= self.trigger_tick
+        };
+        let price_fell = if underlying_is_token_0 {
+            twap_tick <= self.trigger_tick
+        } else {
+            twap_tick >= self.trigger_tick
+        };
+        match self.kind {
+            TriggerKind::StopLoss => price_fell,
+            TriggerKind::TakeProfit => price_rose,
+        }
+    }
+
+    pub fn signer_seeds<'a>(&'a self, order_id: &'a [u8; 8]) -> [&'a [u8]; 4] {
+        [
+            TRIGGER_ORDER_SEED,
+            self.owner.as_ref(),
+            order_id,
+            std::slice::from_ref(&self.bump),
+        ]
+    }
+}
@@ -0,0 +1,62 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{self, CloseAccount, Mint, TokenAccount, TransferChecked};
+use crate::error::InteractDappError;
+
+/// Amount held by an SPL Token / Token-2022 account, 0 if it is not created yet.
+pub fn get_token_balance(info: &AccountInfo) -> Result<u64> {
+    let data = info.try_borrow_data()?;
+    if data.is_empty() {
+        return Ok(0);
+    }
+    data.get(64..72)
+        .and_then(|bytes| bytes.try_into().ok())
+        .map(u64::from_le_bytes)
+        .ok_or(InteractDappError::InvalidAccountData.into())
+}
+
+/// Supply of an SPL Token / Token-2022 mint.
+pub fn get_mint_supply(info: &AccountInfo) -> Result<u64> {
+    let data = info.try_borrow_data()?;
+    data.get(36..44)
+        .and_then(|bytes| bytes.try_into().ok())
+        .map(u64::from_le_bytes)
+        .ok_or(InteractDappError::InvalidAccountData.into())
+}
+
+/// Sends the whole balance of a program-owned token account to `destination` and closes
+/// it, returning the rent to `rent_receiver`.
+pub fn drain_token_account<'info>(
+    token_program: AccountInfo<'info>,
+    vault: &InterfaceAccount<'info, TokenAccount>,
+    mint: &InterfaceAccount<'info, Mint>,
+    destination: AccountInfo<'info>,
+    authority: AccountInfo<'info>,
+    rent_receiver: AccountInfo<'info>,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    if vault.amount > 0 {
+        token_interface::transfer_checked(
+            CpiContext::new_with_signer(
+                token_program.clone(),
+                TransferChecked {
+                    from: vault.to_account_info(),
+                    mint: mint.to_account_info(),
+                    to: destination,
+                    authority: authority.clone(),
+                },
+                signer_seeds,
+            ),
+            vault.amount,
+            mint.decimals,
+        )?;
+    }
+    token_interface::close_account(CpiContext::new_with_signer(
+        token_program,
+        CloseAccount {
+            account: vault.to_account_info(),
+            destination: rent_receiver,
+            authority,
+        },
+        signer_seeds,
+    ))
+}
@@ -0,0 +1,347 @@
+//! Stand-in for the Jupiter lending and liquidity programs in local tests. It keeps the
+//! `deposit`, `withdraw` and `redeem` discriminators, account lists and the `Lending`
+//! layout of idls/jupiter_lend.json, and plays the liquidity program as well: the vault is
+//! the ATA of its own `liquidity` PDA. Exchange prices only move through
+//! `set_token_exchange_price`. It also answers the Jupiter oracle
+//! `get_exchange_rate_operate` call with the rate stored in its `Oracle` account.
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_interface::{
+    self, Burn, Mint, MintTo, TokenAccount, TokenInterface, TransferChecked,
+};
+
+declare_id!("8dyGaYwQkXrN2gRm5asDTdpxfUuUoFw5BFcozYZ3o4H");
+
+pub const LENDING_ADMIN_SEED: &[u8] = b"lending_admin";
+pub const F_TOKEN_MINT_SEED: &[u8] = b"f_token_mint";
+pub const LENDING_SEED: &[u8] = b"lending";
+pub const LENDING_REWARDS_RATE_MODEL_SEED: &[u8] = b"lending_rewards_rate_model";
+pub const LIQUIDITY_SEED: &[u8] = b"liquidity";
+pub const TOKEN_RESERVE_SEED: &[u8] = b"reserve";
+pub const USER_SUPPLY_POSITION_SEED: &[u8] = b"user_supply_position";
+
+pub const EXCHANGE_PRICES_PRECISION: u64 = 1_000_000_000_000;
+
+#[program]
+pub mod mock_lending {
+    use super::*;
+
+    pub fn init_lending(ctx: Context<InitLending>) -> Result<()> {
+        let accounts = ctx.accounts;
+        let mint = accounts.mint.key();
+        let lending = accounts.lending.key();
+        accounts.lending.set_inner(Lending {
+            mint,
+            f_token_mint: accounts.f_token_mint.key(),
+            lending_id: 0,
+            decimals: accounts.mint.decimals,
+            rewards_rate_model: Pubkey::find_program_address(
+                &[LENDING_REWARDS_RATE_MODEL_SEED, mint.as_ref()],
+                &ID,
+            )
+            .0,
+            liquidity_exchange_price: EXCHANGE_PRICES_PRECISION,
+            token_exchange_price: EXCHANGE_PRICES_PRECISION,
+            last_update_timestamp: Clock::get()?.unix_timestamp as u64,
+            token_reserves_liquidity: Pubkey::find_program_address(
+                &[TOKEN_RESERVE_SEED, mint.as_ref()],
+                &ID,
+            )
+            .0,
+            supply_position_on_liquidity: Pubkey::find_program_address(
+                &[USER_SUPPLY_POSITION_SEED, mint.as_ref(), lending.as_ref()],
+                &ID,
+            )
+            .0,
+            bump: ctx.bumps.lending,
+        });
+        Ok(())
+    }
+
+    /// Simulates accrued yield, or a loss, on the market.
+    pub fn set_token_exchange_price(
+        ctx: Context<SetTokenExchangePrice>,
+        token_exchange_price: u64,
+    ) -> Result<()> {
+        let lending = &mut ctx.accounts.lending;
+        lending.token_exchange_price = token_exchange_price;
+        lending.last_update_timestamp = Clock::get()?.unix_timestamp as u64;
+        Ok(())
+    }
+
+    pub fn deposit(ctx: Context<Deposit>, assets: u64) -> Result<u64> {
+        let accounts = ctx.accounts;
+        let shares = to_shares(assets, accounts.lending.token_exchange_price, false)?;
+        token_interface::transfer_checked(
+            CpiContext::new(
+                accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: accounts.depositor_token_account.to_account_info(),
+                    mint: accounts.mint.to_account_info(),
+                    to: accounts.vault.to_account_info(),
+                    authority: accounts.signer.to_account_info(),
+                },
+            ),
+            assets,
+            accounts.mint.decimals,
+        )?;
+        token_interface::mint_to(
+            CpiContext::new_with_signer(
+                accounts.token_program.to_account_info(),
+                MintTo {
+                    mint: accounts.f_token_mint.to_account_info(),
+                    to: accounts.recipient_token_account.to_account_info(),
+                    authority: accounts.lending_admin.to_account_info(),
+                },
+                &[&[LENDING_ADMIN_SEED, &[ctx.bumps.lending_admin]]],
+            ),
+            shares,
+        )?;
+        Ok(shares)
+    }
+
+    pub fn withdraw(ctx: Context<Withdraw>, amount: u64) -> Result<u64> {
+        let shares = to_shares(amount, ctx.accounts.lending.token_exchange_price, true)?;
+        ctx.accounts
+            .burn_and_transfer(shares, amount, ctx.bumps.liquidity)?;
+        Ok(shares)
+    }
+
+    pub fn redeem(ctx: Context<Withdraw>, shares: u64) -> Result<u64> {
+        let assets = to_assets(shares, ctx.accounts.lending.token_exchange_price)?;
+        ctx.accounts
+            .burn_and_transfer(shares, assets, ctx.bumps.liquidity)?;
+        Ok(assets)
+    }
+
+    pub fn get_exchange_rate_operate(ctx: Context<GetExchangeRate>, _nonce: u16) -> Result<u128> {
+        Ok(ctx.accounts.oracle.price)
+    }
+}
+
+/// Same layout and discriminator as the Jupiter `Lending` account.
+#[account]
+pub struct Lending {
+    pub mint: Pubkey,
+    pub f_token_mint: Pubkey,
+    pub lending_id: u16,
+    pub decimals: u8,
+    pub rewards_rate_model: Pubkey,
+    pub liquidity_exchange_price: u64,
+    pub token_exchange_price: u64,
+    pub last_update_timestamp: u64,
+    pub token_reserves_liquidity: Pubkey,
+    pub supply_position_on_liquidity: Pubkey,
+    pub bump: u8,
+}
+
+impl Lending {
+    pub const LEN: usize = 8 + 32 + 32 + 2 + 1 + 32 + 8 + 8 + 8 + 32 + 32 + 1;
+}
+
+/// Same discriminator and leading `nonce` as the Jupiter `Oracle` account, followed by the
+/// collateral/debt rate `get_exchange_rate_operate` returns.
+#[account]
+pub struct Oracle {
+    pub nonce: u16,
+    pub price: u128,
+}
+
+#[error_code]
+pub enum MockLendingError {
+    #[msg("Math overflow.")]
+    MathOverflow,
+    #[msg("Amount is too small.")]
+    ZeroAmount,
+}
+
+fn to_shares(assets: u64, token_exchange_price: u64, round_up: bool) -> Result<u64> {
+    let numerator = assets as u128 * EXCHANGE_PRICES_PRECISION as u128;
+    let shares = if round_up {
+        numerator.div_ceil(token_exchange_price as u128)
+    } else {
+        numerator / token_exchange_price as u128
+    };
+    require!(shares > 0, MockLendingError::ZeroAmount);
+    u64::try_from(shares).map_err(|_| MockLendingError::MathOverflow.into())
+}
+
+fn to_assets(shares: u64, token_exchange_price: u64) -> Result<u64> {
+    let assets = shares as u128 * token_exchange_price as u128 / EXCHANGE_PRICES_PRECISION as u128;
+    require!(assets > 0, MockLendingError::ZeroAmount);
+    u64::try_from(assets).map_err(|_| MockLendingError::MathOverflow.into())
+}
+
+#[derive(Accounts)]
+pub struct InitLending<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+
+    ///CHECK: fToken mint authority
+    #[account(seeds = [LENDING_ADMIN_SEED], bump)]
+    pub lending_admin: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        payer = signer,
+        seeds = [F_TOKEN_MINT_SEED, mint.key().as_ref()],
+        bump,
+        mint::decimals = mint.decimals,
+        mint::authority = lending_admin,
+        mint::token_program = token_program,
+    )]
+    pub f_token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        init,
+        payer = signer,
+        space = Lending::LEN,
+        seeds = [LENDING_SEED, mint.key().as_ref(), f_token_mint.key().as_ref()],
+        bump,
+    )]
+    pub lending: Box<Account<'info, Lending>>,
+
+    ///CHECK: vault authority
+    #[account(seeds = [LIQUIDITY_SEED], bump)]
+    pub liquidity: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        payer = signer,
+        associated_token::mint = mint,
+        associated_token::authority = liquidity,
+        associated_token::token_program = token_program,
+    )]
+    pub vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetTokenExchangePrice<'info> {
+    pub signer: Signer<'info>,
+    #[account(mut)]
+    pub lending: Box<Account<'info, Lending>>,
+}
+
+#[derive(Accounts)]
+pub struct Deposit<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    #[account(mut, token::mint = mint)]
+    pub depositor_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(mut, token::mint = f_token_mint)]
+    pub recipient_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+    ///CHECK: fToken mint authority
+    #[account(seeds = [LENDING_ADMIN_SEED], bump)]
+    pub lending_admin: UncheckedAccount<'info>,
+    #[account(mut, has_one = mint, has_one = f_token_mint)]
+    pub lending: Box<Account<'info, Lending>>,
+    #[account(mut)]
+    pub f_token_mint: Box<InterfaceAccount<'info, Mint>>,
+    ///CHECK: unused by the mock
+    #[account(mut)]
+    pub supply_token_reserves_liquidity: UncheckedAccount<'info>,
+    ///CHECK: unused by the mock
+    #[account(mut)]
+    pub lending_supply_position_on_liquidity: UncheckedAccount<'info>,
+    ///CHECK: unused by the mock
+    pub rate_model: UncheckedAccount<'info>,
+    #[account(mut, token::mint = mint, token::authority = liquidity)]
+    pub vault: Box<InterfaceAccount<'info, TokenAccount>>,
+    ///CHECK: vault authority
+    #[account(mut, seeds = [LIQUIDITY_SEED], bump)]
+    pub liquidity: UncheckedAccount<'info>,
+    ///CHECK: unused by the mock
+    #[account(mut)]
+    pub liquidity_program: UncheckedAccount<'info>,
+    ///CHECK: unused by the mock
+    pub rewards_rate_model: UncheckedAccount<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Oracle sources, passed as remaining accounts, are ignored.
+#[derive(Accounts)]
+pub struct GetExchangeRate<'info> {
+    pub oracle: Box<Account<'info, Oracle>>,
+}
+
+/// Accounts of `withdraw` and `redeem`, which share the Jupiter account list.
+#[derive(Accounts)]
+pub struct Withdraw<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    #[account(mut, token::mint = f_token_mint)]
+    pub owner_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(mut, token::mint = mint)]
+    pub recipient_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    ///CHECK: fToken mint authority
+    #[account(seeds = [LENDING_ADMIN_SEED], bump)]
+    pub lending_admin: UncheckedAccount<'info>,
+    #[account(mut, has_one = mint, has_one = f_token_mint)]
+    pub lending: Box<Account<'info, Lending>>,
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(mut)]
+    pub f_token_mint: Box<InterfaceAccount<'info, Mint>>,
+    ///CHECK: unused by the mock
+    #[account(mut)]
+    pub supply_token_reserves_liquidity: UncheckedAccount<'info>,
+    ///CHECK: unused by the mock
+    #[account(mut)]
+    pub lending_supply_position_on_liquidity: UncheckedAccount<'info>,
+    ///CHECK: unused by the mock
+    pub rate_model: UncheckedAccount<'info>,
+    #[account(mut, token::mint = mint, token::authority = liquidity)]
+    pub vault: Box<InterfaceAccount<'info, TokenAccount>>,
+    ///CHECK: the mock never queues withdrawals
+    #[account(mut)]
+    pub claim_account: UncheckedAccount<'info>,
+    ///CHECK: vault authority
+    #[account(mut, seeds = [LIQUIDITY_SEED], bump)]
+    pub liquidity: UncheckedAccount<'info>,
+    ///CHECK: unused by the mock
+    #[account(mut)]
+    pub liquidity_program: UncheckedAccount<'info>,
+    ///CHECK: unused by the mock
+    pub rewards_rate_model: UncheckedAccount<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> Withdraw<'info> {
+    fn burn_and_transfer(&self, shares: u64, assets: u64, liquidity_bump: u8) -> Result<()> {
+        token_interface::burn(
+            CpiContext::new(
+                self.token_program.to_account_info(),
+                Burn {
+                    mint: self.f_token_mint.to_account_info(),
+                    from: self.owner_token_account.to_account_info(),
+                    authority: self.signer.to_account_info(),
+                },
+            ),
+            shares,
+        )?;
+        token_interface::transfer_checked(
+            CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                TransferChecked {
+                    from: self.vault.to_account_info(),
+                    mint: self.mint.to_account_info(),
+                    to: self.recipient_token_account.to_account_info(),
+                    authority: self.liquidity.to_account_info(),
+                },
+                &[&[LIQUIDITY_SEED, &[liquidity_bump]]],
+            ),
+            assets,
+            self.mint.decimals,
+        )
+    }
+}